@@ -36,6 +36,7 @@ fn main() {
         },
         enable_event_logging: false, // Enable to see consensus events
         csv_output_path: Some("sim_events.csv".to_string()), // Set to Some("events.csv") to export all events
+        json_output_path: None, // Set to Some("result.json") to export the result
     };
 
     info!("Starting simulation...");
@@ -24,6 +24,9 @@ pub struct CommitChainSimConfig {
 
     /// Network simulation configuration
     pub network: NetworkConfig,
+
+    /// Write the result as JSON to this path (None = don't export)
+    pub json_output_path: Option<String>,
 }
 
 impl Default for CommitChainSimConfig {
@@ -35,6 +38,7 @@ impl Default for CommitChainSimConfig {
             commit_chain: CommitChainConfig::default(),
             block_injection: BlockInjectionConfig::default(),
             network: NetworkConfig::default(),
+            json_output_path: None,
         }
     }
 }
@@ -93,8 +93,12 @@ impl CommitChainRunner {
             let peer_range = self.calculate_peer_range(peer_id);
 
             // Create backend with commit chain
-            // Note: Using default config for now, as there's no constructor that takes custom config
-            let backend = Rc::new(RefCell::new(MemoryBackend::new_with_peer_id(peer_id)));
+            // Seeded ticket secret keeps commit chain tickets reproducible
+            let backend = Rc::new(RefCell::new(MemoryBackend::with_ticket_secret(
+                peer_id,
+                self.config.commit_chain.clone(),
+                self.rng.gen(),
+            )));
 
             self.backends.insert(peer_id, backend);
         }
@@ -158,7 +162,16 @@ impl CommitChainRunner {
 
         println!("\nSimulation complete!");
 
-        self.build_result()
+        let result = self.build_result();
+        if let Some(ref json_path) = self.config.json_output_path {
+            if let Err(e) = result.write_json(json_path) {
+                eprintln!(
+                    "Warning: Could not write result JSON '{}': {}",
+                    json_path, e
+                );
+            }
+        }
+        result
     }
 
     /// Inject blocks randomly into peer backends
@@ -1,10 +1,14 @@
 //! Statistics and results for commit chain simulator
 
 use ec_rust::ec_interface::{EcTime, PeerId};
+use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::Path;
 
 /// Simulation result
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct SimResult {
     /// Seed used for the simulation
     pub seed_used: [u8; 32],
@@ -23,6 +27,13 @@ pub struct SimResult {
 }
 
 impl SimResult {
+    /// Write the result as pretty-printed JSON (for comparing runs offline)
+    pub fn write_json(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let file = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(file, self)?;
+        Ok(())
+    }
+
     /// Print a summary of the simulation results
     pub fn print_summary(&self) {
         println!("\n╔════════════════════════════════════════════════════════╗");
@@ -80,7 +91,7 @@ impl SimResult {
 }
 
 /// Commit statistics
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct CommitStats {
     /// Total number of commits across all peers
     pub total_commits: usize,
@@ -90,7 +101,7 @@ pub struct CommitStats {
 }
 
 /// Synchronization statistics
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct SyncStats {
     /// Final watermark per peer
     pub final_watermarks: BTreeMap<PeerId, EcTime>,
@@ -103,7 +114,7 @@ pub struct SyncStats {
 }
 
 /// Message statistics
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct MessageStats {
     /// Total message count
     pub total_messages: usize,
@@ -122,7 +133,7 @@ pub struct MessageStats {
 }
 
 /// Message counters (internal tracking)
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct MessageCounts {
     pub query_commit_block: usize,
     pub commit_block: usize,
@@ -39,6 +39,8 @@ fn main() {
             delay_fraction: 0.3,
             loss_fraction: 0.01,
         },
+
+        json_output_path: None, // Set to Some("result.json") to export the result
     };
 
     info!("Configuration:");
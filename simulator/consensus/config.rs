@@ -11,6 +11,7 @@ pub struct SimConfig {
     pub transactions: TransactionConfig,
     pub enable_event_logging: bool,
     pub csv_output_path: Option<String>,
+    pub json_output_path: Option<String>,
 }
 
 /// Network behavior configuration
@@ -59,6 +60,7 @@ impl Default for SimConfig {
             transactions: TransactionConfig::default(),
             enable_event_logging: false,
             csv_output_path: None,
+            json_output_path: None,
         }
     }
 }
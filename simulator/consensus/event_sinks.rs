@@ -3,6 +3,7 @@
 use std::fs::File;
 use std::io::{BufWriter, Write};
 use std::path::Path;
+use std::sync::{Arc, Mutex};
 
 use ec_rust::{EcTime, Event, EventSink, PeerId};
 
@@ -137,6 +138,82 @@ impl EventSink for ConsoleEventSink {
                     sender & 0xFFFF,
                 );
             }
+            Event::PeerConnected { peer_id } => {
+                println!(
+                    "{:>5} {:>6} PeerConnected    peer:{:x}",
+                    round,
+                    peer_fmt,
+                    peer_id & 0xFFFF,
+                );
+            }
+            Event::PeerDemoted { peer_id } => {
+                println!(
+                    "{:>5} {:>6} PeerDemoted      peer:{:x}",
+                    round,
+                    peer_fmt,
+                    peer_id & 0xFFFF,
+                );
+            }
+            Event::ElectionStarted { token } => {
+                println!(
+                    "{:>5} {:>6} ElectionStarted  token:{:x}",
+                    round,
+                    peer_fmt,
+                    token & 0xFFFF,
+                );
+            }
+            Event::ElectionCompleted { token, winner } => {
+                println!(
+                    "{:>5} {:>6} ElectionDone     token:{:x} winner:{:x}",
+                    round,
+                    peer_fmt,
+                    token & 0xFFFF,
+                    winner & 0xFFFF,
+                );
+            }
+            Event::SplitBrainDetected { token } => {
+                println!(
+                    "{:>5} {:>6} SplitBrain       token:{:x}",
+                    round,
+                    peer_fmt,
+                    token & 0xFFFF,
+                );
+            }
+            Event::SyncApplied {
+                peer: source_peer,
+                commit_block,
+                tokens,
+            } => {
+                println!(
+                    "{:>5} {:>6} SyncApplied      peer:{:x} commit:{:x} tokens:{}",
+                    round,
+                    peer_fmt,
+                    source_peer & 0xFFFF,
+                    commit_block & 0xFFFF,
+                    tokens,
+                );
+            }
+            Event::SyncRejected {
+                token,
+                peer: source_peer,
+            } => {
+                println!(
+                    "{:>5} {:>6} SyncRejected     token:{:x} peer:{:x}",
+                    round,
+                    peer_fmt,
+                    token & 0xFFFF,
+                    source_peer & 0xFFFF,
+                );
+            }
+            Event::PendingExpired { token, block } => {
+                println!(
+                    "{:>5} {:>6} PendingExpired   token:{:x} block:{:x}",
+                    round,
+                    peer_fmt,
+                    token & 0xFFFF,
+                    block & 0xFFFF,
+                );
+            }
         }
     }
 }
@@ -241,6 +318,53 @@ impl EventSink for CsvEventSink {
                 "{},{},IdentityBlockReceived,0,{},{},{},peer_id",
                 round, peer, sender, peer_id, 0
             ),
+            Event::PeerConnected { peer_id } => writeln!(
+                self.writer,
+                "{},{},PeerConnected,0,{},0,0,",
+                round, peer, peer_id
+            ),
+            Event::PeerDemoted { peer_id } => writeln!(
+                self.writer,
+                "{},{},PeerDemoted,0,{},0,0,",
+                round, peer, peer_id
+            ),
+            Event::ElectionStarted { token } => writeln!(
+                self.writer,
+                "{},{},ElectionStarted,0,0,{},0,token",
+                round, peer, token
+            ),
+            Event::ElectionCompleted { token, winner } => writeln!(
+                self.writer,
+                "{},{},ElectionCompleted,0,{},{},0,token",
+                round, peer, winner, token
+            ),
+            Event::SplitBrainDetected { token } => writeln!(
+                self.writer,
+                "{},{},SplitBrainDetected,0,0,{},0,token",
+                round, peer, token
+            ),
+            Event::SyncApplied {
+                peer: source_peer,
+                commit_block,
+                tokens,
+            } => writeln!(
+                self.writer,
+                "{},{},SyncApplied,0,{},{},{},commit_block:tokens",
+                round, peer, source_peer, commit_block, tokens
+            ),
+            Event::SyncRejected {
+                token,
+                peer: source_peer,
+            } => writeln!(
+                self.writer,
+                "{},{},SyncRejected,0,{},{},0,token",
+                round, peer, source_peer, token
+            ),
+            Event::PendingExpired { token, block } => writeln!(
+                self.writer,
+                "{},{},PendingExpired,{},0,{},0,token",
+                round, peer, block, token
+            ),
         };
 
         if let Err(e) = result {
@@ -255,6 +379,25 @@ impl Drop for CsvEventSink {
     }
 }
 
+/// Wrapper to share a single CsvEventSink across multiple nodes
+pub struct SharedCsvSink {
+    sink: Arc<Mutex<CsvEventSink>>,
+}
+
+impl SharedCsvSink {
+    pub fn new(sink: Arc<Mutex<CsvEventSink>>) -> Self {
+        Self { sink }
+    }
+}
+
+impl EventSink for SharedCsvSink {
+    fn log(&mut self, round: EcTime, peer: PeerId, event: Event) {
+        if let Ok(mut sink) = self.sink.lock() {
+            sink.log(round, peer, event);
+        }
+    }
+}
+
 // ============================================================================
 // Collector Event Sink (In-Memory)
 // ============================================================================
@@ -311,6 +454,14 @@ impl CollectorEventSink {
                 Event::BlockStateChange { .. } => counts.state_change += 1,
                 Event::VoteReceived { .. } => counts.vote_received += 1,
                 Event::IdentityBlockReceived { .. } => counts.identity_block_received += 1,
+                Event::PeerConnected { .. } => counts.peer_connected += 1,
+                Event::PeerDemoted { .. } => counts.peer_demoted += 1,
+                Event::ElectionStarted { .. } => counts.election_started += 1,
+                Event::ElectionCompleted { .. } => counts.election_completed += 1,
+                Event::SplitBrainDetected { .. } => counts.split_brain += 1,
+                Event::SyncApplied { .. } => counts.sync_applied += 1,
+                Event::SyncRejected { .. } => counts.sync_rejected += 1,
+                Event::PendingExpired { .. } => counts.pending_expired += 1,
             }
         }
         counts
@@ -335,6 +486,14 @@ pub struct EventTypeCounts {
     pub state_change: usize,
     pub vote_received: usize,
     pub identity_block_received: usize,
+    pub peer_connected: usize,
+    pub peer_demoted: usize,
+    pub election_started: usize,
+    pub election_completed: usize,
+    pub split_brain: usize,
+    pub sync_applied: usize,
+    pub sync_rejected: usize,
+    pub pending_expired: usize,
 }
 
 impl EventSink for CollectorEventSink {
@@ -7,7 +7,7 @@
 use std::collections::HashMap;
 
 use ec_rust::ec_interface::{BlockId, BlockTime, EcTime, TokenId};
-use ec_rust::ec_proof_of_storage::{SignatureSearchResult, TokenStorageBackend};
+use ec_rust::ec_proof_of_storage::{SignatureParams, SignatureSearchResult, TokenStorageBackend};
 
 /// Simple HashMap-based token storage
 ///
@@ -53,16 +53,35 @@ impl TokenStorageBackend for HashMapTokens {
             .or_insert_with(|| BlockTime::new(*block, *parent, time));
     }
 
+    fn remove(&mut self, token: &TokenId) -> Option<BlockTime> {
+        self.tokens.remove(token)
+    }
+
     fn search_signature(
         &self,
         _lookup_token: &TokenId,
-        _signature_chunks: &[u16; ec_rust::ec_proof_of_storage::SIGNATURE_CHUNKS],
+        _signature_chunks: &[u16],
+        _params: &SignatureParams,
+        _forward_chunks: usize,
+        _max_steps: usize,
     ) -> SignatureSearchResult {
         panic!(
             "HashMapTokens does not support signature search - use MemTokens or similar instead"
         );
     }
 
+    fn range_after(&self, token: &TokenId) -> Box<dyn Iterator<Item = (TokenId, BlockTime)> + '_> {
+        // Unordered map: sort the matching entries (O(n log n), fine for the simulator)
+        let mut above: Vec<_> = self
+            .tokens
+            .iter()
+            .filter(|(t, _)| *t > token)
+            .map(|(t, m)| (*t, *m))
+            .collect();
+        above.sort_unstable_by_key(|(t, _)| *t);
+        Box::new(above.into_iter())
+    }
+
     fn len(&self) -> usize {
         self.tokens.len()
     }
@@ -22,7 +22,7 @@ pub use config::{NetworkConfig, SimConfig, TopologyConfig, TopologyMode, Transac
 #[allow(unused_imports)]
 pub use event_sinks::{
     CollectorEventSink, ConsoleEventSink, CsvEventSink, EventRecord, EventTypeCounts,
-    MultiEventSink,
+    MultiEventSink, SharedCsvSink,
 };
 #[allow(unused_imports)]
 pub use runner::SimRunner;
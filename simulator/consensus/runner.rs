@@ -9,6 +9,7 @@ use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
 use rand::{Rng, RngCore, SeedableRng};
 
+use ec_rust::ec_commit_chain::CommitChainConfig;
 use ec_rust::ec_interface::{
     BatchRequestItem, Block, BlockId, Message, MessageEnvelope, PeerId, PublicKeyReference,
     TokenBlock, TokenId, TOKENS_PER_BLOCK,
@@ -19,7 +20,7 @@ use ec_rust::ec_node::EcNode;
 use super::hashmap_tokens::HashMapTokens;
 
 use super::config::{SimConfig, TopologyConfig, TopologyMode};
-use super::event_sinks::{ConsoleEventSink, CsvEventSink, MultiEventSink};
+use super::event_sinks::{ConsoleEventSink, CsvEventSink, MultiEventSink, SharedCsvSink};
 use super::stats::{MessageCounts, PeerStats, SimResult, SimStatistics};
 
 use std::sync::{Arc, Mutex};
@@ -78,7 +79,19 @@ impl SimRunner {
         // Create nodes with topology
         let mut nodes: BTreeMap<PeerId, EcNode<MemoryBackend, HashMapTokens>> = BTreeMap::new();
         for peer_id in &peers {
-            let backend = Rc::new(RefCell::new(MemoryBackend::new_with_peer_id(*peer_id)));
+            // Create deterministic RNG for this node based on peer_id and seed
+            let mut node_seed = [0u8; 32];
+            // Use peer_id as part of the seed to ensure different nodes have different RNGs
+            node_seed[0..8].copy_from_slice(&peer_id.to_le_bytes());
+            node_seed[8..].copy_from_slice(&seed[8..]);
+            let mut node_rng = StdRng::from_seed(node_seed);
+
+            // Commit chain tickets come from the node's RNG too
+            let backend = Rc::new(RefCell::new(MemoryBackend::with_ticket_secret(
+                *peer_id,
+                CommitChainConfig::default(),
+                node_rng.gen(),
+            )));
             let token_storage = HashMapTokens::new();
 
             // Create event sink based on configuration
@@ -91,9 +104,7 @@ impl SimRunner {
                     }
 
                     if let Some(ref csv_sink_arc) = shared_csv_sink {
-                        multi.add_sink(Box::new(SharedCsvSink {
-                            sink: csv_sink_arc.clone(),
-                        }));
+                        multi.add_sink(Box::new(SharedCsvSink::new(csv_sink_arc.clone())));
                     }
 
                     Box::new(multi)
@@ -102,13 +113,6 @@ impl SimRunner {
                     Box::new(ConsoleEventSink::new(false))
                 };
 
-            // Create deterministic RNG for this node based on peer_id and seed
-            let mut node_seed = [0u8; 32];
-            // Use peer_id as part of the seed to ensure different nodes have different RNGs
-            node_seed[0..8].copy_from_slice(&peer_id.to_le_bytes());
-            node_seed[8..].copy_from_slice(&seed[8..]);
-            let node_rng = StdRng::from_seed(node_seed);
-
             let mut node =
                 EcNode::new_with_sink(backend, *peer_id, 0, token_storage, event_sink, node_rng);
 
@@ -207,7 +211,16 @@ impl SimRunner {
             self.step(i);
         }
 
-        self.build_result()
+        let result = self.build_result();
+        if let Some(ref json_path) = self.config.json_output_path {
+            if let Err(e) = result.write_json(json_path) {
+                eprintln!(
+                    "Warning: Could not write result JSON '{}': {}",
+                    json_path, e
+                );
+            }
+        }
+        result
     }
 
     fn step(&mut self, round: usize) {
@@ -310,8 +323,13 @@ impl SimRunner {
                     Message::Block { .. } => self.message_counters.2 += 1,
                     Message::Answer { .. } => self.message_counters.3 += 1,
                     Message::Referral { .. } => (),
+                    Message::PeerList { .. } => (),
                     Message::QueryCommitBlock { .. } => (),
                     Message::CommitBlock { .. } => (),
+                    Message::QueryBlocks { .. } => (),
+                    Message::Blocks { .. } => (),
+                    Message::Keepalive { .. } => (),
+                    Message::CommitHead { .. } => (),
                 };
                 node.handle_message(m, &mut next);
             }
@@ -376,16 +394,3 @@ impl SimRunner {
         }
     }
 }
-
-/// Wrapper to share a single CsvEventSink across multiple nodes
-struct SharedCsvSink {
-    sink: Arc<Mutex<CsvEventSink>>,
-}
-
-impl ec_rust::EventSink for SharedCsvSink {
-    fn log(&mut self, round: ec_rust::EcTime, peer: ec_rust::PeerId, event: ec_rust::Event) {
-        if let Ok(mut sink) = self.sink.lock() {
-            sink.log(round, peer, event);
-        }
-    }
-}
@@ -1,7 +1,12 @@
 // Simulation Statistics and Results
 
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::Path;
+
 /// Complete simulation result
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimResult {
     pub statistics: SimStatistics,
     pub committed_blocks: usize,
@@ -9,8 +14,17 @@ pub struct SimResult {
     pub seed_used: [u8; 32],
 }
 
+impl SimResult {
+    /// Write the result as pretty-printed JSON (for comparing runs offline)
+    pub fn write_json(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let file = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(file, self)?;
+        Ok(())
+    }
+}
+
 /// Aggregated simulation statistics
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimStatistics {
     pub message_counts: MessageCounts,
     pub peer_stats: PeerStats,
@@ -19,7 +33,7 @@ pub struct SimStatistics {
 }
 
 /// Breakdown of message types
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MessageCounts {
     pub query: usize,
     pub vote: usize,
@@ -28,7 +42,7 @@ pub struct MessageCounts {
 }
 
 /// Peer connectivity statistics
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PeerStats {
     pub max_peers: usize,
     pub min_peers: usize,
@@ -18,6 +18,8 @@ pub struct IntegratedSimConfig {
     pub events: EventSchedule,
     pub network: NetworkConfig,
     pub transactions: TransactionFlowConfig,
+    /// Write the result as JSON to this path (None = don't export)
+    pub json_output_path: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -153,6 +155,7 @@ impl Default for IntegratedSimConfig {
             events: EventSchedule::default(),
             network: NetworkConfig::default(),
             transactions: TransactionFlowConfig::default(),
+            json_output_path: None,
         }
     }
 }
@@ -7,6 +7,7 @@ use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
 use rand::{Rng, RngCore, SeedableRng};
 
+use ec_rust::ec_commit_chain::CommitChainConfig;
 use ec_rust::ec_interface::{
     BatchRequestItem, Block, BlockId, EcBlocks, Message, MessageEnvelope, PeerId,
     PublicKeyReference, TokenBlock, TokenId, GENESIS_BLOCK_ID, TOKENS_PER_BLOCK,
@@ -402,7 +403,16 @@ impl IntegratedRunner {
             self.record_round_metrics(commits_this_round);
         }
 
-        self.build_result()
+        let result = self.build_result();
+        if let Some(ref json_path) = self.config.json_output_path {
+            if let Err(e) = result.write_json(json_path) {
+                eprintln!(
+                    "Warning: Could not write result JSON '{}': {}",
+                    json_path, e
+                );
+            }
+        }
+        result
     }
 
     fn initialize_network(&mut self) {
@@ -623,7 +633,13 @@ impl IntegratedRunner {
         coverage_fraction: f64,
         initialize_backend_from_genesis: bool,
     ) -> SimPeer {
-        let mut backend = MemoryBackend::new_with_peer_id(peer_id);
+        // Ticket secret from the run seed, so commit chain tickets are reproducible
+        let mut secret_seed = [0u8; 32];
+        secret_seed[0..8].copy_from_slice(&peer_id.to_le_bytes());
+        secret_seed[8..].copy_from_slice(&self.seed_used[8..]);
+        let ticket_secret = StdRng::from_seed(secret_seed).gen();
+        let mut backend =
+            MemoryBackend::with_ticket_secret(peer_id, CommitChainConfig::default(), ticket_secret);
         if initialize_backend_from_genesis && matches!(&self.token_space, TokenSpace::Genesis(_)) {
             self.populate_genesis_backend(&mut backend, peer_id);
         }
@@ -1337,6 +1353,8 @@ impl IntegratedRunner {
             Message::Vote { block_id, .. } => vec![*block_id],
             Message::QueryBlock { block_id, .. } => vec![*block_id],
             Message::Block { block } => vec![block.id],
+            Message::QueryBlocks { block_ids, .. } => block_ids.clone(),
+            Message::Blocks { blocks } => blocks.iter().map(|block| block.id).collect(),
             Message::RequestBatch { items } => items
                 .iter()
                 .filter_map(|item| match item {
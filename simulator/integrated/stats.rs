@@ -1,6 +1,10 @@
 use ec_rust::ec_interface::{BatchRequestItem, Message};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::Path;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DistributionSummary {
     pub samples: usize,
     pub min: usize,
@@ -33,7 +37,7 @@ impl DistributionSummary {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FloatDistributionSummary {
     pub samples: usize,
     pub min: f64,
@@ -66,7 +70,7 @@ impl FloatDistributionSummary {
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct MessageTypeBreakdown {
     pub initial_vote: usize,
     pub vote: usize,
@@ -76,8 +80,13 @@ pub struct MessageTypeBreakdown {
     pub answer: usize,
     pub block: usize,
     pub referral: usize,
+    pub peer_list: usize,
     pub query_commit_block: usize,
     pub commit_block: usize,
+    pub query_blocks: usize,
+    pub blocks: usize,
+    pub keepalive: usize,
+    pub commit_head: usize,
     pub batched_request_items: usize,
 }
 
@@ -104,8 +113,13 @@ impl MessageTypeBreakdown {
             Message::Answer { .. } => self.answer += 1,
             Message::Block { .. } => self.block += 1,
             Message::Referral { .. } => self.referral += 1,
+            Message::PeerList { .. } => self.peer_list += 1,
             Message::QueryCommitBlock { .. } => self.query_commit_block += 1,
             Message::CommitBlock { .. } => self.commit_block += 1,
+            Message::QueryBlocks { .. } => self.query_blocks += 1,
+            Message::Blocks { .. } => self.blocks += 1,
+            Message::Keepalive { .. } => self.keepalive += 1,
+            Message::CommitHead { .. } => self.commit_head += 1,
         }
     }
 
@@ -129,12 +143,17 @@ impl MessageTypeBreakdown {
             + self.answer
             + self.block
             + self.referral
+            + self.peer_list
             + self.query_commit_block
             + self.commit_block
+            + self.query_blocks
+            + self.blocks
+            + self.keepalive
+            + self.commit_head
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MempoolPressureSummary {
     pub avg_pending_without_block: f64,
     pub peak_pending_without_block: usize,
@@ -152,7 +171,7 @@ pub struct MempoolPressureSummary {
     pub peak_pending_age_200_plus: usize,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VoteIngressSummary {
     pub trusted_votes_recorded: usize,
     pub untrusted_votes_received: usize,
@@ -161,7 +180,7 @@ pub struct VoteIngressSummary {
     pub missing_parent_requests_triggered: usize,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RoundMetrics {
     pub round: usize,
     pub active_peers: usize,
@@ -209,7 +228,7 @@ pub struct RoundMetrics {
     pub missing_parent_requests_triggered: usize,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OnboardingSummary {
     pub observed_peers: usize,
     pub bootstrap_seeded_peers: usize,
@@ -220,7 +239,7 @@ pub struct OnboardingSummary {
     pub connected_before_sync_trace: usize,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RecoverySummary {
     pub label: String,
     pub start_round: usize,
@@ -235,7 +254,7 @@ impl RecoverySummary {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NeighborhoodBucketSummary {
     pub label: String,
     pub token_samples: usize,
@@ -246,7 +265,7 @@ pub struct NeighborhoodBucketSummary {
     pub commit_latency: Option<DistributionSummary>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NeighborhoodSummary {
     pub token_samples: usize,
     pub local_token_samples: usize,
@@ -256,7 +275,7 @@ pub struct NeighborhoodSummary {
     pub buckets: Vec<NeighborhoodBucketSummary>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionSpreadSummary {
     pub submitted_blocks: usize,
     pub committed_blocks: usize,
@@ -276,7 +295,7 @@ pub struct TransactionSpreadSummary {
     pub total_ideal_coalesced_lower_bound_messages: usize,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionWorkloadSummary {
     pub configured_existing_token_fraction: f64,
     pub existing_token_parts: usize,
@@ -295,7 +314,7 @@ impl TransactionWorkloadSummary {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionCategorySummary {
     pub label: String,
     pub submitted: usize,
@@ -306,7 +325,7 @@ pub struct TransactionCategorySummary {
     pub settled_block_messages: Option<DistributionSummary>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConflictLineageSummary {
     pub label: String,
     pub families: usize,
@@ -327,7 +346,7 @@ pub struct ConflictLineageSummary {
     pub signal_coverage_among_participants: Option<FloatDistributionSummary>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConflictWorkloadSummary {
     pub configured_family_fraction: f64,
     pub configured_contenders: usize,
@@ -358,7 +377,7 @@ pub struct ConflictWorkloadSummary {
     pub signal_coverage_among_participants: Option<FloatDistributionSummary>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimResult {
     pub seed_used: [u8; 32],
     pub rounds_completed: usize,
@@ -432,6 +451,13 @@ pub struct SimResult {
 }
 
 impl SimResult {
+    /// Write the result as pretty-printed JSON (for comparing runs offline)
+    pub fn write_json(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let file = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(file, self)?;
+        Ok(())
+    }
+
     pub fn print_summary(&self) {
         println!("\n╔════════════════════════════════════════════════════════╗");
         println!("║  Integrated Simulation Summary                        ║");
@@ -691,7 +717,7 @@ impl SimResult {
             );
         }
         println!(
-            "Scheduled logical messages by type: total {}, initial-votes {}, votes {}, query-block {}, query-token {}, answers {}, blocks {}, referrals {}, query-commit {}, commit-block {}",
+            "Scheduled logical messages by type: total {}, initial-votes {}, votes {}, query-block {}, query-token {}, answers {}, blocks {}, referrals {}, peer-lists {}, query-commit {}, commit-block {}",
             self.scheduled_message_types.total(),
             self.scheduled_message_types.initial_vote,
             self.scheduled_message_types.vote,
@@ -700,11 +726,12 @@ impl SimResult {
             self.scheduled_message_types.answer,
             self.scheduled_message_types.block,
             self.scheduled_message_types.referral,
+            self.scheduled_message_types.peer_list,
             self.scheduled_message_types.query_commit_block,
             self.scheduled_message_types.commit_block,
         );
         println!(
-            "Delivered logical messages by type: total {}, initial-votes {}, votes {}, query-block {}, query-token {}, answers {}, blocks {}, referrals {}, query-commit {}, commit-block {}",
+            "Delivered logical messages by type: total {}, initial-votes {}, votes {}, query-block {}, query-token {}, answers {}, blocks {}, referrals {}, peer-lists {}, query-commit {}, commit-block {}",
             self.delivered_message_types.total(),
             self.delivered_message_types.initial_vote,
             self.delivered_message_types.vote,
@@ -713,11 +740,12 @@ impl SimResult {
             self.delivered_message_types.answer,
             self.delivered_message_types.block,
             self.delivered_message_types.referral,
+            self.delivered_message_types.peer_list,
             self.delivered_message_types.query_commit_block,
             self.delivered_message_types.commit_block,
         );
         println!(
-            "Scheduled wire messages by type: total {}, request-batches {}, batched-items {}, initial-votes {}, votes {}, query-block {}, query-token {}, answers {}, blocks {}, referrals {}, query-commit {}, commit-block {}",
+            "Scheduled wire messages by type: total {}, request-batches {}, batched-items {}, initial-votes {}, votes {}, query-block {}, query-token {}, answers {}, blocks {}, referrals {}, peer-lists {}, query-commit {}, commit-block {}",
             self.scheduled_wire_message_types.total(),
             self.scheduled_wire_message_types.request_batch,
             self.scheduled_wire_message_types.batched_request_items,
@@ -728,11 +756,12 @@ impl SimResult {
             self.scheduled_wire_message_types.answer,
             self.scheduled_wire_message_types.block,
             self.scheduled_wire_message_types.referral,
+            self.scheduled_wire_message_types.peer_list,
             self.scheduled_wire_message_types.query_commit_block,
             self.scheduled_wire_message_types.commit_block,
         );
         println!(
-            "Delivered wire messages by type: total {}, request-batches {}, batched-items {}, initial-votes {}, votes {}, query-block {}, query-token {}, answers {}, blocks {}, referrals {}, query-commit {}, commit-block {}",
+            "Delivered wire messages by type: total {}, request-batches {}, batched-items {}, initial-votes {}, votes {}, query-block {}, query-token {}, answers {}, blocks {}, referrals {}, peer-lists {}, query-commit {}, commit-block {}",
             self.delivered_wire_message_types.total(),
             self.delivered_wire_message_types.request_batch,
             self.delivered_wire_message_types.batched_request_items,
@@ -743,6 +772,7 @@ impl SimResult {
             self.delivered_wire_message_types.answer,
             self.delivered_wire_message_types.block,
             self.delivered_wire_message_types.referral,
+            self.delivered_wire_message_types.peer_list,
             self.delivered_wire_message_types.query_commit_block,
             self.delivered_wire_message_types.commit_block,
         );
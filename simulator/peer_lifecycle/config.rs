@@ -2,7 +2,9 @@
 
 use ec_rust::ec_interface::PeerId;
 use ec_rust::ec_peers::PeerManagerConfig;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 // ============================================================================
 // Main Configuration
@@ -267,10 +269,46 @@ pub enum BootstrapMethod {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NetworkConfig {
     /// Fraction of messages delayed to next round (0.0 to 1.0)
+    ///
+    /// Ignored when `latency` is set.
     pub delay_fraction: f64,
 
-    /// Fraction of messages dropped (0.0 to 1.0)
+    /// Fraction of messages dropped (0.0 to 1.0), independent of latency
     pub loss_fraction: f64,
+
+    /// Per-message delay distribution in rounds (None = `delay_fraction` model)
+    #[serde(default)]
+    pub latency: Option<LatencyModel>,
+}
+
+/// Distribution of extra rounds a message spends in flight
+///
+/// A sampled delay of 0 delivers the message in the round it is processed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum LatencyModel {
+    /// Every message is delayed by the same number of rounds
+    Fixed(usize),
+
+    /// Uniformly distributed delay in `min..=max` rounds
+    Uniform { min: usize, max: usize },
+
+    /// Exponentially distributed delay (rounded to whole rounds), for long tails
+    Exponential { mean: f64 },
+}
+
+impl LatencyModel {
+    /// Sample the delay of one message in rounds
+    pub fn sample_rounds<R: Rng>(&self, rng: &mut R) -> usize {
+        match *self {
+            LatencyModel::Fixed(rounds) => rounds,
+            LatencyModel::Uniform { min, max } => rng.gen_range(min..=max.max(min)),
+            LatencyModel::Exponential { mean } => {
+                // Inverse transform sampling; 1 - u is in (0, 1] so ln stays finite
+                let u: f64 = rng.gen();
+                (-mean * (1.0 - u).ln()).round() as usize
+            }
+        }
+    }
 }
 
 // ============================================================================
@@ -295,6 +333,11 @@ pub struct MetricsConfig {
     /// Track convergence time
     pub track_convergence_time: bool,
 
+    /// Average Connected count at which the network (or a joined group) is
+    /// considered converged
+    #[serde(default = "default_target_peer_count")]
+    pub target_peer_count: usize,
+
     /// How often to sample metrics (every N rounds)
     pub sample_interval: usize,
 
@@ -312,8 +355,11 @@ pub struct OutputConfig {
     /// Enable console event logging
     pub enable_console: bool,
 
-    /// CSV output file path
-    pub csv_path: Option<String>,
+    /// Per-event CSV output path (one row per EcPeers event, all peers in one file)
+    pub event_csv_path: Option<PathBuf>,
+
+    /// Write the SimulationResult (including metrics history) as JSON here
+    pub result_json_path: Option<PathBuf>,
 
     /// Verbose logging
     pub verbose: bool,
@@ -376,10 +422,15 @@ impl Default for NetworkConfig {
         Self {
             delay_fraction: 0.3,
             loss_fraction: 0.01,
+            latency: None,
         }
     }
 }
 
+fn default_target_peer_count() -> usize {
+    8
+}
+
 impl Default for MetricsConfig {
     fn default() -> Self {
         Self {
@@ -388,6 +439,7 @@ impl Default for MetricsConfig {
             track_quality_scores: true,
             track_ring_coverage: true,
             track_convergence_time: true,
+            target_peer_count: default_target_peer_count(),
             sample_interval: 10,
             peer_snapshots: false,
         }
@@ -398,7 +450,8 @@ impl Default for OutputConfig {
     fn default() -> Self {
         Self {
             enable_console: false,
-            csv_path: None,
+            event_csv_path: None,
+            result_json_path: None,
             verbose: false,
         }
     }
@@ -1,6 +1,9 @@
 // Peer Lifecycle Simulator Module
 
 pub mod config;
+#[allow(dead_code)] // Shared with the consensus simulator; not every sink is used here
+#[path = "../consensus/event_sinks.rs"]
+pub mod event_sinks;
 pub mod runner;
 pub mod scenarios;
 pub mod stats;
@@ -10,8 +13,8 @@ pub mod topology;
 // Re-export commonly used types for public API
 #[allow(unused_imports)] // Re-exports for external consumers
 pub use config::{
-    BootstrapMethod, EventSchedule, InitialNetworkState, NetworkEvent, PeerLifecycleConfig,
-    PeerSelection, ScheduledEvent, TokenDistributionConfig, TopologyMode,
+    BootstrapMethod, EventSchedule, InitialNetworkState, LatencyModel, NetworkEvent,
+    PeerLifecycleConfig, PeerSelection, ScheduledEvent, TokenDistributionConfig, TopologyMode,
 };
 
 #[allow(unused_imports)] // Re-exports for external consumers
@@ -101,6 +101,15 @@ impl RoundBuilder {
         self.scenario
     }
 
+    /// Remove peers permanently (graceful leave)
+    pub fn peers_leave(mut self, selection: PeerSelection) -> ScenarioBuilder {
+        self.scenario.events.push(ScheduledEvent {
+            round: self.round,
+            event: NetworkEvent::PeerLeave { selection },
+        });
+        self.scenario
+    }
+
     /// Change network conditions
     pub fn network_conditions(
         mut self,
@@ -1,14 +1,18 @@
 // Peer Lifecycle Simulator Statistics
 
 use ec_rust::ec_interface::PeerId;
-use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::Path;
 
 // ============================================================================
 // Simulation Result
 // ============================================================================
 
 /// Complete simulation result
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimulationResult {
     /// Configuration summary
     pub config_summary: String,
@@ -40,7 +44,7 @@ pub struct SimulationResult {
 // ============================================================================
 
 /// Metrics collected at a single round
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RoundMetrics {
     /// Round number
     pub round: usize,
@@ -62,7 +66,7 @@ pub struct RoundMetrics {
 }
 
 /// Peer state counts
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PeerCounts {
     /// Total peers in simulation
     pub total_peers: usize,
@@ -81,7 +85,7 @@ pub struct PeerCounts {
 }
 
 /// Election performance statistics
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ElectionStats {
     /// Elections started this round
     pub elections_started: usize,
@@ -109,7 +113,7 @@ pub struct ElectionStats {
 }
 
 /// Network health metrics
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NetworkHealth {
     /// Minimum connected peers (across all active nodes)
     pub min_connected_peers: usize,
@@ -126,9 +130,20 @@ pub struct NetworkHealth {
     /// Ring coverage percentage (0.0 to 100.0)
     pub ring_coverage_percent: f64,
 
-    /// Network partition detected
+    /// Network partition detected (more than one non-trivial component in the
+    /// Connected graph)
     pub partition_detected: bool,
 
+    /// Number of connected components in the Connected graph of active peers
+    /// (isolated peers count as their own component)
+    #[serde(default)]
+    pub connected_components: usize,
+
+    /// Largest gap between covered peer IDs exceeds `PARTITION_GAP_FRACTION`
+    /// of the ring
+    #[serde(default)]
+    pub coverage_gap_detected: bool,
+
     /// Connected peer count distribution by quantile
     pub connected_peer_distribution: Option<ConnectedPeerDistribution>,
 
@@ -146,7 +161,7 @@ pub struct NetworkHealth {
 }
 
 /// Quality score metrics
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QualityMetrics {
     /// Minimum quality score
     pub min_quality: f64,
@@ -166,7 +181,7 @@ pub struct QualityMetrics {
 // ============================================================================
 
 /// Distribution of connected peer counts across nodes
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConnectedPeerDistribution {
     /// Number of quantiles (typically 4 for quartiles)
     pub num_quantiles: usize,
@@ -212,7 +227,7 @@ impl Default for ConnectedPeerDistribution {
 
 /// Quantile distribution of locality gradient values
 /// Measures how well connected peers cluster near the node (locality gradient)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GradientSteepnessDistribution {
     /// Number of quantiles (typically 4 for quartiles)
     pub num_quantiles: usize,
@@ -243,7 +258,7 @@ pub struct GradientSteepnessDistribution {
 }
 
 /// Average fit against the corrected fixed-network ring-gradient target.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct GradientShapeMetrics {
     /// Connected peers that are also active in the simulator.
     pub avg_active_connected_peers: f64,
@@ -267,7 +282,7 @@ pub struct GradientShapeMetrics {
     pub avg_far_coverage: f64,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SmallWorldShapeMetrics {
     /// Average connected peers that are also active.
     pub avg_active_connected_peers: f64,
@@ -310,7 +325,7 @@ impl Default for GradientSteepnessDistribution {
 // ============================================================================
 
 /// Log entry for a network event
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EventOutcome {
     /// Round when event occurred
     pub round: usize,
@@ -333,14 +348,20 @@ pub struct EventOutcome {
 // ============================================================================
 
 /// Analysis of network convergence behavior
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConvergenceAnalysis {
-    /// Rounds to reach initial stability
+    /// First round where the network-wide average Connected count reached
+    /// the target peer count
     pub bootstrap_convergence_time: Option<usize>,
 
     /// Recovery times after churn events (round → recovery_time)
     pub post_churn_recovery_times: Vec<(usize, usize)>,
 
+    /// Rounds from each group's join until its average Connected count
+    /// reached the target peer count (None if it never did)
+    #[serde(default)]
+    pub group_convergence: BTreeMap<String, Option<usize>>,
+
     /// Achieved peer count (average across nodes)
     pub achieved_peer_count: usize,
 
@@ -353,7 +374,7 @@ pub struct ConvergenceAnalysis {
 // ============================================================================
 
 /// Message overhead statistics
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MessageOverhead {
     /// Total messages sent
     pub total_messages: usize,
@@ -413,6 +434,8 @@ impl RoundMetrics {
                 stddev_connected_peers: 0.0,
                 ring_coverage_percent: 0.0,
                 partition_detected: false,
+                connected_components: 0,
+                coverage_gap_detected: false,
                 connected_peer_distribution: None,
                 gradient_distribution: None,
                 gradient_shape: None,
@@ -430,6 +453,13 @@ impl RoundMetrics {
 }
 
 impl SimulationResult {
+    /// Write the result as pretty-printed JSON (for comparing runs offline)
+    pub fn write_json(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let file = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(file, self)?;
+        Ok(())
+    }
+
     /// Print summary to console
     pub fn print_summary(&self) {
         println!("\n╔════════════════════════════════════════════════════════╗");
@@ -490,8 +520,22 @@ impl SimulationResult {
             metrics.network_health.avg_connected_peers
         );
         println!(
-            "  Ring Coverage: {:.1}%",
-            metrics.network_health.ring_coverage_percent
+            "  Ring Coverage: {:.1}%{}",
+            metrics.network_health.ring_coverage_percent,
+            if metrics.network_health.coverage_gap_detected {
+                " (COVERAGE GAP)"
+            } else {
+                ""
+            }
+        );
+        println!(
+            "  Components: {}{}",
+            metrics.network_health.connected_components,
+            if metrics.network_health.partition_detected {
+                " (PARTITIONED)"
+            } else {
+                ""
+            }
         );
         println!();
 
@@ -514,6 +558,12 @@ impl SimulationResult {
                 println!("  Bootstrap Time: {} rounds", bootstrap_time);
             }
             println!("  Achieved Peers: {}", self.convergence.achieved_peer_count);
+            for (group_name, rounds) in &self.convergence.group_convergence {
+                match rounds {
+                    Some(rounds) => println!("  Group '{}': {} rounds", group_name, rounds),
+                    None => println!("  Group '{}': not converged", group_name),
+                }
+            }
             println!();
         }
 
@@ -856,3 +906,177 @@ pub fn calculate_gradient_distribution(
         near_ideal_percent: strong_locality_percent,
     }
 }
+
+// ============================================================================
+// Ring Coverage Calculation
+// ============================================================================
+
+/// Largest uncovered gap (as a fraction of the ring) before ring coverage is
+/// flagged in `NetworkHealth::coverage_gap_detected`
+pub const PARTITION_GAP_FRACTION: f64 = 0.25;
+
+/// Calculate how much of the ID ring is spanned by a set of connected peers
+///
+/// Sorts the covered peer IDs and finds the largest gap between neighbours
+/// (including the wrap-around gap from the last ID back to the first).
+///
+/// Returns `(coverage_percent, largest_gap_fraction)`:
+/// - coverage_percent = (1.0 - largest_gap / ring_size) * 100.0
+/// - largest_gap_fraction = largest_gap / ring_size (1.0 when fewer than two peers)
+pub fn calculate_ring_coverage(covered_peers: &[PeerId]) -> (f64, f64) {
+    let mut sorted: Vec<PeerId> = covered_peers.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+
+    if sorted.len() < 2 {
+        return (0.0, 1.0);
+    }
+
+    let wrap_gap = sorted[0].wrapping_sub(sorted[sorted.len() - 1]);
+    let largest_gap = sorted
+        .windows(2)
+        .map(|pair| pair[1] - pair[0])
+        .fold(wrap_gap, u64::max);
+
+    let ring_size = u64::MAX as f64 + 1.0;
+    let largest_gap_fraction = largest_gap as f64 / ring_size;
+    ((1.0 - largest_gap_fraction) * 100.0, largest_gap_fraction)
+}
+
+// ============================================================================
+// Connected Component Calculation
+// ============================================================================
+
+/// Smallest component size that counts towards partition detection
+///
+/// Isolated peers (e.g. freshly joined, not yet connected) are their own
+/// component but do not by themselves indicate a fractured network.
+pub const MIN_PARTITION_COMPONENT_SIZE: usize = 2;
+
+/// Find the connected components of the undirected Connected graph
+///
+/// `peers` are the graph's nodes; each edge `(a, b)` joins two of them (edges
+/// touching unknown peers are ignored). Uses union-find with path halving.
+///
+/// Returns the component sizes, largest first.
+pub fn calculate_connected_components(peers: &[PeerId], edges: &[(PeerId, PeerId)]) -> Vec<usize> {
+    let index: HashMap<PeerId, usize> = peers.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+    let mut parent: Vec<usize> = (0..peers.len()).collect();
+
+    fn find(parent: &mut [usize], mut node: usize) -> usize {
+        while parent[node] != node {
+            parent[node] = parent[parent[node]];
+            node = parent[node];
+        }
+        node
+    }
+
+    for (a, b) in edges {
+        if let (Some(&a), Some(&b)) = (index.get(a), index.get(b)) {
+            let (root_a, root_b) = (find(&mut parent, a), find(&mut parent, b));
+            if root_a != root_b {
+                parent[root_a] = root_b;
+            }
+        }
+    }
+
+    let mut sizes: HashMap<usize, usize> = HashMap::new();
+    for node in 0..peers.len() {
+        *sizes.entry(find(&mut parent, node)).or_default() += 1;
+    }
+
+    let mut sizes: Vec<usize> = sizes.into_values().collect();
+    sizes.sort_unstable_by(|a, b| b.cmp(a));
+    sizes
+}
+
+/// Whether component sizes describe a partitioned network
+pub fn is_partitioned(component_sizes: &[usize]) -> bool {
+    component_sizes
+        .iter()
+        .filter(|size| **size >= MIN_PARTITION_COMPONENT_SIZE)
+        .count()
+        > 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_coverage_full_ring_topology() {
+        // 64 evenly spaced peers, each connected to its two ring neighbours
+        let step = u64::MAX / 64 + 1;
+        let peers: Vec<PeerId> = (0..64).map(|i| i * step).collect();
+        let covered: Vec<PeerId> = peers
+            .iter()
+            .enumerate()
+            .flat_map(|(i, _)| [peers[(i + 1) % 64], peers[(i + 63) % 64]])
+            .collect();
+
+        let (coverage, largest_gap) = calculate_ring_coverage(&covered);
+        assert!(coverage > 98.0, "coverage {coverage}");
+        assert!(largest_gap < PARTITION_GAP_FRACTION);
+    }
+
+    #[test]
+    fn test_ring_coverage_isolated_pair() {
+        let covered = vec![1_000, 2_000, 2_000, 1_000];
+
+        let (coverage, largest_gap) = calculate_ring_coverage(&covered);
+        assert!(coverage < 1.0, "coverage {coverage}");
+        assert!(largest_gap > PARTITION_GAP_FRACTION);
+    }
+
+    #[test]
+    fn test_ring_coverage_empty() {
+        assert_eq!(calculate_ring_coverage(&[]), (0.0, 1.0));
+        assert_eq!(calculate_ring_coverage(&[42]), (0.0, 1.0));
+    }
+
+    #[test]
+    fn test_connected_components_detects_disconnected_groups() {
+        // Two fully connected groups of four with no edges between them
+        let peers: Vec<PeerId> = (1..=8).collect();
+        let edges: Vec<(PeerId, PeerId)> = [1..=4, 5..=8]
+            .into_iter()
+            .flat_map(|group| {
+                let group: Vec<PeerId> = group.collect();
+                group
+                    .iter()
+                    .flat_map(|a| group.iter().map(move |b| (*a, *b)))
+                    .filter(|(a, b)| a != b)
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        let sizes = calculate_connected_components(&peers, &edges);
+        assert_eq!(sizes, vec![4, 4]);
+        assert!(is_partitioned(&sizes));
+    }
+
+    #[test]
+    fn test_connected_components_fully_connected_group() {
+        let peers: Vec<PeerId> = (1..=8).collect();
+        let edges: Vec<(PeerId, PeerId)> = peers
+            .iter()
+            .flat_map(|a| peers.iter().map(move |b| (*a, *b)))
+            .filter(|(a, b)| a != b)
+            .collect();
+
+        let sizes = calculate_connected_components(&peers, &edges);
+        assert_eq!(sizes, vec![8]);
+        assert!(!is_partitioned(&sizes));
+    }
+
+    #[test]
+    fn test_connected_components_isolated_peers_not_a_partition() {
+        // One connected chain plus two peers that have not connected yet
+        let peers: Vec<PeerId> = (1..=6).collect();
+        let edges = vec![(1, 2), (2, 3), (4, 3)];
+
+        let sizes = calculate_connected_components(&peers, &edges);
+        assert_eq!(sizes, vec![4, 1, 1]);
+        assert!(!is_partitioned(&sizes));
+    }
+}
@@ -10,11 +10,11 @@ use ec_rust::ec_genesis::GenesisConfig;
 use ec_rust::ec_interface::{BlockId, BlockTime, EcTime, PeerId, TokenId, GENESIS_BLOCK_ID};
 use ec_rust::ec_memory_backend::MemTokens;
 use ec_rust::ec_proof_of_storage::{
-    ring_distance, SignatureSearchResult, TokenStorageBackend, SIGNATURE_CHUNKS,
+    ring_distance, SignatureParams, SignatureSearchResult, TokenStorageBackend,
 };
 use rand::rngs::StdRng;
 use rand::Rng;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet};
 use std::sync::Arc;
 
 /// Configuration for token distribution
@@ -48,10 +48,10 @@ impl Default for TokenDistributionConfig {
 /// Peer IDs are tokens - this ensures all peer IDs are discoverable via elections.
 pub struct GlobalTokenMapping {
     /// All token→block mappings (includes both regular tokens and peer ID tokens)
-    mappings: HashMap<TokenId, BlockId>,
+    mappings: BTreeMap<TokenId, BlockId>,
 
     /// Set of token IDs that have been allocated as peer IDs
-    allocated_peer_ids: HashSet<PeerId>,
+    allocated_peer_ids: BTreeSet<PeerId>,
 
     /// Random number generator for token selection and sampling
     rng: StdRng,
@@ -63,7 +63,7 @@ impl GlobalTokenMapping {
     /// Generates a pool of random tokens. Peer IDs will be allocated from this pool
     /// on-demand using allocate_peer_id().
     pub fn new(mut rng: StdRng, total_tokens: usize) -> Self {
-        let mut mappings = HashMap::with_capacity(total_tokens);
+        let mut mappings = BTreeMap::new();
 
         // Generate random token→block mappings
         // Peer IDs will be allocated from this pool later
@@ -75,7 +75,7 @@ impl GlobalTokenMapping {
 
         Self {
             mappings,
-            allocated_peer_ids: HashSet::new(),
+            allocated_peer_ids: BTreeSet::new(),
             rng,
         }
     }
@@ -216,8 +216,15 @@ impl GlobalTokenMapping {
         None
     }
 
+    /// Return a peer ID to the pool so it can be allocated again
+    ///
+    /// Returns false if the ID was not allocated.
+    pub fn release_peer_id(&mut self, peer_id: PeerId) -> bool {
+        self.allocated_peer_ids.remove(&peer_id)
+    }
+
     /// Get all currently allocated peer IDs
-    pub fn allocated_peer_ids(&self) -> &HashSet<PeerId> {
+    pub fn allocated_peer_ids(&self) -> &BTreeSet<PeerId> {
         &self.allocated_peer_ids
     }
 
@@ -254,7 +261,7 @@ pub struct GenesisTokenSet {
     token_ids: Vec<TokenId>,
 
     /// Allocated peer IDs (subset of token_ids)
-    allocated_peer_ids: HashSet<PeerId>,
+    allocated_peer_ids: BTreeSet<PeerId>,
 
     /// Random number generator for sampling
     rng: StdRng,
@@ -336,17 +343,22 @@ impl TokenStorageBackend for GenesisPeerTokens {
         // tests use the integrated simulator with a mutable backend.
     }
 
+    fn remove(&mut self, _token: &TokenId) -> Option<BlockTime> {
+        // Immutable, like `set`
+        None
+    }
+
     fn search_signature(
         &self,
         lookup_token: &TokenId,
-        signature_chunks: &[u16; SIGNATURE_CHUNKS],
+        signature_chunks: &[u16],
+        params: &SignatureParams,
+        forward_chunks: usize,
+        max_steps: usize,
     ) -> SignatureSearchResult {
-        #[inline]
-        fn matches_chunk(token: &TokenId, chunk_value: u16) -> bool {
-            (token & 0x3FF) as u16 == chunk_value
-        }
-
-        let mut found_tokens = Vec::with_capacity(SIGNATURE_CHUNKS);
+        let total = signature_chunks.len();
+        let forward = forward_chunks.min(total);
+        let mut found_tokens = Vec::with_capacity(total);
         let mut steps = 0;
         let mut chunk_idx = 0;
 
@@ -359,19 +371,26 @@ impl TokenStorageBackend for GenesisPeerTokens {
         };
 
         for offset in 0..self.mappings.len() {
+            if chunk_idx >= forward {
+                break;
+            }
             let idx = (start_idx + offset) % self.mappings.len();
             let token_id = self.mappings[idx].0;
+            if steps >= max_steps {
+                break;
+            }
             steps += 1;
-            if self.owns(token_id) && matches_chunk(&token_id, signature_chunks[chunk_idx]) {
+            if self.owns(token_id) && params.matches_chunk(&token_id, signature_chunks[chunk_idx]) {
                 found_tokens.push(token_id);
                 chunk_idx += 1;
-                if chunk_idx >= SIGNATURE_CHUNKS / 2 {
+                if chunk_idx >= forward {
                     break;
                 }
             }
         }
 
-        chunk_idx = SIGNATURE_CHUNKS / 2;
+        let forward_steps = steps;
+        chunk_idx = forward;
         let backward_start = match self
             .mappings
             .binary_search_by_key(lookup_token, |(token_id, _)| *token_id)
@@ -384,23 +403,41 @@ impl TokenStorageBackend for GenesisPeerTokens {
         for offset in 0..self.mappings.len() {
             let idx = (backward_start + self.mappings.len() - offset) % self.mappings.len();
             let token_id = self.mappings[idx].0;
+            if steps >= max_steps {
+                break;
+            }
             steps += 1;
-            if self.owns(token_id) && matches_chunk(&token_id, signature_chunks[chunk_idx]) {
+            if self.owns(token_id) && params.matches_chunk(&token_id, signature_chunks[chunk_idx]) {
                 found_tokens.push(token_id);
                 chunk_idx += 1;
-                if chunk_idx >= SIGNATURE_CHUNKS {
+                if chunk_idx >= total {
                     break;
                 }
             }
         }
 
         SignatureSearchResult {
-            complete: found_tokens.len() == SIGNATURE_CHUNKS,
+            complete: found_tokens.len() == total,
+            chunks_matched: found_tokens.len(),
             tokens: found_tokens,
             steps,
+            forward_steps,
+            backward_steps: steps - forward_steps,
         }
     }
 
+    fn range_after(&self, token: &TokenId) -> Box<dyn Iterator<Item = (TokenId, BlockTime)> + '_> {
+        let idx = self
+            .mappings
+            .partition_point(|(token_id, _)| token_id <= token);
+        Box::new(
+            self.mappings[idx..]
+                .iter()
+                .filter(|(token_id, _)| self.owns(*token_id))
+                .map(|(token_id, block)| (*token_id, BlockTime::new(*block, GENESIS_BLOCK_ID, 0))),
+        )
+    }
+
     fn len(&self) -> usize {
         self.len
     }
@@ -445,7 +482,7 @@ impl GenesisTokenSet {
 
         Self {
             token_ids,
-            allocated_peer_ids: HashSet::new(),
+            allocated_peer_ids: BTreeSet::new(),
             rng,
         }
     }
@@ -555,7 +592,7 @@ impl GenesisTokenSet {
     }
 
     /// Get all allocated peer IDs
-    pub fn allocated_peer_ids(&self) -> &HashSet<PeerId> {
+    pub fn allocated_peer_ids(&self) -> &BTreeSet<PeerId> {
         &self.allocated_peer_ids
     }
 
@@ -1,6 +1,6 @@
 //! Commit Chain Module - Two-Slot Sync
 //!
-//! Implements continuous background synchronization by tracking 4 peer chains
+//! Implements continuous background synchronization by tracking nearby peer chains
 //! (2 above, 2 below on ring) and syncing from newest to oldest.
 //!
 //! Key principles:
@@ -9,11 +9,12 @@
 //! - Top-down sync (latest → oldest)
 //! - Simple 2-state machine per peer
 //! - Local protection via mempool delegation
-//! - Highest transaction ID wins (deterministic conflict resolution)
+//! - Highest transaction ID wins by default (`ConflictResolution` is configurable)
 
 use crate::ec_interface::{
-    Block, BlockId, CommitBlock, CommitBlockId, EcBlocks, EcCommitChainBackend, EcTime, EcTokensV2,
-    MessageTicket, PeerId, StorageBatch, TokenId, GENESIS_BLOCK_ID,
+    Block, BlockId, CommitBlock, CommitBlockId, ConflictResolution, EcBlocks, EcCommitChainBackend,
+    EcTime, EcTokensV2, Event, EventSink, MessageTicket, ParentBlockRequest, PeerId, StorageBatch,
+    TokenId, TokenState, GENESIS_BLOCK_ID,
 };
 use crate::ec_mempool::EcMemPool;
 use crate::ec_peers::PeerRange;
@@ -27,12 +28,35 @@ use std::collections::{HashMap, HashSet};
 pub struct CommitChainConfig {
     /// Initial sync target (e.g., 30 days back)
     pub sync_target: EcTime,
+    /// How long conflicting sync mappings are kept as fraud evidence
+    pub fraud_log_retention: EcTime,
+    /// Number of peer chains to track (split above/below on ring)
+    pub max_tracked_peers: usize,
+    /// How long a received block may sit unreferenced by any trace
+    pub orphan_ttl: EcTime,
+    /// Which mapping wins when a sync update competes with pending
+    pub conflict_resolution: ConflictResolution,
+    /// How long a pending mapping may wait for a confirming peer before it is dropped
+    pub pending_expiry_age: EcTime,
+    /// Per-tick budget of parent requests to one peer, refilled every tick
+    /// whether or not earlier requests were answered; once spent, the peer's
+    /// trace falls back to one step per tick
+    pub parent_requests_per_tick: usize,
+    /// Sync work runs on ticks where `time` is a multiple of this (1 = every tick)
+    pub sync_interval: EcTime,
 }
 
 impl Default for CommitChainConfig {
     fn default() -> Self {
         Self {
-            sync_target: 30 * 24 * 3600, // 30 days
+            sync_target: 30 * 24 * 3600,        // 30 days
+            fraud_log_retention: 7 * 24 * 3600, // 7 days
+            max_tracked_peers: 4,
+            orphan_ttl: 3600, // 1 hour
+            conflict_resolution: ConflictResolution::HighestBlockId,
+            pending_expiry_age: 24 * 3600, // 1 day
+            parent_requests_per_tick: 8,
+            sync_interval: 1,
         }
     }
 }
@@ -70,6 +94,9 @@ struct PeerChainLog {
     /// Time of first (oldest) CommitBlock in current trace
     /// Used to update global watermark when trace completes
     first_commit_time: Option<EcTime>,
+    /// Parent requests sent to this peer since the last tick, spending the
+    /// `parent_requests_per_tick` budget
+    parent_requests_this_tick: usize,
 }
 
 impl PeerChainLog {
@@ -79,6 +106,7 @@ impl PeerChainLog {
             known_head: Some(head),
             current_trace: None,
             first_commit_time: None,
+            parent_requests_this_tick: 0,
         }
     }
 
@@ -87,16 +115,81 @@ impl PeerChainLog {
     }
 }
 
+/// A sync mapping that conflicts with what we already hold for a token
+///
+/// Recorded when a peer's commit chain names a different block spending the
+/// same parent as our current or pending mapping (a double spend of the same
+/// token output). Plain older mappings are not evidence: walking history
+/// backwards legitimately delivers them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FraudEvidence {
+    /// Conflicting block reported by the peer
+    pub block: BlockId,
+    /// Parent the conflicting block spends
+    pub parent: BlockId,
+    /// Block time of the conflicting mapping
+    pub time: EcTime,
+    /// Peer whose commit chain contained the conflicting block
+    pub reporter: PeerId,
+    /// Block of our current/pending mapping it conflicts with
+    pub conflicts_with: BlockId,
+    /// Local time the conflict was recorded (for retention)
+    pub recorded_at: EcTime,
+}
+
+/// Where a tracked peer's trace currently is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TracePhase {
+    /// No trace running (next tick starts one from the known head)
+    Idle,
+    /// Waiting for a CommitBlock response
+    WaitingForCommit,
+    /// Fetching the blocks named by a CommitBlock
+    FetchingBlocks,
+}
+
+/// Sync state of one tracked peer chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeerSyncStats {
+    pub peer_id: PeerId,
+    pub known_head: Option<CommitBlockId>,
+    pub trace: TracePhase,
+    /// Blocks named by the CommitBlock being fetched
+    pub blocks_in_commit: usize,
+    /// Of those, blocks not yet received
+    pub blocks_waiting: usize,
+}
+
+/// Snapshot returned by `EcCommitChain::sync_stats`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncStats {
+    pub tracked_peers: usize,
+    /// Per-peer state, sorted by peer id
+    pub peers: Vec<PeerSyncStats>,
+    /// Blocks held in the shared received pool
+    pub received_blocks: usize,
+    /// Received blocks no active trace references (candidates for eviction)
+    pub orphaned_blocks: usize,
+    /// Blocks waiting to be saved on the next batch commit
+    pub blocks_to_store: usize,
+    /// Unconfirmed pending mappings awaiting a second peer (or expiry)
+    pub pending_mappings: usize,
+    /// Fraud evidence entries within retention
+    pub fraud_evidence: usize,
+    pub watermark: EcTime,
+}
+
 // ============================================================================
 // Main Structure
 // ============================================================================
 
+#[derive(Clone)]
 pub struct EcCommitChain {
     peer_id: PeerId,
     my_range: PeerRange,
     config: CommitChainConfig,
 
-    /// Track 4 peers (2 above, 2 below on ring)
+    /// Tracked peers (max_tracked_peers, half above and half below on ring)
     peer_logs: HashMap<PeerId, PeerChainLog>,
 
     /// Blocks to store (block-id in range, but no tokens in range)
@@ -106,12 +199,22 @@ pub struct EcCommitChain {
     /// Blocks arrive via routing, not necessarily from tracking peers
     received_blocks: HashMap<BlockId, Block>,
 
+    /// First tick each received block was seen (for orphan_ttl)
+    received_at: HashMap<BlockId, EcTime>,
+
     /// Global watermark: how far back we've synced
     /// Starts at sync_target, moves forward (deeper) as traces complete
     watermark: EcTime,
 
+    /// When each token's current pending mapping was first seen (for pending_expiry_age)
+    /// Runtime only: pendings left over from before a restart never expire
+    pending_since: HashMap<TokenId, (BlockId, EcTime)>,
+
+    /// Conflicting mappings seen during sync, pruned after fraud_log_retention
+    fraud_log: HashMap<TokenId, Vec<FraudEvidence>>,
+
     /// Secret for generating tickets
-    ticket_secret: u64,
+    ticket_secret: [u8; 32],
 }
 
 // ============================================================================
@@ -135,10 +238,25 @@ enum SyncOperation {
 }
 
 impl EcCommitChain {
+    /// Create a commit chain with a random ticket secret
     pub fn new(peer_id: PeerId, my_range: PeerRange, config: CommitChainConfig) -> Self {
-        // Simple ticket secret (in production: crypto random)
-        let ticket_secret = peer_id.wrapping_mul(0x9e3779b97f4a7c15);
+        let mut ticket_secret = [0u8; 32];
+        use rand::RngCore;
+        rand::thread_rng().fill_bytes(&mut ticket_secret);
+
+        Self::with_secret(peer_id, my_range, config, ticket_secret)
+    }
 
+    /// Create a commit chain with a caller-provided ticket secret
+    ///
+    /// Chains built with the same secret issue identical tickets; derive it
+    /// from a seeded RNG for reproducible simulations.
+    pub fn with_secret(
+        peer_id: PeerId,
+        my_range: PeerRange,
+        config: CommitChainConfig,
+        ticket_secret: [u8; 32],
+    ) -> Self {
         Self {
             peer_id,
             my_range,
@@ -147,6 +265,9 @@ impl EcCommitChain {
             peer_logs: HashMap::new(),
             blocks_to_store: HashMap::new(),
             received_blocks: HashMap::new(),
+            received_at: HashMap::new(),
+            pending_since: HashMap::new(),
+            fraud_log: HashMap::new(),
             ticket_secret,
         }
     }
@@ -158,9 +279,13 @@ impl EcCommitChain {
     // Note: These tickets are for QueryCommitBlock/CommitBlock messages only.
     // Regular Block messages use the unified TicketManager in ec_node.rs.
 
+    /// Ticket = first 8 bytes of Blake3(id || ticket_secret)
     fn generate_ticket(&self, id: u64) -> MessageTicket {
-        let combined = id.wrapping_add(self.ticket_secret);
-        combined.wrapping_mul(0x9e3779b97f4a7c15)
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&id.to_le_bytes());
+        hasher.update(&self.ticket_secret);
+        let hash = hasher.finalize();
+        u64::from_le_bytes(hash.as_bytes()[0..8].try_into().unwrap())
     }
 
     fn verify_ticket(&self, id: u64, ticket: MessageTicket) -> bool {
@@ -171,11 +296,11 @@ impl EcCommitChain {
     // Peer Tracking
     // ========================================================================
 
-    /// Update tracked peers: drop inactive, add new if below 4
+    /// Update tracked peers: drop inactive, add new if below max_tracked_peers
     ///
     /// Strategy:
     /// 1. Check current tracked peers - drop if not active (Pending or Connected)
-    /// 2. If below 4 peers: find closest active peers and add them
+    /// 2. If below max_tracked_peers: find closest active peers and add them
     /// 3. Update commit chain heads for tracked peers
     fn update_tracked_peers(&mut self, peers: &crate::ec_peers::EcPeers) {
         // Step 1: Drop inactive peers
@@ -190,10 +315,13 @@ impl EcCommitChain {
             self.peer_logs.remove(&peer_id);
         }
 
-        // Step 2: Add new peers if below 4
-        while self.peer_logs.len() < 4 {
+        // Step 2: Add new peers if below max_tracked_peers
+        let max_tracked = self.config.max_tracked_peers;
+        // Look a bit wider than the slot count (10 candidates for 4 slots)
+        let candidate_count = max_tracked * 5 / 2;
+        while self.peer_logs.len() < max_tracked {
             // Find closest active peers to our peer_id
-            let candidates = peers.find_closest_active_peers(self.peer_id, 10);
+            let candidates = peers.find_closest_active_peers(self.peer_id, candidate_count);
 
             // Filter out already tracked peers and not-active peers
             let new_candidates: Vec<_> = candidates
@@ -290,6 +418,55 @@ impl EcCommitChain {
         true
     }
 
+    /// Request the previous CommitBlock right away if the current one has nothing to sync
+    ///
+    /// Called after `handle_commit_block` accepted a block from `sender`. When
+    /// all of its blocks are already stored locally (and none wait in the
+    /// received pool), the next tick would only step the trace back to
+    /// `previous`; doing it here saves a tick per gap. Traces that reach the
+    /// sync cutoff or genesis are left for the tick to complete, as are
+    /// further steps once `sender` used up its `parent_requests_per_tick`
+    /// budget for this tick.
+    pub fn parent_request(&mut self, sender: PeerId, time: EcTime) -> Option<ParentBlockRequest> {
+        let cutoff = self
+            .watermark
+            .max(time.saturating_sub(self.config.sync_target));
+        let log = self.peer_logs.get_mut(&sender)?;
+        if log.parent_requests_this_tick >= self.config.parent_requests_per_tick {
+            return None;
+        }
+
+        let previous = match &log.current_trace {
+            Some(TraceState::FetchingBlocks {
+                commit_block,
+                waiting_for,
+            }) if waiting_for.is_empty()
+                && commit_block.time > cutoff
+                && commit_block.previous != GENESIS_BLOCK_ID
+                && !commit_block
+                    .committed_blocks
+                    .iter()
+                    .any(|id| self.received_blocks.contains_key(id)) =>
+            {
+                commit_block.previous
+            }
+            _ => return None,
+        };
+
+        // Counted as already sent so the tick doesn't repeat the query
+        log.current_trace = Some(TraceState::WaitingForCommit {
+            requested_id: previous,
+            ticks_waiting: 1,
+        });
+        log.parent_requests_this_tick += 1;
+
+        Some(ParentBlockRequest {
+            receiver: sender,
+            block_id: previous,
+            ticket: self.generate_ticket(previous),
+        })
+    }
+
     /// Handle incoming Block
     ///
     /// Blocks arrive via routing from any peer (not necessarily tracking peers).
@@ -305,6 +482,32 @@ impl EcCommitChain {
         true
     }
 
+    /// Handle the blocks of a `Blocks` reply to a batch query
+    ///
+    /// Only blocks that `sender`'s trace is still waiting for (and that have
+    /// not arrived yet) are stored, each once, so a reply can never add more
+    /// blocks than were asked of that peer. Everything else is dropped.
+    ///
+    /// Returns the number of blocks stored.
+    pub fn handle_block_batch(&mut self, sender: PeerId, blocks: &[Block]) -> usize {
+        let Some(PeerChainLog {
+            current_trace: Some(TraceState::FetchingBlocks { waiting_for, .. }),
+            ..
+        }) = self.peer_logs.get(&sender)
+        else {
+            return 0;
+        };
+
+        let mut accepted = 0;
+        for block in blocks {
+            if waiting_for.contains(&block.id) && !self.received_blocks.contains_key(&block.id) {
+                self.received_blocks.insert(block.id, *block);
+                accepted += 1;
+            }
+        }
+        accepted
+    }
+
     /// Collect sync operations from received blocks (read phase)
     ///
     /// Reads storage state to determine what operations are needed.
@@ -398,11 +601,184 @@ impl EcCommitChain {
         (operations, work)
     }
 
+    /// Blocks named by any trace currently fetching a CommitBlock's blocks
+    fn referenced_blocks(&self) -> HashSet<BlockId> {
+        self.peer_logs
+            .values()
+            .filter_map(|log| match &log.current_trace {
+                Some(TraceState::FetchingBlocks { commit_block, .. }) => {
+                    Some(commit_block.committed_blocks.iter().copied())
+                }
+                _ => None,
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// Drop received blocks no trace will ever connect
+    ///
+    /// Blocks referenced by an active FetchingBlocks trace are always kept.
+    /// Others are evicted once older than the sync window, or after sitting
+    /// unreferenced for longer than orphan_ttl.
+    fn evict_stale_received_blocks(&mut self, time: EcTime) {
+        let cutoff = time.saturating_sub(self.config.sync_target);
+        let orphan_ttl = self.config.orphan_ttl;
+
+        let referenced = self.referenced_blocks();
+
+        let received_at = &mut self.received_at;
+        self.received_blocks.retain(|id, block| {
+            let arrived = *received_at.entry(*id).or_insert(time);
+            referenced.contains(id)
+                || (block.time >= cutoff && time.saturating_sub(arrived) <= orphan_ttl)
+        });
+        self.received_at
+            .retain(|id, _| self.received_blocks.contains_key(id));
+    }
+
+    /// Remember when each pending mapping touched by this tick first appeared
+    fn track_pending<S>(&mut self, operations: &[SyncOperation], storage: &S, time: EcTime)
+    where
+        S: EcTokensV2,
+    {
+        for op in operations {
+            let SyncOperation::UpdateTokenSync { token, .. } = op else {
+                continue;
+            };
+            match storage.lookup_state(token).and_then(|s| s.pending) {
+                Some(p) => {
+                    let entry = self.pending_since.entry(*token).or_insert((p.block, time));
+                    if entry.0 != p.block {
+                        *entry = (p.block, time);
+                    }
+                }
+                None => {
+                    self.pending_since.remove(token);
+                }
+            }
+        }
+    }
+
+    /// Remove and return pending mappings older than pending_expiry_age
+    fn take_expired_pending(&mut self, time: EcTime) -> Vec<(TokenId, BlockId)> {
+        let expiry = self.config.pending_expiry_age;
+        let mut expired = Vec::new();
+        self.pending_since.retain(|token, (block, since)| {
+            let keep = time.saturating_sub(*since) <= expiry;
+            if !keep {
+                expired.push((*token, *block));
+            }
+            keep
+        });
+        expired
+    }
+
+    /// Record sync updates that conflict with stored mappings (before they are applied)
+    ///
+    /// The two-slot state machine silently drops a same-parent sibling that is
+    /// older than current, and replaces or ignores one that races pending.
+    /// Either way we keep the conflicting mapping as evidence.
+    fn record_fraud_evidence<S>(&mut self, operations: &[SyncOperation], storage: &S, time: EcTime)
+    where
+        S: EcTokensV2,
+    {
+        for op in operations {
+            let SyncOperation::UpdateTokenSync {
+                token,
+                block,
+                parent,
+                time: block_time,
+                source_peer,
+            } = op
+            else {
+                continue;
+            };
+
+            let Some(state) = storage.lookup_state(token) else {
+                continue;
+            };
+
+            let existing = state
+                .current
+                .map(|c| (c.block, c.parent))
+                .into_iter()
+                .chain(state.pending.map(|p| (p.block, p.parent)));
+
+            for (existing_block, existing_parent) in existing {
+                if *parent != existing_parent || *block == existing_block {
+                    continue;
+                }
+
+                let entries = self.fraud_log.entry(*token).or_default();
+                if entries
+                    .iter()
+                    .any(|e| e.block == *block && e.reporter == *source_peer)
+                {
+                    continue;
+                }
+
+                entries.push(FraudEvidence {
+                    block: *block,
+                    parent: *parent,
+                    time: *block_time,
+                    reporter: *source_peer,
+                    conflicts_with: existing_block,
+                    recorded_at: time,
+                });
+            }
+        }
+    }
+
+    /// Drop fraud evidence older than fraud_log_retention
+    fn prune_fraud_log(&mut self, time: EcTime) {
+        let retention = self.config.fraud_log_retention;
+        self.fraud_log.retain(|_, entries| {
+            entries.retain(|e| time.saturating_sub(e.recorded_at) <= retention);
+            !entries.is_empty()
+        });
+    }
+
+    /// Sync updates the batch will ignore, as (token, reporting peer) (read phase)
+    ///
+    /// Replays the two-slot state machine over stored state in operation order,
+    /// so updates to the same token within a tick see each other.
+    fn rejected_sync_updates<S>(
+        operations: &[SyncOperation],
+        storage: &S,
+        resolution: ConflictResolution,
+    ) -> Vec<(TokenId, PeerId)>
+    where
+        S: EcTokensV2,
+    {
+        let mut states: HashMap<TokenId, TokenState> = HashMap::new();
+        let mut rejected = Vec::new();
+        for op in operations {
+            let SyncOperation::UpdateTokenSync {
+                token,
+                block,
+                parent,
+                time,
+                source_peer,
+            } = op
+            else {
+                continue;
+            };
+            let state = states
+                .entry(*token)
+                .or_insert_with(|| storage.lookup_state(token).unwrap_or_default());
+            if !state.apply_sync_update(*block, *parent, *time, *source_peer, resolution) {
+                rejected.push((*token, *source_peer));
+            }
+        }
+        rejected
+    }
+
     /// Apply sync operations to batch and mempool (write phase)
     fn apply_sync_operations(
         operations: &[SyncOperation],
         batch: &mut dyn StorageBatch,
         mempool: &mut EcMemPool,
+        resolution: ConflictResolution,
         time: EcTime,
     ) {
         for op in operations {
@@ -414,7 +790,14 @@ impl EcCommitChain {
                     time: block_time,
                     source_peer,
                 } => {
-                    batch.update_token_sync(token, block, parent, *block_time, *source_peer);
+                    batch.update_token_sync(
+                        token,
+                        block,
+                        parent,
+                        *block_time,
+                        *source_peer,
+                        resolution,
+                    );
                 }
                 SyncOperation::SaveBlock(block) => {
                     batch.save_block(block);
@@ -426,6 +809,52 @@ impl EcCommitChain {
         }
     }
 
+    /// Report what a committed tick batch applied
+    fn log_committed(
+        &self,
+        operations: &[SyncOperation],
+        work: &[(PeerId, CommitBlock)],
+        rejected: &[(TokenId, PeerId)],
+        expired: &[(TokenId, BlockId)],
+        time: EcTime,
+        event_sink: &mut dyn EventSink,
+    ) {
+        // A peer has at most one CommitBlock in flight, so its updates all came from it
+        for (peer, commit_block) in work {
+            let updates = operations
+                .iter()
+                .filter(|op| {
+                    matches!(op, SyncOperation::UpdateTokenSync { source_peer, .. }
+                        if source_peer == peer)
+                })
+                .count();
+            let ignored = rejected.iter().filter(|(_, p)| p == peer).count();
+            let tokens = updates - ignored;
+            if tokens > 0 {
+                let event = Event::SyncApplied {
+                    peer: *peer,
+                    commit_block: commit_block.id,
+                    tokens,
+                };
+                event_sink.log(time, self.peer_id, event);
+            }
+        }
+        for (token, peer) in rejected {
+            let event = Event::SyncRejected {
+                token: *token,
+                peer: *peer,
+            };
+            event_sink.log(time, self.peer_id, event);
+        }
+        for (token, block) in expired {
+            let event = Event::PendingExpired {
+                token: *token,
+                block: *block,
+            };
+            event_sink.log(time, self.peer_id, event);
+        }
+    }
+
     /// Update peer logs after processing (advance traces, update watermark)
     fn update_peer_logs_after_sync(&mut self, work: Vec<(PeerId, CommitBlock)>, time: EcTime) {
         let cutoff = time.saturating_sub(self.config.sync_target);
@@ -472,15 +901,18 @@ impl EcCommitChain {
     ) -> CommitBlock {
         let previous = backend.get_head().unwrap_or(GENESIS_BLOCK_ID);
 
-        // Generate ID (in production: Blake3 hash)
-        use std::collections::hash_map::RandomState;
-        use std::hash::{BuildHasher, Hash, Hasher};
-        let random_state = RandomState::new();
-        let mut hasher = random_state.build_hasher();
-        self.peer_id.hash(&mut hasher);
-        time.hash(&mut hasher);
-        previous.hash(&mut hasher);
-        let id = hasher.finish();
+        // Content-addressed ID: Blake3(previous || time || sorted(committed_blocks))
+        let mut sorted_blocks = committed_blocks.clone();
+        sorted_blocks.sort_unstable();
+
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&previous.to_le_bytes());
+        hasher.update(&time.to_le_bytes());
+        for block_id in &sorted_blocks {
+            hasher.update(&block_id.to_le_bytes());
+        }
+        // Lower 64 bits until CommitBlockId becomes a full hash
+        let id = u64::from_le_bytes(hasher.finalize().as_bytes()[0..8].try_into().unwrap());
 
         CommitBlock::new(id, previous, time, committed_blocks)
     }
@@ -491,6 +923,10 @@ impl EcCommitChain {
 
     /// Main tick function
     ///
+    /// Reports applied and rejected sync mappings and expired pending mappings
+    /// to `event_sink`.
+    /// Does nothing unless `time` is a multiple of `sync_interval`.
+    ///
     /// Returns list of (receiver, message_type, data) for node to send
     pub fn tick<S>(
         &mut self,
@@ -498,39 +934,62 @@ impl EcCommitChain {
         storage: &mut S,
         mempool: &mut EcMemPool,
         time: EcTime,
+        event_sink: &mut dyn EventSink,
     ) -> Vec<(PeerId, TickMessage)>
     where
         S: EcTokensV2 + crate::ec_interface::BatchedBackend,
     {
         let mut messages = Vec::new();
+        if !time.is_multiple_of(self.config.sync_interval.max(1)) {
+            return messages;
+        }
 
-        // Update tracked peers (drop inactive, add new if below 4)
+        // Update tracked peers (drop inactive, add new if below max_tracked_peers)
         self.update_tracked_peers(peers);
 
         // Phase 1: Collect operations (reads storage, no mutations)
         let (operations, work) = self.collect_sync_operations(storage);
+        let rejected =
+            Self::rejected_sync_updates(&operations, storage, self.config.conflict_resolution);
+        self.record_fraud_evidence(&operations, storage, time);
+        self.prune_fraud_log(time);
 
         // Phase 2: Create batch and apply operations
         let mut batch = storage.begin_batch();
 
         // Apply collected sync operations
-        Self::apply_sync_operations(&operations, &mut *batch, mempool, time);
+        Self::apply_sync_operations(
+            &operations,
+            &mut *batch,
+            mempool,
+            self.config.conflict_resolution,
+            time,
+        );
 
         // Save blocks in our block-id range (no tokens in range)
         for block in self.blocks_to_store.values() {
             batch.save_block(block);
         }
 
+        // Drop pending mappings no second peer confirmed in time
+        let expired = self.take_expired_pending(time);
+        for (token, block) in &expired {
+            batch.expire_pending(token, block);
+        }
+
         // Commit batch
         if let Err(e) = batch.commit() {
             eprintln!("Error committing batch: {:?}", e);
         } else {
             // Clear blocks_to_store on successful commit
             self.blocks_to_store.clear();
+            self.log_committed(&operations, &work, &rejected, &expired, time, event_sink);
         }
+        self.track_pending(&operations, storage, time);
 
         // Phase 3: Update peer logs (advance traces, update watermark)
         self.update_peer_logs_after_sync(work, time);
+        self.evict_stale_received_blocks(time);
 
         // Generate requests for each peer's trace
 
@@ -590,15 +1049,20 @@ impl EcCommitChain {
             messages.push((peer_id, TickMessage::QueryCommitBlock { block_id, ticket }));
         }
 
-        for (peer_id, blocks) in query_blocks {
-            for block_id in blocks {
+        // One request per peer: a lone block keeps the plain query, gaps go as a batch
+        for (peer_id, mut blocks) in query_blocks {
+            if let [block_id] = blocks[..] {
                 let ticket = self.generate_ticket(block_id);
                 messages.push((peer_id, TickMessage::QueryBlock { block_id, ticket }));
+            } else {
+                blocks.sort_unstable();
+                messages.push((peer_id, TickMessage::QueryBlockBatch { block_ids: blocks }));
             }
         }
 
-        // Update tick counters
+        // Update tick counters and refill each peer's parent request budget
         for log in self.peer_logs.values_mut() {
+            log.parent_requests_this_tick = 0;
             if let Some(TraceState::WaitingForCommit { ticks_waiting, .. }) = &mut log.current_trace
             {
                 *ticks_waiting += 1;
@@ -613,11 +1077,28 @@ impl EcCommitChain {
         std::mem::take(&mut self.blocks_to_store)
     }
 
+    /// Configuration this chain runs with
+    pub fn config(&self) -> &CommitChainConfig {
+        &self.config
+    }
+
+    /// Replace the configuration; tracked peers and traces are kept
+    pub fn set_config(&mut self, config: CommitChainConfig) {
+        self.config = config;
+    }
+
     /// Get current watermark (how far back we've synced)
     pub fn watermark(&self) -> EcTime {
         self.watermark
     }
 
+    /// Conflicting mappings recorded during sync (within fraud_log_retention)
+    pub fn fraud_evidence(&self) -> impl Iterator<Item = (&TokenId, &FraudEvidence)> {
+        self.fraud_log
+            .iter()
+            .flat_map(|(token, entries)| entries.iter().map(move |e| (token, e)))
+    }
+
     /// Get number of active traces
     pub fn active_traces(&self) -> usize {
         self.peer_logs
@@ -625,6 +1106,58 @@ impl EcCommitChain {
             .filter(|log| log.current_trace.is_some())
             .count()
     }
+
+    /// Read-only snapshot of sync progress (for dashboards and simulators)
+    ///
+    /// Pending/confirmed token mappings live in token storage, not here.
+    pub fn sync_stats(&self) -> SyncStats {
+        let mut peers: Vec<PeerSyncStats> = self
+            .peer_logs
+            .iter()
+            .map(|(peer_id, log)| {
+                let (trace, blocks_in_commit, blocks_waiting) = match &log.current_trace {
+                    None => (TracePhase::Idle, 0, 0),
+                    Some(TraceState::WaitingForCommit { .. }) => {
+                        (TracePhase::WaitingForCommit, 0, 0)
+                    }
+                    Some(TraceState::FetchingBlocks {
+                        commit_block,
+                        waiting_for,
+                    }) => (
+                        TracePhase::FetchingBlocks,
+                        commit_block.committed_blocks.len(),
+                        waiting_for.len(),
+                    ),
+                };
+                PeerSyncStats {
+                    peer_id: *peer_id,
+                    known_head: log.known_head,
+                    trace,
+                    blocks_in_commit,
+                    blocks_waiting,
+                }
+            })
+            .collect();
+        peers.sort_by_key(|p| p.peer_id);
+
+        let referenced = self.referenced_blocks();
+        let orphaned_blocks = self
+            .received_blocks
+            .keys()
+            .filter(|id| !referenced.contains(id))
+            .count();
+
+        SyncStats {
+            tracked_peers: peers.len(),
+            peers,
+            received_blocks: self.received_blocks.len(),
+            orphaned_blocks,
+            blocks_to_store: self.blocks_to_store.len(),
+            pending_mappings: self.pending_since.len(),
+            fraud_evidence: self.fraud_log.values().map(Vec::len).sum(),
+            watermark: self.watermark,
+        }
+    }
 }
 
 // ============================================================================
@@ -641,6 +1174,11 @@ pub enum TickMessage {
         block_id: BlockId,
         ticket: MessageTicket,
     },
+    /// Every block a trace is still waiting for, asked of the same peer at once
+    ///
+    /// Carries no ticket: the node issues one keyed on the queried peer, and
+    /// `handle_block_batch` accepts only the blocks that peer was asked for.
+    QueryBlockBatch { block_ids: Vec<BlockId> },
 }
 
 // ============================================================================
@@ -651,8 +1189,8 @@ pub enum TickMessage {
 mod tests {
     use super::*;
     use crate::ec_interface::{
-        BatchedBackend, BlockTime, EcTokens, PendingMapping, TokenId, TokenSignature, TokenState,
-        TrustSource, TrustedMapping,
+        BatchedBackend, BlockTime, EcTokens, NoOpSink, PendingMapping, TokenId, TokenSignature,
+        TokenState, TrustSource, TrustedMapping,
     };
     use crate::ec_peers::EcPeers;
     use std::collections::{HashMap, HashSet};
@@ -713,8 +1251,16 @@ mod tests {
     struct MockBatch<'a> {
         storage: &'a mut MockTokenStorage,
         blocks: Vec<Block>,
-        sync_updates: Vec<(TokenId, BlockId, BlockId, EcTime, PeerId)>,
+        sync_updates: Vec<(
+            TokenId,
+            BlockId,
+            BlockId,
+            EcTime,
+            PeerId,
+            ConflictResolution,
+        )>,
         local_updates: Vec<(TokenId, BlockId, BlockId, EcTime)>,
+        expired_pending: Vec<(TokenId, BlockId)>,
     }
 
     impl<'a> StorageBatch for MockBatch<'a> {
@@ -739,11 +1285,18 @@ mod tests {
             parent: &BlockId,
             time: EcTime,
             source_peer: PeerId,
+            resolution: ConflictResolution,
         ) {
             self.sync_updates
-                .push((*token, *block, *parent, time, source_peer));
+                .push((*token, *block, *parent, time, source_peer, resolution));
+        }
+
+        fn expire_pending(&mut self, token: &TokenId, block: &BlockId) {
+            self.expired_pending.push((*token, *block));
         }
 
+        fn rollback(self: Box<Self>) {}
+
         fn commit(self: Box<Self>) -> Result<(), Box<dyn std::error::Error>> {
             // Apply local updates (become Local)
             for (token, block, parent, time) in &self.local_updates {
@@ -751,7 +1304,7 @@ mod tests {
             }
 
             // Apply sync updates (two-slot logic)
-            for (token, block, parent, time, source_peer) in &self.sync_updates {
+            for (token, block, parent, time, source_peer, resolution) in &self.sync_updates {
                 let state = self.storage.tokens.entry(*token).or_default();
 
                 match (&state.current, &state.pending) {
@@ -774,8 +1327,8 @@ mod tests {
                                 source: TrustSource::Confirmed,
                             });
                             state.pending = None;
-                        } else if *block > p.block {
-                            // Higher ID replaces pending
+                        } else if resolution.replaces_pending(*block, *time, p) {
+                            // Preferred mapping replaces pending
                             state.pending = Some(PendingMapping {
                                 block: *block,
                                 parent: *parent,
@@ -809,7 +1362,7 @@ mod tests {
                                 });
                                 state.pending = None;
                             }
-                            Some(p) if *block > p.block => {
+                            Some(p) if resolution.replaces_pending(*block, *time, p) => {
                                 state.pending = Some(PendingMapping {
                                     block: *block,
                                     parent: *parent,
@@ -822,6 +1375,17 @@ mod tests {
                     }
                 }
             }
+
+            for (token, block) in &self.expired_pending {
+                if let Some(state) = self.storage.tokens.get_mut(token) {
+                    if state.pending.map(|p| p.block) == Some(*block) {
+                        state.pending = None;
+                        if state.current.is_none() {
+                            self.storage.tokens.remove(token);
+                        }
+                    }
+                }
+            }
             Ok(())
         }
 
@@ -837,6 +1401,7 @@ mod tests {
                 blocks: Vec::new(),
                 sync_updates: Vec::new(),
                 local_updates: Vec::new(),
+                expired_pending: Vec::new(),
             })
         }
     }
@@ -861,7 +1426,14 @@ mod tests {
         // Apply from peer 1 via batch
         {
             let mut batch = storage.begin_batch();
-            batch.update_token_sync(&50, &100, &GENESIS_BLOCK_ID, 1000, 1);
+            batch.update_token_sync(
+                &50,
+                &100,
+                &GENESIS_BLOCK_ID,
+                1000,
+                1,
+                ConflictResolution::HighestBlockId,
+            );
             batch.commit().unwrap();
         }
 
@@ -874,7 +1446,14 @@ mod tests {
         // Apply same block from peer 2 (confirmation)
         {
             let mut batch = storage.begin_batch();
-            batch.update_token_sync(&50, &100, &GENESIS_BLOCK_ID, 1000, 2);
+            batch.update_token_sync(
+                &50,
+                &100,
+                &GENESIS_BLOCK_ID,
+                1000,
+                2,
+                ConflictResolution::HighestBlockId,
+            );
             batch.commit().unwrap();
         }
 
@@ -893,14 +1472,28 @@ mod tests {
         // Apply block 100 from peer 1
         {
             let mut batch = storage.begin_batch();
-            batch.update_token_sync(&50, &100, &GENESIS_BLOCK_ID, 1000, 1);
+            batch.update_token_sync(
+                &50,
+                &100,
+                &GENESIS_BLOCK_ID,
+                1000,
+                1,
+                ConflictResolution::HighestBlockId,
+            );
             batch.commit().unwrap();
         }
 
         // Apply block 200 from peer 2 (higher ID wins)
         {
             let mut batch = storage.begin_batch();
-            batch.update_token_sync(&50, &200, &GENESIS_BLOCK_ID, 1000, 2);
+            batch.update_token_sync(
+                &50,
+                &200,
+                &GENESIS_BLOCK_ID,
+                1000,
+                2,
+                ConflictResolution::HighestBlockId,
+            );
             batch.commit().unwrap();
         }
 
@@ -912,7 +1505,14 @@ mod tests {
         // Apply block 200 from peer 3 (confirmation)
         {
             let mut batch = storage.begin_batch();
-            batch.update_token_sync(&50, &200, &GENESIS_BLOCK_ID, 1000, 3);
+            batch.update_token_sync(
+                &50,
+                &200,
+                &GENESIS_BLOCK_ID,
+                1000,
+                3,
+                ConflictResolution::HighestBlockId,
+            );
             batch.commit().unwrap();
         }
 
@@ -953,6 +1553,7 @@ mod tests {
                     waiting_for: [block.id].into_iter().collect(),
                 }),
                 first_commit_time: Some(1000),
+                parent_requests_this_tick: 0,
             },
         );
 
@@ -971,6 +1572,82 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_conflicting_sync_mapping_recorded_and_expired() {
+        use crate::ec_interface::{TokenBlock, TOKENS_PER_BLOCK};
+
+        let my_range = PeerRange::new(0, 1000);
+        let config = CommitChainConfig {
+            fraud_log_retention: 100,
+            ..Default::default()
+        };
+        let mut chain = EcCommitChain::new(500, my_range, config);
+        let mut peers = EcPeers::new(500);
+        let mut mempool = EcMemPool::new();
+
+        // Confirmed mapping: block 200 spends parent 100
+        let mut storage = MockTokenStorage::new();
+        storage.tokens.insert(
+            50,
+            TokenState {
+                current: Some(TrustedMapping {
+                    block: 200,
+                    parent: 100,
+                    time: 900,
+                    source: TrustSource::Confirmed,
+                }),
+                pending: None,
+            },
+        );
+
+        // Peer 42 reports an older block spending the same parent
+        let mut block = Block {
+            id: 150,
+            time: 950,
+            used: 1,
+            parts: [TokenBlock::default(); TOKENS_PER_BLOCK],
+            signatures: [None; TOKENS_PER_BLOCK],
+        };
+        block.parts[0].token = 50;
+        block.parts[0].last = 100;
+        chain.received_blocks.insert(block.id, block);
+
+        let commit_block = CommitBlock::new(999, GENESIS_BLOCK_ID, 1000, vec![block.id]);
+        peers.update_peer(&42, 0);
+        peers.update_peer_commit_chain_head(&42, commit_block.id);
+        chain.peer_logs.insert(
+            42,
+            PeerChainLog {
+                _peer_id: 42,
+                known_head: Some(commit_block.id),
+                current_trace: Some(TraceState::FetchingBlocks {
+                    commit_block,
+                    waiting_for: [block.id].into_iter().collect(),
+                }),
+                first_commit_time: Some(1000),
+                parent_requests_this_tick: 0,
+            },
+        );
+
+        chain.tick(&peers, &mut storage, &mut mempool, 1000, &mut NoOpSink);
+
+        let evidence: Vec<_> = chain.fraud_evidence().collect();
+        assert_eq!(evidence.len(), 1);
+        let (token, e) = evidence[0];
+        assert_eq!(*token, 50);
+        assert_eq!((e.block, e.parent, e.time, e.reporter), (150, 100, 950, 42));
+        assert_eq!(e.conflicts_with, 200);
+
+        // Current mapping is untouched by the rejected sibling
+        assert_eq!(storage.lookup_current(&50).unwrap().block, 200);
+
+        // Still retained at the edge of the window, gone after it
+        chain.tick(&peers, &mut storage, &mut mempool, 1100, &mut NoOpSink);
+        assert_eq!(chain.fraud_evidence().count(), 1);
+        chain.tick(&peers, &mut storage, &mut mempool, 1101, &mut NoOpSink);
+        assert_eq!(chain.fraud_evidence().count(), 0);
+    }
+
     #[test]
     fn test_update_tracked_peers_skips_active_peers_without_heads() {
         let my_range = PeerRange::new(0, 1000);
@@ -987,6 +1664,499 @@ mod tests {
         assert!(chain.peer_logs.contains_key(&120));
     }
 
+    #[test]
+    fn test_max_tracked_peers_fills_configured_slots() {
+        let my_range = PeerRange::new(0, 1000);
+        let config = CommitChainConfig {
+            max_tracked_peers: 8,
+            ..Default::default()
+        };
+        let mut chain = EcCommitChain::new(500, my_range.clone(), config);
+        let mut peers = EcPeers::new(500);
+
+        for i in 1..=6 {
+            for peer_id in [500 + i * 10, 500 - i * 10] {
+                peers.update_peer(&peer_id, 0);
+                peers.update_peer_commit_chain_head(&peer_id, 999);
+            }
+        }
+
+        chain.update_tracked_peers(&peers);
+        assert_eq!(chain.peer_logs.len(), 8);
+
+        // Default config still caps at 4
+        let mut chain = EcCommitChain::new(500, my_range, CommitChainConfig::default());
+        chain.update_tracked_peers(&peers);
+        assert_eq!(chain.peer_logs.len(), 4);
+    }
+
+    #[test]
+    fn test_unreferenced_received_blocks_evicted_after_ttl() {
+        use crate::ec_interface::{TokenBlock, TOKENS_PER_BLOCK};
+
+        let my_range = PeerRange::new(0, 1000);
+        let config = CommitChainConfig {
+            sync_target: 500,
+            orphan_ttl: 100,
+            ..Default::default()
+        };
+        let mut chain = EcCommitChain::new(500, my_range, config);
+
+        let block_at = |id: BlockId, time: EcTime| Block {
+            id,
+            time,
+            used: 0,
+            parts: [TokenBlock::default(); TOKENS_PER_BLOCK],
+            signatures: [None; TOKENS_PER_BLOCK],
+        };
+
+        // 10: routed to us, no trace references it
+        // 20: part of an active trace still waiting for 30
+        // 5: older than the sync window
+        for block in [block_at(10, 1000), block_at(20, 1000), block_at(5, 100)] {
+            chain.received_blocks.insert(block.id, block);
+        }
+
+        let commit_block = CommitBlock::new(999, 800, 1000, vec![20, 30]);
+        chain.peer_logs.insert(
+            42,
+            PeerChainLog {
+                _peer_id: 42,
+                known_head: Some(commit_block.id),
+                current_trace: Some(TraceState::FetchingBlocks {
+                    commit_block,
+                    waiting_for: [30].into_iter().collect(),
+                }),
+                first_commit_time: Some(1000),
+                parent_requests_this_tick: 0,
+            },
+        );
+
+        chain.evict_stale_received_blocks(1000);
+        assert!(!chain.received_blocks.contains_key(&5));
+        assert!(chain.received_blocks.contains_key(&10));
+        assert!(chain.received_blocks.contains_key(&20));
+
+        chain.evict_stale_received_blocks(1101);
+        assert!(!chain.received_blocks.contains_key(&10));
+        assert!(chain.received_blocks.contains_key(&20));
+        assert_eq!(chain.received_at.len(), chain.received_blocks.len());
+    }
+
+    #[test]
+    fn test_commit_block_ids_are_content_addressed() {
+        use crate::ec_memory_backend::MemCommitChain;
+
+        let chain_a = EcCommitChain::new(100, PeerRange::new(0, 1000), Default::default());
+        let chain_b = EcCommitChain::new(200, PeerRange::new(0, 1000), Default::default());
+        let mut backend_a = MemCommitChain::new();
+        let mut backend_b = MemCommitChain::new();
+
+        // Same committed blocks (in a different order) at the same times
+        for (time, blocks_a, blocks_b) in
+            [(10, vec![1, 2, 3], vec![3, 1, 2]), (20, vec![7], vec![7])]
+        {
+            let a = chain_a.create_commit_block(&backend_a, blocks_a, time);
+            let b = chain_b.create_commit_block(&backend_b, blocks_b, time);
+            assert_eq!(a.id, b.id);
+            assert_eq!(a.previous, b.previous);
+            assert_ne!(a.id, GENESIS_BLOCK_ID);
+
+            backend_a.save(&a);
+            backend_a.set_head(&a.id);
+            backend_b.save(&b);
+            backend_b.set_head(&b.id);
+        }
+
+        // Different content gives a different id
+        let c = chain_a.create_commit_block(&backend_a, vec![8], 30);
+        let d = chain_a.create_commit_block(&backend_a, vec![9], 30);
+        assert_ne!(c.id, d.id);
+    }
+
+    #[test]
+    fn test_sync_stats_reflect_trace_state() {
+        use crate::ec_interface::{TokenBlock, TOKENS_PER_BLOCK};
+
+        let my_range = PeerRange::new(0, 1000);
+        let mut chain = EcCommitChain::new(500, my_range, CommitChainConfig::default());
+        let mut peers = EcPeers::new(500);
+
+        peers.update_peer(&510, 0);
+        peers.update_peer_commit_chain_head(&510, 999);
+        chain.update_tracked_peers(&peers);
+
+        let stats = chain.sync_stats();
+        assert_eq!(stats.tracked_peers, 1);
+        assert_eq!(stats.peers[0].peer_id, 510);
+        assert_eq!(stats.peers[0].known_head, Some(999));
+        assert_eq!(stats.peers[0].trace, TracePhase::Idle);
+
+        // Peer 510 is fetching blocks 20 and 30; 20 and an unrelated 10 arrive
+        chain.peer_logs.get_mut(&510).unwrap().current_trace = Some(TraceState::FetchingBlocks {
+            commit_block: CommitBlock::new(999, 800, 1000, vec![20, 30]),
+            waiting_for: [20, 30].into_iter().collect(),
+        });
+        for id in [10, 20] {
+            let block = Block {
+                id,
+                time: 1000,
+                used: 0,
+                parts: [TokenBlock::default(); TOKENS_PER_BLOCK],
+                signatures: [None; TOKENS_PER_BLOCK],
+            };
+            chain.handle_block(block, 0);
+        }
+
+        let stats = chain.sync_stats();
+        assert_eq!(stats.peers[0].trace, TracePhase::FetchingBlocks);
+        assert_eq!(stats.peers[0].blocks_in_commit, 2);
+        assert_eq!(stats.peers[0].blocks_waiting, 2);
+        assert_eq!(stats.received_blocks, 2);
+        assert_eq!(stats.orphaned_blocks, 1);
+        assert_eq!(stats.fraud_evidence, 0);
+    }
+
+    #[test]
+    fn test_unconfirmed_pending_expires_after_pending_expiry_age() {
+        use crate::ec_interface::{TokenBlock, TOKENS_PER_BLOCK};
+
+        let my_range = PeerRange::new(0, 1000);
+        let config = CommitChainConfig {
+            pending_expiry_age: 100,
+            ..Default::default()
+        };
+        let mut chain = EcCommitChain::new(500, my_range, config);
+        let mut peers = EcPeers::new(500);
+        let mut mempool = EcMemPool::new();
+        let mut storage = MockTokenStorage::new();
+
+        peers.update_peer(&42, 0);
+        peers.update_peer_commit_chain_head(&42, 999);
+
+        // Peer 42 delivers a block carrying `token` through a one-block trace
+        let deliver = |chain: &mut EcCommitChain, id: BlockId, token: TokenId| {
+            let mut block = Block {
+                id,
+                time: 900,
+                used: 1,
+                parts: [TokenBlock::default(); TOKENS_PER_BLOCK],
+                signatures: [None; TOKENS_PER_BLOCK],
+            };
+            block.parts[0].token = token;
+            chain.received_blocks.insert(id, block);
+            chain.peer_logs.insert(
+                42,
+                PeerChainLog {
+                    _peer_id: 42,
+                    known_head: Some(999),
+                    current_trace: Some(TraceState::FetchingBlocks {
+                        commit_block: CommitBlock::new(999, GENESIS_BLOCK_ID, 900, vec![id]),
+                        waiting_for: [id].into_iter().collect(),
+                    }),
+                    first_commit_time: Some(900),
+                    parent_requests_this_tick: 0,
+                },
+            );
+        };
+
+        // Lone unconfirmed mapping for token 50
+        deliver(&mut chain, 150, 50);
+        chain.tick(&peers, &mut storage, &mut mempool, 1000, &mut NoOpSink);
+        assert_eq!(
+            storage.lookup_state(&50).unwrap().pending.unwrap().block,
+            150
+        );
+
+        // Token 60 starts maturing later
+        deliver(&mut chain, 160, 60);
+        chain.tick(&peers, &mut storage, &mut mempool, 1050, &mut NoOpSink);
+
+        chain.tick(&peers, &mut storage, &mut mempool, 1100, &mut NoOpSink);
+        assert!(storage.lookup_state(&50).is_some());
+
+        chain.tick(&peers, &mut storage, &mut mempool, 1101, &mut NoOpSink);
+        assert!(storage.lookup_state(&50).is_none());
+        assert_eq!(
+            storage.lookup_state(&60).unwrap().pending.unwrap().block,
+            160
+        );
+    }
+
+    struct CaptureSink(Vec<Event>);
+
+    impl EventSink for CaptureSink {
+        fn log(&mut self, _round: EcTime, _peer: PeerId, event: Event) {
+            self.0.push(event);
+        }
+    }
+
+    #[test]
+    fn test_sync_events_report_applied_and_expired_mappings() {
+        use crate::ec_interface::{TokenBlock, TOKENS_PER_BLOCK};
+
+        let my_range = PeerRange::new(0, 1000);
+        let config = CommitChainConfig {
+            pending_expiry_age: 100,
+            ..Default::default()
+        };
+        let mut chain = EcCommitChain::new(500, my_range, config);
+        let mut peers = EcPeers::new(500);
+        let mut mempool = EcMemPool::new();
+        let mut storage = MockTokenStorage::new();
+        let mut sink = CaptureSink(Vec::new());
+
+        peers.update_peer(&42, 0);
+        peers.update_peer_commit_chain_head(&42, 999);
+
+        // Peer 42's head commits one block: three tokens in our range, one outside
+        let mut block = Block {
+            id: 150,
+            time: 900,
+            used: 4,
+            parts: [TokenBlock::default(); TOKENS_PER_BLOCK],
+            signatures: [None; TOKENS_PER_BLOCK],
+        };
+        for (part, token) in block.parts.iter_mut().zip([50, 60, 70, 5000]) {
+            part.token = token;
+        }
+        chain.received_blocks.insert(150, block);
+        chain.peer_logs.insert(
+            42,
+            PeerChainLog {
+                _peer_id: 42,
+                known_head: Some(999),
+                current_trace: Some(TraceState::FetchingBlocks {
+                    commit_block: CommitBlock::new(999, GENESIS_BLOCK_ID, 900, vec![150]),
+                    waiting_for: [150].into_iter().collect(),
+                }),
+                first_commit_time: Some(900),
+                parent_requests_this_tick: 0,
+            },
+        );
+
+        chain.tick(&peers, &mut storage, &mut mempool, 1000, &mut sink);
+        match &sink.0[..] {
+            [Event::SyncApplied {
+                peer: 42,
+                commit_block: 999,
+                tokens,
+            }] => assert_eq!(*tokens, 3),
+            other => panic!("expected one SyncApplied event, got {:?}", other),
+        }
+
+        // Nothing new to apply; no second peer confirms, so all three expire
+        sink.0.clear();
+        chain.tick(&peers, &mut storage, &mut mempool, 1050, &mut sink);
+        assert!(sink.0.is_empty());
+        chain.tick(&peers, &mut storage, &mut mempool, 1101, &mut sink);
+        let mut expired: Vec<_> = sink
+            .0
+            .iter()
+            .map(|event| match event {
+                Event::PendingExpired { token, block: 150 } => *token,
+                other => panic!("expected PendingExpired, got {:?}", other),
+            })
+            .collect();
+        expired.sort_unstable();
+        assert_eq!(expired, vec![50, 60, 70]);
+    }
+
+    #[test]
+    fn test_sync_events_report_rejected_mappings() {
+        use crate::ec_interface::{TokenBlock, TOKENS_PER_BLOCK};
+
+        let my_range = PeerRange::new(0, 1000);
+        let mut chain = EcCommitChain::new(500, my_range, CommitChainConfig::default());
+        let mut peers = EcPeers::new(500);
+        let mut mempool = EcMemPool::new();
+        let mut storage = MockTokenStorage::new();
+        let mut sink = CaptureSink(Vec::new());
+
+        peers.update_peer(&42, 0);
+        peers.update_peer_commit_chain_head(&42, 999);
+
+        // Token 50 already has a newer current; token 60 a higher pending from peer 7
+        storage.tokens.insert(
+            50,
+            TokenState {
+                current: Some(TrustedMapping {
+                    block: 200,
+                    parent: GENESIS_BLOCK_ID,
+                    time: 800,
+                    source: TrustSource::Confirmed,
+                }),
+                pending: None,
+            },
+        );
+        storage.tokens.insert(
+            60,
+            TokenState {
+                current: None,
+                pending: Some(PendingMapping {
+                    block: 300,
+                    parent: GENESIS_BLOCK_ID,
+                    time: 800,
+                    source_peer: 7,
+                }),
+            },
+        );
+
+        let mut block = Block {
+            id: 150,
+            time: 900,
+            used: 3,
+            parts: [TokenBlock::default(); TOKENS_PER_BLOCK],
+            signatures: [None; TOKENS_PER_BLOCK],
+        };
+        for (part, token) in block.parts.iter_mut().zip([50, 60, 70]) {
+            part.token = token;
+        }
+        chain.received_blocks.insert(150, block);
+        chain.peer_logs.insert(
+            42,
+            PeerChainLog {
+                _peer_id: 42,
+                known_head: Some(999),
+                current_trace: Some(TraceState::FetchingBlocks {
+                    commit_block: CommitBlock::new(999, GENESIS_BLOCK_ID, 900, vec![150]),
+                    waiting_for: [150].into_iter().collect(),
+                }),
+                first_commit_time: Some(900),
+                parent_requests_this_tick: 0,
+            },
+        );
+
+        chain.tick(&peers, &mut storage, &mut mempool, 1000, &mut sink);
+
+        // Only token 70 was applied; the stale and the outranked mapping were rejected
+        let mut rejected = Vec::new();
+        for event in &sink.0 {
+            match event {
+                Event::SyncApplied {
+                    peer: 42,
+                    commit_block: 999,
+                    tokens,
+                } => assert_eq!(*tokens, 1),
+                Event::SyncRejected { token, peer: 42 } => rejected.push(*token),
+                other => panic!("unexpected event {:?}", other),
+            }
+        }
+        rejected.sort_unstable();
+        assert_eq!(rejected, vec![50, 60]);
+        assert_eq!(
+            storage.lookup_state(&60).unwrap().pending.unwrap().block,
+            300
+        );
+        assert_eq!(
+            storage.lookup_state(&70).unwrap().pending.unwrap().block,
+            150
+        );
+    }
+
+    #[test]
+    fn test_missing_blocks_requested_as_one_batch() {
+        let my_range = PeerRange::new(0, 1000);
+        let mut chain = EcCommitChain::new(500, my_range, CommitChainConfig::default());
+        let mut peers = EcPeers::new(500);
+        let mut mempool = EcMemPool::new();
+        let mut storage = MockTokenStorage::new();
+
+        peers.update_peer(&42, 0);
+        peers.update_peer_commit_chain_head(&42, 999);
+
+        let missing = vec![10, 20, 30, 40, 50];
+        chain.peer_logs.insert(
+            42,
+            PeerChainLog {
+                _peer_id: 42,
+                known_head: Some(999),
+                current_trace: Some(TraceState::FetchingBlocks {
+                    commit_block: CommitBlock::new(999, 800, 900, missing.clone()),
+                    waiting_for: missing.iter().copied().collect(),
+                }),
+                first_commit_time: Some(900),
+                parent_requests_this_tick: 0,
+            },
+        );
+
+        let messages = chain.tick(&peers, &mut storage, &mut mempool, 1000, &mut NoOpSink);
+        assert_eq!(messages.len(), 1);
+        match &messages[0] {
+            (42, TickMessage::QueryBlockBatch { block_ids }) => {
+                assert_eq!(*block_ids, missing);
+            }
+            other => panic!("expected one batch request to peer 42, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_block_batch_keeps_only_requested_blocks() {
+        use crate::ec_interface::{TokenBlock, TOKENS_PER_BLOCK};
+
+        let my_range = PeerRange::new(0, 1000);
+        let mut chain = EcCommitChain::new(500, my_range, CommitChainConfig::default());
+        let waiting = vec![10, 20, 30];
+        chain.peer_logs.insert(
+            42,
+            PeerChainLog {
+                _peer_id: 42,
+                known_head: Some(999),
+                current_trace: Some(TraceState::FetchingBlocks {
+                    commit_block: CommitBlock::new(999, 800, 900, waiting.clone()),
+                    waiting_for: waiting.iter().copied().collect(),
+                }),
+                first_commit_time: Some(900),
+                parent_requests_this_tick: 0,
+            },
+        );
+        let block = |id: BlockId| Block {
+            id,
+            time: 900,
+            used: 0,
+            parts: [TokenBlock::default(); TOKENS_PER_BLOCK],
+            signatures: [None; TOKENS_PER_BLOCK],
+        };
+
+        // Unrequested ids and repeats are dropped
+        let reply: Vec<Block> = [10, 11, 20, 20, 12, 13].into_iter().map(block).collect();
+        assert_eq!(chain.handle_block_batch(42, &reply), 2);
+        let mut received: Vec<BlockId> = chain.received_blocks.keys().copied().collect();
+        received.sort_unstable();
+        assert_eq!(received, vec![10, 20]);
+
+        // Only the sender's own trace counts, and arrived blocks are not taken again
+        assert_eq!(chain.handle_block_batch(43, &[block(30)]), 0);
+        assert_eq!(chain.handle_block_batch(42, &[block(10), block(30)]), 1);
+        assert_eq!(chain.received_blocks.len(), 3);
+    }
+
+    #[test]
+    fn test_tickets_depend_on_instance_secret() {
+        let my_range = PeerRange::new(0, 1000);
+        let chain_a = EcCommitChain::new(500, my_range.clone(), CommitChainConfig::default());
+        let chain_b = EcCommitChain::new(500, my_range.clone(), CommitChainConfig::default());
+
+        // Same peer id, independent secrets
+        let ticket = chain_a.generate_ticket(42);
+        assert_ne!(ticket, chain_b.generate_ticket(42));
+        assert!(chain_a.verify_ticket(42, ticket));
+        assert!(!chain_b.verify_ticket(42, ticket));
+        assert!(!chain_a.verify_ticket(43, ticket));
+
+        // A fixed secret reproduces tickets
+        let seeded =
+            |secret| EcCommitChain::with_secret(500, my_range.clone(), Default::default(), secret);
+        assert_eq!(
+            seeded([7u8; 32]).generate_ticket(42),
+            seeded([7u8; 32]).generate_ticket(42)
+        );
+        assert_ne!(
+            seeded([7u8; 32]).generate_ticket(42),
+            seeded([8u8; 32]).generate_ticket(42)
+        );
+    }
+
     #[test]
     fn test_empty_waiting_for_advances_trace_without_new_blocks() {
         let my_range = PeerRange::new(0, 1000);
@@ -1003,6 +2173,7 @@ mod tests {
                     waiting_for: HashSet::new(),
                 }),
                 first_commit_time: Some(commit_block.time),
+                parent_requests_this_tick: 0,
             },
         );
 
@@ -143,9 +143,15 @@ pub enum BlockUseCase {
 // ============================================================================
 
 pub const TOKENS_PER_BLOCK: usize = 6;
-/// Number of tokens in a proof-of-storage signature response
-/// This should match SIGNATURE_CHUNKS in ec_tokens.rs (10 chunks)
-pub const TOKENS_SIGNATURE_SIZE: usize = 10;
+/// Maximum number of tokens in a proof-of-storage signature response
+///
+/// The active count is set at runtime by `SignatureParams::chunks` in
+/// ec_proof_of_storage.rs (default 10). Slots past the active count are zero.
+///
+/// This sizes the `Message::Answer` array in both the wire codec and serde, so
+/// changing it breaks compatibility with nodes built with another value (see
+/// ec_wire.rs).
+pub const TOKENS_SIGNATURE_SIZE: usize = 16;
 
 // block can not claim to be further into the future
 pub const SOME_STEPS_INTO_THE_FUTURE: EcTime = 100;
@@ -153,14 +159,14 @@ pub const SOME_STEPS_INTO_THE_FUTURE: EcTime = 100;
 pub const VOTE_THRESHOLD: i64 = 2;
 
 // TODO bad name
-#[derive(Copy, Clone, PartialEq, Debug, Default)]
+#[derive(Copy, Clone, PartialEq, Debug, Default, serde::Serialize, serde::Deserialize)]
 pub struct TokenBlock {
     pub token: TokenId,
     pub last: BlockId,
     pub key: PublicKeyReference,
 }
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Block {
     pub id: BlockId,
     pub time: EcTime,
@@ -171,6 +177,86 @@ pub struct Block {
     pub signatures: [Option<Signature>; TOKENS_PER_BLOCK],
 }
 
+/// Structural problems found by `Block::validate`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockError {
+    /// `used` is not below `TOKENS_PER_BLOCK`
+    UsedOutOfRange { used: u8 },
+    /// Part `index` is inside `0..used` but has token 0
+    MissingToken { index: usize },
+    /// Part or signature `index` is at or beyond `used` but not zeroed
+    UnusedPartSet { index: usize },
+}
+
+impl Block {
+    /// Build a block whose id is derived from its contents (see `compute_id`)
+    pub fn with_computed_id(
+        time: EcTime,
+        used: u8,
+        parts: [TokenBlock; TOKENS_PER_BLOCK],
+        signatures: [Option<Signature>; TOKENS_PER_BLOCK],
+    ) -> Self {
+        let mut block = Self {
+            id: 0,
+            time,
+            used,
+            parts,
+            signatures,
+        };
+        block.id = block.compute_id();
+        block
+    }
+
+    /// Content-addressed id: first 8 bytes (LE) of Blake3 over the block
+    ///
+    /// Hashes `time`, `used` and the active `parts`/`signatures` in order, so
+    /// peers building the same transaction get the same id. The current `id`
+    /// and anything past `used` are ignored. Opt-in: random ids stay valid.
+    pub fn compute_id(&self) -> BlockId {
+        let used = (self.used as usize).min(TOKENS_PER_BLOCK);
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&self.time.to_le_bytes());
+        hasher.update(&[self.used]);
+        for (part, signature) in self.parts[..used].iter().zip(&self.signatures[..used]) {
+            hasher.update(&part.token.to_le_bytes());
+            hasher.update(&part.last.to_le_bytes());
+            hasher.update(&part.key.to_le_bytes());
+            match signature {
+                Some(signature) => {
+                    hasher.update(&[1]);
+                    hasher.update(&signature.to_le_bytes());
+                }
+                None => {
+                    hasher.update(&[0]);
+                }
+            }
+        }
+        let hash = hasher.finalize();
+        u64::from_le_bytes(hash.as_bytes()[0..8].try_into().unwrap())
+    }
+
+    /// Check that `used` matches the parts and signatures actually filled in
+    ///
+    /// `used` must be below `TOKENS_PER_BLOCK`, the bound mempool admission
+    /// enforces. Parts `0..used` must name a token; parts and signatures from
+    /// `used` onward must be empty, so consumers can index the arrays by `used` alone.
+    pub fn validate(&self) -> Result<(), BlockError> {
+        let used = self.used as usize;
+        if used >= TOKENS_PER_BLOCK {
+            return Err(BlockError::UsedOutOfRange { used: self.used });
+        }
+        if let Some(index) = self.parts[..used].iter().position(|part| part.token == 0) {
+            return Err(BlockError::MissingToken { index });
+        }
+        let unused_set = (used..TOKENS_PER_BLOCK)
+            .find(|&i| self.parts[i] != TokenBlock::default() || self.signatures[i].is_some());
+        if let Some(index) = unused_set {
+            return Err(BlockError::UnusedPartSet { index });
+        }
+        Ok(())
+    }
+}
+
 // ============================================================================
 // Commit Chain Types
 // ============================================================================
@@ -184,7 +270,7 @@ pub type CommitBlockId = BlockId;
 ///
 /// CommitBlocks form a blockchain tracking which transaction blocks were committed.
 /// Each node builds its own commit chain and syncs with neighbors for bootstrap/validation.
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct CommitBlock {
     /// Blake3 hash of (previous + time + committed_blocks)
     pub id: CommitBlockId,
@@ -202,8 +288,8 @@ pub struct CommitBlock {
 impl CommitBlock {
     /// Create a new commit block
     ///
-    /// Note: In simulation, the id is just assigned sequentially.
-    /// In production, it should be Blake3(previous || time || committed_blocks)
+    /// `EcCommitChain::create_commit_block` derives the id as
+    /// Blake3(previous || time || sorted(committed_blocks)); tests may assign any id.
     pub fn new(
         id: CommitBlockId,
         previous: CommitBlockId,
@@ -236,7 +322,7 @@ impl CommitBlock {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct TokenMapping {
     pub id: TokenId,
     pub block: BlockId,
@@ -244,7 +330,7 @@ pub struct TokenMapping {
 
 /// Result of a signature-based proof of storage query
 /// Contains the queried token's mapping plus signature tokens that prove storage
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct TokenSignature {
     /// The main token that was queried
     pub answer: TokenMapping,
@@ -253,7 +339,7 @@ pub struct TokenSignature {
 }
 
 // TODO make group message of Submit, Query and Validate
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum Message {
     InitialVote {
         block: Block,
@@ -290,6 +376,10 @@ pub enum Message {
         high: PeerId,
         low: PeerId,
     },
+    // Peer exchange: sample of the sender's closest peers
+    PeerList {
+        peers: Vec<PeerId>,
+    },
     // Commit chain messages
     QueryCommitBlock {
         block_id: CommitBlockId,
@@ -298,9 +388,25 @@ pub enum Message {
     CommitBlock {
         block: CommitBlock,
     },
+    // Batched block sync: the reply carries whichever of the blocks the peer has
+    QueryBlocks {
+        block_ids: Vec<BlockId>,
+        ticket: MessageTicket,
+    },
+    Blocks {
+        blocks: Vec<Block>,
+    },
+    // Liveness ping between Connected peers (refreshes the receiver's keepalive)
+    Keepalive {
+        head_of_chain: CommitBlockId, // Head of sender's commit chain (0 for nodes without commit chain)
+    },
+    // Periodic commit chain head advertisement to Connected peers
+    CommitHead {
+        head: CommitBlockId,
+    },
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum BatchRequestItem {
     Vote {
         block_id: BlockId,
@@ -427,7 +533,7 @@ pub enum Message2 {
     },
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct MessageEnvelope {
     pub sender: PeerId,
     pub receiver: PeerId,
@@ -500,7 +606,7 @@ pub enum TrustSource {
 }
 
 /// Trusted token mapping (current slot)
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct TrustedMapping {
     pub block: BlockId,
     pub parent: BlockId,
@@ -509,7 +615,7 @@ pub struct TrustedMapping {
 }
 
 /// Pending token mapping awaiting confirmation (pending slot)
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct PendingMapping {
     pub block: BlockId,
     pub parent: BlockId,
@@ -517,11 +623,42 @@ pub struct PendingMapping {
     pub source_peer: PeerId,
 }
 
+/// How a sync update competing with an unconfirmed pending mapping is resolved
+///
+/// Only the pending slot is contested: an update for the pending block from a
+/// different peer always confirms it, and a confirmed/local current mapping is
+/// never replaced directly. The winner takes the pending slot with its own
+/// reporter, so it still needs a second peer to confirm.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConflictResolution {
+    /// Higher block ID wins (deterministic, the protocol default)
+    #[default]
+    HighestBlockId,
+    /// Earlier block time wins; equal times fall back to higher block ID
+    EarliestTime,
+    /// The first reported mapping keeps the pending slot until it is
+    /// confirmed or the current slot moves past it
+    FirstSeen,
+}
+
+impl ConflictResolution {
+    /// Whether an update for `block` at `time` should replace `pending`
+    pub fn replaces_pending(self, block: BlockId, time: EcTime, pending: &PendingMapping) -> bool {
+        match self {
+            ConflictResolution::HighestBlockId => block > pending.block,
+            ConflictResolution::EarliestTime => {
+                time < pending.time || (time == pending.time && block > pending.block)
+            }
+            ConflictResolution::FirstSeen => false,
+        }
+    }
+}
+
 /// Two-slot token state for sync
 ///
 /// - `current`: Trusted state (Confirmed or Local) - served to queries
 /// - `pending`: Unconfirmed state from one peer - never served
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct TokenState {
     pub current: Option<TrustedMapping>,
     pub pending: Option<PendingMapping>,
@@ -543,6 +680,61 @@ impl TokenState {
     pub fn current_block(&self) -> Option<BlockId> {
         self.current.map(|c| c.block)
     }
+
+    /// Apply a sync update (two-slot state machine)
+    ///
+    /// - First peer: pending
+    /// - Second peer same block: confirmed
+    /// - Competing block replaces pending if `resolution` prefers it
+    ///
+    /// Returns false if the update was ignored: not newer than current, a
+    /// repeat from the pending reporter, or not preferred over pending.
+    pub fn apply_sync_update(
+        &mut self,
+        block: BlockId,
+        parent: BlockId,
+        time: EcTime,
+        source_peer: PeerId,
+        resolution: ConflictResolution,
+    ) -> bool {
+        let new_pending = PendingMapping {
+            block,
+            parent,
+            time,
+            source_peer,
+        };
+        if let Some(c) = &self.current {
+            if block <= c.block {
+                // Not newer than current, ignore
+                return false;
+            }
+        }
+        match &self.pending {
+            None => {
+                // First seen (or newer than current) - create pending
+                self.pending = Some(new_pending);
+            }
+            Some(p) if block == p.block && source_peer != p.source_peer => {
+                // Confirmation! Promote to current
+                self.current = Some(TrustedMapping {
+                    block: p.block,
+                    parent: p.parent,
+                    time: p.time,
+                    source: TrustSource::Confirmed,
+                });
+                self.pending = None;
+            }
+            Some(p) if resolution.replaces_pending(block, time, p) => {
+                // Preferred mapping replaces pending
+                self.pending = Some(new_pending);
+            }
+            _ => {
+                // Not preferred or same peer - ignore
+                return false;
+            }
+        }
+        true
+    }
 }
 
 pub trait EcTokens {
@@ -640,6 +832,12 @@ pub trait EcCommitChainAccess {
     /// Returns true if block was accepted, false if ticket was invalid.
     fn handle_block(&mut self, block: Block, ticket: MessageTicket) -> bool;
 
+    /// Handle the blocks of a `Blocks` reply from `sender`
+    ///
+    /// Stores only blocks `sender` was asked for and that are still missing,
+    /// dropping the rest. Returns the number of blocks stored.
+    fn handle_block_batch(&mut self, sender: PeerId, blocks: &[Block]) -> usize;
+
     /// Tick function for commit chain sync operations
     ///
     /// Orchestrates two-slot sync and returns actions for messaging.
@@ -649,6 +847,7 @@ pub trait EcCommitChainAccess {
     /// * `peers` - Peer manager for finding sync targets
     /// * `mempool` - Mempool for Local protection delegation
     /// * `time` - Current time
+    /// * `event_sink` - Receives sync events (applied and expired mappings)
     ///
     /// # Returns
     /// List of (peer_id, message) tuples for ec_node to convert to messages
@@ -657,7 +856,11 @@ pub trait EcCommitChainAccess {
         peers: &crate::ec_peers::EcPeers,
         mempool: &mut crate::ec_mempool::EcMemPool,
         time: EcTime,
+        event_sink: &mut dyn EventSink,
     ) -> Vec<(PeerId, crate::ec_commit_chain::TickMessage)>;
+
+    /// Configure commit chain sync (tracked peers, sync target, expiry ages)
+    fn set_commit_chain_config(&mut self, config: crate::ec_commit_chain::CommitChainConfig);
 }
 
 // ============================================================================
@@ -725,7 +928,7 @@ pub trait StorageBatch {
     /// Handles the two-slot state machine:
     /// - If no state: creates pending
     /// - If pending exists with same block from different peer: promotes to confirmed
-    /// - If pending exists with lower block ID: replaces pending
+    /// - If pending exists with a different block: `resolution` decides whether it replaces pending
     /// - If current (Confirmed) exists: may create/update pending
     ///
     /// Note: Caller must check for Local state BEFORE calling this.
@@ -737,8 +940,16 @@ pub trait StorageBatch {
         parent: &BlockId,
         time: EcTime,
         source_peer: PeerId,
+        resolution: ConflictResolution,
     );
 
+    /// Drop an unconfirmed sync mapping that aged out (commit chain cleanup)
+    ///
+    /// Clears the pending slot only if it still holds `block`; a pending that was
+    /// confirmed or replaced in the meantime is left alone. Tokens left with no
+    /// current mapping are removed.
+    fn expire_pending(&mut self, token: &TokenId, block: &BlockId);
+
     /// Commit all batched operations atomically
     ///
     /// # Errors
@@ -746,6 +957,9 @@ pub trait StorageBatch {
     /// On error, all operations in the batch are discarded.
     fn commit(self: Box<Self>) -> Result<(), Box<dyn std::error::Error>>;
 
+    /// Discard all batched operations without applying any of them
+    fn rollback(self: Box<Self>);
+
     /// Get the number of blocks in this batch
     fn block_count(&self) -> usize;
 }
@@ -813,6 +1027,27 @@ pub enum Event {
     },
     /// Identity-block received from a peer
     IdentityBlockReceived { peer_id: TokenId, sender: PeerId },
+    /// Peer promoted to Connected
+    PeerConnected { peer_id: PeerId },
+    /// Connected peer demoted to Identified
+    PeerDemoted { peer_id: PeerId },
+    /// Peer election started for a challenge token
+    ElectionStarted { token: TokenId },
+    /// Peer election completed with a winner
+    ElectionCompleted { token: TokenId, winner: PeerId },
+    /// Peer election abandoned with an unresolved split-brain
+    SplitBrainDetected { token: TokenId },
+    /// Commit-chain sync committed mappings from a tracked peer's CommitBlock
+    SyncApplied {
+        peer: PeerId,
+        commit_block: CommitBlockId,
+        tokens: usize,
+    },
+    /// Sync update from a tracked peer's CommitBlock that the two-slot state
+    /// machine ignored (stale, a repeat, or lost to pending under `ConflictResolution`)
+    SyncRejected { token: TokenId, peer: PeerId },
+    /// Unconfirmed pending mapping dropped after `pending_expiry_age`
+    PendingExpired { token: TokenId, block: BlockId },
 }
 
 /// Trait for consuming events from the consensus system
@@ -829,3 +1064,204 @@ impl EventSink for NoOpSink {
         // Intentionally empty - compiler should optimize this away
     }
 }
+
+/// Clock for `EcNode` (see `EcNode::set_time_source`)
+///
+/// Without one the node counts its own ticks. `Send` so a clock never stops a
+/// node from moving to another thread.
+pub trait TimeSource: Send {
+    fn now(&self) -> EcTime;
+}
+
+/// Time set by hand, for tests and simulations
+///
+/// Clones share the same clock, so a test can keep one and hand another to the node,
+/// also across threads.
+#[derive(Clone, Debug, Default)]
+pub struct ManualTimeSource(std::sync::Arc<std::sync::atomic::AtomicU64>);
+
+impl ManualTimeSource {
+    pub fn new(time: EcTime) -> Self {
+        Self(std::sync::Arc::new(std::sync::atomic::AtomicU64::new(time)))
+    }
+
+    pub fn set(&self, time: EcTime) {
+        self.0.store(time, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    pub fn advance(&self, ticks: EcTime) {
+        self.0
+            .fetch_add(ticks, std::sync::atomic::Ordering::Relaxed);
+    }
+}
+
+impl TimeSource for ManualTimeSource {
+    fn now(&self) -> EcTime {
+        self.0.load(std::sync::atomic::Ordering::Relaxed)
+    }
+}
+
+/// Wall-clock ticks of `tick_length` since creation, from a monotonic clock
+pub struct MonotonicTimeSource {
+    start: std::time::Instant,
+    tick_length: std::time::Duration,
+}
+
+impl MonotonicTimeSource {
+    pub fn new(tick_length: std::time::Duration) -> Self {
+        assert!(!tick_length.is_zero(), "tick_length must be positive");
+        Self {
+            start: std::time::Instant::now(),
+            tick_length,
+        }
+    }
+}
+
+impl TimeSource for MonotonicTimeSource {
+    fn now(&self) -> EcTime {
+        (self.start.elapsed().as_nanos() / self.tick_length.as_nanos()) as EcTime
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block_with_tokens(tokens: &[TokenId]) -> Block {
+        let mut block = Block {
+            id: 1,
+            time: 0,
+            used: tokens.len() as u8,
+            parts: [TokenBlock::default(); TOKENS_PER_BLOCK],
+            signatures: [None; TOKENS_PER_BLOCK],
+        };
+        for (part, &token) in block.parts.iter_mut().zip(tokens) {
+            part.token = token;
+            part.last = 9;
+        }
+        block
+    }
+
+    #[test]
+    fn test_compute_id_is_content_addressed() {
+        let block = block_with_tokens(&[11, 12]);
+        let rebuilt =
+            Block::with_computed_id(block.time, block.used, block.parts, block.signatures);
+
+        // The assigned id doesn't feed into the hash
+        assert_eq!(Block { id: 77, ..block }.compute_id(), rebuilt.id);
+        assert_eq!(rebuilt.compute_id(), rebuilt.id);
+
+        assert_ne!(Block { time: 1, ..block }.compute_id(), rebuilt.id);
+        assert_ne!(block_with_tokens(&[11, 13]).compute_id(), rebuilt.id);
+        let mut signed = block;
+        signed.signatures[0] = Some(5);
+        assert_ne!(signed.compute_id(), rebuilt.id);
+    }
+
+    #[test]
+    fn test_block_validate() {
+        assert_eq!(block_with_tokens(&[11, 12]).validate(), Ok(()));
+        assert_eq!(
+            block_with_tokens(&[11; TOKENS_PER_BLOCK - 1]).validate(),
+            Ok(())
+        );
+
+        // Same bound as mempool admission: a full block is rejected
+        assert_eq!(
+            block_with_tokens(&[11; TOKENS_PER_BLOCK]).validate(),
+            Err(BlockError::UsedOutOfRange {
+                used: TOKENS_PER_BLOCK as u8
+            })
+        );
+        let too_many = Block {
+            used: TOKENS_PER_BLOCK as u8 + 1,
+            ..block_with_tokens(&[11; TOKENS_PER_BLOCK])
+        };
+        assert_eq!(
+            too_many.validate(),
+            Err(BlockError::UsedOutOfRange {
+                used: TOKENS_PER_BLOCK as u8 + 1
+            })
+        );
+
+        // Gap in the active prefix
+        assert_eq!(
+            block_with_tokens(&[11, 0, 13]).validate(),
+            Err(BlockError::MissingToken { index: 1 })
+        );
+
+        // Leftovers past `used`
+        let stale_part = Block {
+            used: 1,
+            ..block_with_tokens(&[11, 12])
+        };
+        assert_eq!(
+            stale_part.validate(),
+            Err(BlockError::UnusedPartSet { index: 1 })
+        );
+        let mut stale_signature = block_with_tokens(&[11]);
+        stale_signature.signatures[3] = Some(5);
+        assert_eq!(
+            stale_signature.validate(),
+            Err(BlockError::UnusedPartSet { index: 3 })
+        );
+    }
+
+    fn roundtrip<T>(value: &T) -> T
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned,
+    {
+        let encoded = serde_yaml::to_string(value).unwrap();
+        serde_yaml::from_str(&encoded).unwrap()
+    }
+
+    #[test]
+    fn test_wire_types_serde_roundtrip() {
+        let mut block = Block {
+            id: u64::MAX,
+            time: 1_000,
+            used: 2,
+            parts: [TokenBlock::default(); TOKENS_PER_BLOCK],
+            signatures: [None; TOKENS_PER_BLOCK],
+        };
+        block.parts[0] = TokenBlock {
+            token: 11,
+            last: GENESIS_BLOCK_ID,
+            key: 7,
+        };
+        block.parts[1] = TokenBlock {
+            token: 12,
+            last: 99,
+            key: 8,
+        };
+        block.signatures[0] = Some(0xdead_beef);
+        block.signatures[1] = Some(0);
+        assert_eq!(roundtrip(&block), block);
+
+        let mut signature = [TokenMapping { id: 0, block: 0 }; TOKENS_SIGNATURE_SIZE];
+        for (i, mapping) in signature.iter_mut().enumerate() {
+            *mapping = TokenMapping {
+                id: i as u64 * 3,
+                block: i as u64 + 100,
+            };
+        }
+        let answer = Message::Answer {
+            answer: TokenMapping { id: 5, block: 6 },
+            signature,
+            head_of_chain: 42,
+        };
+        assert_eq!(roundtrip(&answer), answer);
+
+        let envelope = MessageEnvelope {
+            sender: 1,
+            receiver: 2,
+            ticket: 3,
+            time: 4,
+            message: Message::CommitBlock {
+                block: CommitBlock::new(9, 8, 7, vec![1, 2, 3]),
+            },
+        };
+        assert_eq!(roundtrip(&envelope), envelope);
+    }
+}
@@ -11,11 +11,11 @@ use std::collections::HashMap;
 
 use crate::ec_commit_chain::{CommitChainConfig, EcCommitChain};
 use crate::ec_interface::{
-    BatchedBackend, Block, BlockId, BlockTime, CommitBlock, CommitBlockId, EcBlocks,
-    EcCommitChainBackend, EcTime, EcTokens, EcTokensV2, PeerId, PendingMapping, StorageBatch,
-    TokenId, TokenSignature, TokenState, TrustSource, TrustedMapping,
+    BatchedBackend, Block, BlockId, BlockTime, CommitBlock, CommitBlockId, ConflictResolution,
+    EcBlocks, EcCommitChainBackend, EcTime, EcTokens, EcTokensV2, EventSink, PeerId,
+    PendingMapping, StorageBatch, TokenId, TokenSignature, TokenState, TrustSource, TrustedMapping,
 };
-use crate::ec_proof_of_storage::{ProofOfStorage, TokenStorageBackend};
+use crate::ec_proof_of_storage::{ProofOfStorage, SignatureParams, TokenStorageBackend};
 
 // ============================================================================
 // In-Memory Token Storage
@@ -38,7 +38,8 @@ use crate::ec_proof_of_storage::{ProofOfStorage, TokenStorageBackend};
 /// - Lookup: O(log n) via binary search
 /// - Set: O(n) worst case for insertion (maintains sort order)
 /// - Search signature: O(k) linear scan from lookup point (cache-friendly)
-/// - Memory: ~57 bytes per token worst case (both slots populated)
+/// - Memory: ~89 bytes per token worst case (both slots populated, plus the
+///   cached current-slot view used by `EcTokens::lookup`)
 ///
 /// # Example
 /// ```rust
@@ -60,37 +61,139 @@ pub struct MemTokens {
     /// Token states sorted by TokenId for binary search and range scans
     /// Two-slot model: each token has current (trusted) and pending (unverified) slots
     tokens: Vec<(TokenId, TokenState)>,
+    /// Current slot of each entry in `tokens` (same index) as a BlockTime, so
+    /// `EcTokens::lookup` can return a reference
+    current_views: Vec<Option<BlockTime>>,
 }
 
 impl MemTokens {
     /// Create a new empty in-memory token storage
     pub fn new() -> Self {
-        Self { tokens: Vec::new() }
+        Self {
+            tokens: Vec::new(),
+            current_views: Vec::new(),
+        }
     }
 
-    /// Create from unsorted mappings (will be sorted internally)
+    /// Load every mapping from another backend, e.g. a persisted RocksDB store
     ///
-    /// Converts legacy (token, block, parent, time) tuples to TokenState with Local trust.
-    pub fn from_mappings(mappings: Vec<(TokenId, BlockId, BlockId, EcTime)>) -> Self {
-        let mut tokens: Vec<(TokenId, TokenState)> = mappings
+    /// Mappings become Local, as if written with `set`.
+    pub fn from_backend(backend: &dyn TokenStorageBackend) -> Self {
+        let first = backend.lookup(&TokenId::MIN).map(|b| (TokenId::MIN, b));
+        let tokens = first
             .into_iter()
-            .map(|(token, block, parent, time)| {
-                (
-                    token,
-                    TokenState {
-                        current: Some(TrustedMapping {
-                            block,
-                            parent,
-                            time,
-                            source: TrustSource::Local,
-                        }),
-                        pending: None,
-                    },
-                )
+            .chain(backend.range_after(&TokenId::MIN))
+            .map(|(token, block_time)| {
+                let state = TokenState {
+                    current: Some(TrustedMapping {
+                        block: block_time.block,
+                        parent: block_time.parent,
+                        time: block_time.time,
+                        source: TrustSource::Local,
+                    }),
+                    pending: None,
+                };
+                (token, state)
             })
             .collect();
-        tokens.sort_by_key(|(token, _)| *token);
-        Self { tokens }
+        Self::from_sorted(tokens)
+    }
+
+    /// Build storage from token states already sorted by TokenId
+    fn from_sorted(tokens: Vec<(TokenId, TokenState)>) -> Self {
+        let current_views = tokens
+            .iter()
+            .map(|(_, state)| Self::view_of(state))
+            .collect();
+        Self {
+            tokens,
+            current_views,
+        }
+    }
+
+    /// BlockTime view of a state's current (trusted) slot
+    fn view_of(state: &TokenState) -> Option<BlockTime> {
+        state
+            .current
+            .map(|c| BlockTime::new(c.block, c.parent, c.time))
+    }
+
+    /// Insert a new token entry at `idx`, keeping the view cache in step
+    fn insert_entry(&mut self, idx: usize, token: TokenId, state: TokenState) {
+        self.current_views.insert(idx, Self::view_of(&state));
+        self.tokens.insert(idx, (token, state));
+    }
+
+    /// Replace the state of the entry at `idx`, keeping the view cache in step
+    fn replace_state(&mut self, idx: usize, state: TokenState) {
+        self.current_views[idx] = Self::view_of(&state);
+        self.tokens[idx].1 = state;
+    }
+
+    /// Create from unsorted mappings (will be sorted internally)
+    ///
+    /// Converts legacy (token, block, parent, time) tuples to TokenState with Local trust.
+    /// Duplicate tokens keep the newest mapping, as with repeated `set` calls.
+    pub fn from_mappings(mappings: Vec<(TokenId, BlockId, BlockId, EcTime)>) -> Self {
+        let mut storage = Self::new();
+        storage.extend_sorted(mappings);
+        storage
+    }
+
+    /// Bulk insert mappings with a single sort and merge pass
+    ///
+    /// Equivalent to calling `TokenStorageBackend::set` for each mapping in order,
+    /// but O((n + m) + m log m) instead of O(n) per insert: the new mappings are
+    /// sorted once, duplicates keep the newest time (first one wins on equal times),
+    /// and the result is merged with the existing tokens. A merged mapping replaces
+    /// an existing token only if that token has no current slot or an older one.
+    pub fn extend_sorted(
+        &mut self,
+        mappings: impl IntoIterator<Item = (TokenId, BlockId, BlockId, EcTime)>,
+    ) {
+        let mut incoming: Vec<(TokenId, BlockId, BlockId, EcTime)> = mappings.into_iter().collect();
+        if incoming.is_empty() {
+            return;
+        }
+        // Stable sort keeps arrival order among duplicates
+        incoming.sort_by_key(|(token, _, _, _)| *token);
+        incoming.dedup_by(|next, kept| {
+            if next.0 != kept.0 {
+                return false;
+            }
+            if next.3 > kept.3 {
+                *kept = *next;
+            }
+            true
+        });
+
+        let existing = std::mem::take(&mut self.tokens);
+        let mut merged = Vec::with_capacity(existing.len() + incoming.len());
+        let mut existing = existing.into_iter().peekable();
+
+        for (token, block, parent, time) in incoming {
+            while let Some(entry) = existing.next_if(|(t, _)| *t < token) {
+                merged.push(entry);
+            }
+            let state = TokenState {
+                current: Some(TrustedMapping {
+                    block,
+                    parent,
+                    time,
+                    source: TrustSource::Local,
+                }),
+                pending: None,
+            };
+            match existing.next_if(|(t, _)| *t == token) {
+                Some((_, old)) if old.current.is_some_and(|c| c.time >= time) => {
+                    merged.push((token, old));
+                }
+                _ => merged.push((token, state)),
+            }
+        }
+        merged.extend(existing);
+
+        *self = Self::from_sorted(merged);
     }
 
     /// Create a ProofOfStorage system using this storage backend
@@ -136,6 +239,71 @@ impl MemTokens {
             .iter()
             .find_map(|(token_id, state)| state.current.map(|current| (*token_id, current)))
     }
+
+    /// Iterate current (trusted) mappings with `start <= token < end` in ascending order
+    ///
+    /// The range follows the ring: when `start > end` it wraps, yielding tokens from
+    /// `start` up to `TokenId::MAX` and then from 0 up to `end`. `start == end` is empty.
+    /// Tokens with only a pending slot are skipped, matching `lookup`.
+    pub fn range(
+        &self,
+        start: TokenId,
+        end: TokenId,
+    ) -> impl Iterator<Item = (TokenId, BlockTime)> + '_ {
+        let lo = self.tokens.partition_point(|(t, _)| *t < start);
+        let hi = self.tokens.partition_point(|(t, _)| *t < end);
+        let (head, tail) = if start <= end {
+            (&self.tokens[lo..hi], &self.tokens[..0])
+        } else {
+            (&self.tokens[lo..], &self.tokens[..hi])
+        };
+        head.iter()
+            .chain(tail.iter())
+            .filter_map(Self::current_block_time)
+    }
+
+    /// Drop every token outside `[low, high]`, e.g. to shed responsibility
+    ///
+    /// The range follows the ring like `PeerRange::in_range`: when `low >= high`
+    /// it wraps past `TokenId::MAX`, keeping tokens `<= high` or `>= low`.
+    /// Cuts whole runs of the sorted Vec, so it is cheaper than per-token `remove`.
+    pub fn retain_range(&mut self, low: TokenId, high: TokenId) {
+        let from_low = self.tokens.partition_point(|(t, _)| *t < low);
+        let above_high = self.tokens.partition_point(|(t, _)| *t <= high);
+
+        if low < high {
+            self.tokens.truncate(above_high);
+            self.current_views.truncate(above_high);
+            self.tokens.drain(..from_low);
+            self.current_views.drain(..from_low);
+        } else if above_high < from_low {
+            self.tokens.drain(above_high..from_low);
+            self.current_views.drain(above_high..from_low);
+        }
+    }
+
+    /// Iterate current mappings strictly above `token` in ascending order (no wrap)
+    pub fn range_after(&self, token: &TokenId) -> impl Iterator<Item = (TokenId, BlockTime)> + '_ {
+        let idx = self.tokens.partition_point(|(t, _)| t <= token);
+        self.tokens[idx..]
+            .iter()
+            .filter_map(Self::current_block_time)
+    }
+
+    /// Iterate current mappings strictly below `token` in descending order (no wrap)
+    pub fn range_before(&self, token: &TokenId) -> impl Iterator<Item = (TokenId, BlockTime)> + '_ {
+        let idx = self.tokens.partition_point(|(t, _)| t < token);
+        self.tokens[..idx]
+            .iter()
+            .rev()
+            .filter_map(Self::current_block_time)
+    }
+
+    fn current_block_time((token, state): &(TokenId, TokenState)) -> Option<(TokenId, BlockTime)> {
+        state
+            .current
+            .map(|c| (*token, BlockTime::new(c.block, c.parent, c.time)))
+    }
 }
 
 impl Default for MemTokens {
@@ -150,16 +318,8 @@ impl Default for MemTokens {
 
 impl TokenStorageBackend for MemTokens {
     fn lookup(&self, token: &TokenId) -> Option<BlockTime> {
-        self.tokens
-            .binary_search_by_key(token, |(t, _)| *t)
-            .ok()
-            .and_then(|idx| {
-                // Only return from current (trusted) slot
-                self.tokens[idx]
-                    .1
-                    .current
-                    .map(|c| BlockTime::new(c.block, c.parent, c.time))
-            })
+        // Only return from current (trusted) slot
+        EcTokens::lookup(self, token).copied()
     }
 
     fn set(&mut self, token: &TokenId, block: &BlockId, parent: &BlockId, time: EcTime) {
@@ -179,53 +339,70 @@ impl TokenStorageBackend for MemTokens {
                 // Token exists - check if we should update based on time
                 if let Some(current) = &self.tokens[idx].1.current {
                     if current.time < time {
-                        self.tokens[idx].1 = new_state;
+                        self.replace_state(idx, new_state);
                     }
                 } else {
                     // No current, always set
-                    self.tokens[idx].1 = new_state;
+                    self.replace_state(idx, new_state);
                 }
             }
             Err(idx) => {
                 // Token doesn't exist - insert at correct position to maintain sort order
-                self.tokens.insert(idx, (*token, new_state));
+                self.insert_entry(idx, *token, new_state);
             }
         }
     }
 
+    fn remove(&mut self, token: &TokenId) -> Option<BlockTime> {
+        // Drops both slots; the remaining entries stay sorted. A pending-only
+        // token returns its pending mapping, since that is what was removed.
+        let idx = self.tokens.binary_search_by_key(token, |(t, _)| *t).ok()?;
+        let (_, state) = self.tokens.remove(idx);
+        self.current_views.remove(idx).or_else(|| {
+            state
+                .pending
+                .map(|pending| BlockTime::new(pending.block, pending.parent, pending.time))
+        })
+    }
+
     fn search_signature(
         &self,
         lookup_token: &TokenId,
-        signature_chunks: &[u16; crate::ec_proof_of_storage::SIGNATURE_CHUNKS],
+        signature_chunks: &[u16],
+        params: &SignatureParams,
+        forward_chunks: usize,
+        max_steps: usize,
     ) -> crate::ec_proof_of_storage::SignatureSearchResult {
-        use crate::ec_proof_of_storage::{SignatureSearchResult, SIGNATURE_CHUNKS};
+        use crate::ec_proof_of_storage::SignatureSearchResult;
 
-        let mut found_tokens = Vec::with_capacity(SIGNATURE_CHUNKS);
+        let total = signature_chunks.len();
+        let forward = forward_chunks.min(total);
+        let mut found_tokens = Vec::with_capacity(total);
         let mut steps = 0;
         let mut chunk_idx = 0;
 
-        // Helper to check if a token's last 10 bits match a signature chunk
-        #[inline]
-        fn matches_chunk(token: &TokenId, chunk_value: u16) -> bool {
-            (token & 0x3FF) as u16 == chunk_value
-        }
-
         // Find starting position for forward search using binary search
         let start_idx = match self.tokens.binary_search_by_key(lookup_token, |(t, _)| *t) {
             Ok(idx) => idx + 1, // Found exact match, start after it
             Err(idx) => idx,    // Not found, idx is insertion point (first token > lookup_token)
         };
 
-        // Search forward (above) for first 5 chunks
+        // Search forward (above) for first half of the chunks
         // Only consider tokens with current (trusted) state
         for i in start_idx..self.tokens.len() {
+            if chunk_idx >= forward {
+                break;
+            }
             let (token, state) = &self.tokens[i];
             if state.current.is_some() {
+                if steps >= max_steps {
+                    break;
+                }
                 steps += 1;
-                if matches_chunk(token, signature_chunks[chunk_idx]) {
+                if params.matches_chunk(token, signature_chunks[chunk_idx]) {
                     found_tokens.push(*token);
                     chunk_idx += 1;
-                    if chunk_idx >= 5 {
+                    if chunk_idx >= forward {
                         break;
                     }
                 }
@@ -233,15 +410,18 @@ impl TokenStorageBackend for MemTokens {
         }
 
         // Ring wrap: from beginning to lookup_token
-        if chunk_idx < 5 {
+        if chunk_idx < forward {
             for i in 0..start_idx.saturating_sub(1) {
                 let (token, state) = &self.tokens[i];
                 if state.current.is_some() {
+                    if steps >= max_steps {
+                        break;
+                    }
                     steps += 1;
-                    if matches_chunk(token, signature_chunks[chunk_idx]) {
+                    if params.matches_chunk(token, signature_chunks[chunk_idx]) {
                         found_tokens.push(*token);
                         chunk_idx += 1;
-                        if chunk_idx >= 5 {
+                        if chunk_idx >= forward {
                             break;
                         }
                     }
@@ -249,22 +429,27 @@ impl TokenStorageBackend for MemTokens {
             }
         }
 
+        let forward_steps = steps;
+
         // Find starting position for backward search
         let end_idx = match self.tokens.binary_search_by_key(lookup_token, |(t, _)| *t) {
             Ok(idx) => idx.saturating_sub(1), // Found exact match, start before it
             Err(idx) => idx.saturating_sub(1), // Not found, start at position before insertion point
         };
 
-        // Search backward (below) for last 5 chunks
-        if end_idx < self.tokens.len() {
+        // Search backward (below) for remaining chunks
+        if chunk_idx < total && end_idx < self.tokens.len() {
             for i in (0..=end_idx).rev() {
                 let (token, state) = &self.tokens[i];
                 if state.current.is_some() {
+                    if steps >= max_steps {
+                        break;
+                    }
                     steps += 1;
-                    if matches_chunk(token, signature_chunks[chunk_idx]) {
+                    if params.matches_chunk(token, signature_chunks[chunk_idx]) {
                         found_tokens.push(*token);
                         chunk_idx += 1;
-                        if chunk_idx >= SIGNATURE_CHUNKS {
+                        if chunk_idx >= total {
                             break;
                         }
                     }
@@ -273,15 +458,18 @@ impl TokenStorageBackend for MemTokens {
         }
 
         // Ring wrap: from end backwards to lookup_token
-        if chunk_idx < SIGNATURE_CHUNKS && end_idx < self.tokens.len() {
+        if chunk_idx < total && end_idx < self.tokens.len() {
             for i in (end_idx + 1..self.tokens.len()).rev() {
                 let (token, state) = &self.tokens[i];
                 if state.current.is_some() {
+                    if steps >= max_steps {
+                        break;
+                    }
                     steps += 1;
-                    if matches_chunk(token, signature_chunks[chunk_idx]) {
+                    if params.matches_chunk(token, signature_chunks[chunk_idx]) {
                         found_tokens.push(*token);
                         chunk_idx += 1;
-                        if chunk_idx >= SIGNATURE_CHUNKS {
+                        if chunk_idx >= total {
                             break;
                         }
                     }
@@ -290,9 +478,34 @@ impl TokenStorageBackend for MemTokens {
         }
 
         SignatureSearchResult {
-            complete: chunk_idx == SIGNATURE_CHUNKS,
+            complete: chunk_idx == total,
+            chunks_matched: found_tokens.len(),
             tokens: found_tokens,
             steps,
+            forward_steps,
+            backward_steps: steps - forward_steps,
+        }
+    }
+
+    fn range_after(&self, token: &TokenId) -> Box<dyn Iterator<Item = (TokenId, BlockTime)> + '_> {
+        Box::new(MemTokens::range_after(self, token))
+    }
+
+    fn count_in_range(&self, start: &TokenId, end: &TokenId) -> usize {
+        // Binary search for the bounds, then count current views only:
+        // pending-only entries are invisible to lookup and range_after too
+        let lo = self.tokens.partition_point(|(t, _)| t < start);
+        let hi = self.tokens.partition_point(|(t, _)| t < end);
+        let current = |range: std::ops::Range<usize>| {
+            self.current_views[range]
+                .iter()
+                .filter(|view| view.is_some())
+                .count()
+        };
+        if start <= end {
+            current(lo..hi)
+        } else {
+            current(lo..self.tokens.len()) + current(0..hi)
         }
     }
 
@@ -317,12 +530,34 @@ impl<'a> TokenStorageBackend for MemTokensRef<'a> {
         panic!("Cannot mutate through MemTokensRef");
     }
 
+    fn remove(&mut self, _token: &TokenId) -> Option<BlockTime> {
+        panic!("Cannot mutate through MemTokensRef");
+    }
+
     fn search_signature(
         &self,
         lookup_token: &TokenId,
-        signature_chunks: &[u16; crate::ec_proof_of_storage::SIGNATURE_CHUNKS],
+        signature_chunks: &[u16],
+        params: &SignatureParams,
+        forward_chunks: usize,
+        max_steps: usize,
     ) -> crate::ec_proof_of_storage::SignatureSearchResult {
-        TokenStorageBackend::search_signature(self.0, lookup_token, signature_chunks)
+        TokenStorageBackend::search_signature(
+            self.0,
+            lookup_token,
+            signature_chunks,
+            params,
+            forward_chunks,
+            max_steps,
+        )
+    }
+
+    fn range_after(&self, token: &TokenId) -> Box<dyn Iterator<Item = (TokenId, BlockTime)> + '_> {
+        TokenStorageBackend::range_after(self.0, token)
+    }
+
+    fn count_in_range(&self, start: &TokenId, end: &TokenId) -> usize {
+        TokenStorageBackend::count_in_range(self.0, start, end)
     }
 
     fn len(&self) -> usize {
@@ -363,7 +598,7 @@ impl MemTokens {
     /// Implements the confirmation logic:
     /// - First peer: pending
     /// - Second peer same block: confirmed
-    /// - Higher ID replaces pending
+    /// - Competing block replaces pending if `resolution` prefers it
     pub fn update_token_sync(
         &mut self,
         token: &TokenId,
@@ -371,11 +606,13 @@ impl MemTokens {
         parent: &BlockId,
         time: EcTime,
         source_peer: PeerId,
+        resolution: ConflictResolution,
     ) {
         match self.tokens.binary_search_by_key(token, |(t, _)| *t) {
             Ok(idx) => {
                 let state = &mut self.tokens[idx].1;
-                Self::apply_sync_update(state, *block, *parent, time, source_peer);
+                state.apply_sync_update(*block, *parent, time, source_peer, resolution);
+                self.current_views[idx] = Self::view_of(&self.tokens[idx].1);
             }
             Err(idx) => {
                 // New token - create with pending
@@ -388,89 +625,26 @@ impl MemTokens {
                         source_peer,
                     }),
                 };
-                self.tokens.insert(idx, (*token, state));
+                self.insert_entry(idx, *token, state);
             }
         }
     }
 
-    /// Apply sync update to existing state (state machine logic)
-    fn apply_sync_update(
-        state: &mut TokenState,
-        block: BlockId,
-        parent: BlockId,
-        time: EcTime,
-        source_peer: PeerId,
-    ) {
-        match (&state.current, &state.pending) {
-            (None, None) => {
-                // First seen - create pending
-                state.pending = Some(PendingMapping {
-                    block,
-                    parent,
-                    time,
-                    source_peer,
-                });
-            }
-            (None, Some(p)) => {
-                if block == p.block && source_peer != p.source_peer {
-                    // Confirmation! Promote to current
-                    state.current = Some(TrustedMapping {
-                        block: p.block,
-                        parent: p.parent,
-                        time: p.time,
-                        source: TrustSource::Confirmed,
-                    });
-                    state.pending = None;
-                } else if block > p.block {
-                    // Higher ID replaces pending
-                    state.pending = Some(PendingMapping {
-                        block,
-                        parent,
-                        time,
-                        source_peer,
-                    });
-                }
-                // else: lower or equal ID from same peer - ignore
-            }
-            (Some(c), pending) => {
-                if block <= c.block {
-                    // Not newer than current, ignore
-                    return;
-                }
-                // Newer block - handle based on pending
-                match pending {
-                    None => {
-                        state.pending = Some(PendingMapping {
-                            block,
-                            parent,
-                            time,
-                            source_peer,
-                        });
-                    }
-                    Some(p) if block == p.block && source_peer != p.source_peer => {
-                        // Confirms pending - promote to current
-                        state.current = Some(TrustedMapping {
-                            block: p.block,
-                            parent: p.parent,
-                            time: p.time,
-                            source: TrustSource::Confirmed,
-                        });
-                        state.pending = None;
-                    }
-                    Some(p) if block > p.block => {
-                        // Higher ID replaces pending
-                        state.pending = Some(PendingMapping {
-                            block,
-                            parent,
-                            time,
-                            source_peer,
-                        });
-                    }
-                    _ => {
-                        // Lower ID or same peer - ignore
-                    }
-                }
-            }
+    /// Drop the pending slot if it still holds `block`
+    ///
+    /// Removes the token entirely when it has no current mapping either.
+    pub fn expire_pending(&mut self, token: &TokenId, block: &BlockId) {
+        let Ok(idx) = self.tokens.binary_search_by_key(token, |(t, _)| *t) else {
+            return;
+        };
+        let state = &mut self.tokens[idx].1;
+        if state.pending.map(|p| p.block) != Some(*block) {
+            return;
+        }
+        state.pending = None;
+        if state.current.is_none() {
+            self.tokens.remove(idx);
+            self.current_views.remove(idx);
         }
     }
 }
@@ -480,12 +654,12 @@ impl MemTokens {
 // ============================================================================
 
 impl EcTokens for MemTokens {
-    fn lookup(&self, _token: &TokenId) -> Option<&BlockTime> {
-        // EcTokens trait expects a reference, but Vec storage makes this awkward
-        // We'd need to return a reference to a temporary - instead panic
-        // This method should not be used with sorted Vec backend
-        // Use TokenStorageBackend::lookup instead which returns owned BlockTime
-        unimplemented!("EcTokens::lookup not supported for sorted Vec backend - use TokenStorageBackend::lookup instead")
+    fn lookup(&self, token: &TokenId) -> Option<&BlockTime> {
+        // Served from the view cache; only the current (trusted) slot is visible
+        self.tokens
+            .binary_search_by_key(token, |(t, _)| *t)
+            .ok()
+            .and_then(|idx| self.current_views[idx].as_ref())
     }
 
     fn set(&mut self, token: &TokenId, block: &BlockId, parent: &BlockId, time: EcTime) {
@@ -537,6 +711,7 @@ impl EcTokens for MemTokens {
 ///     println!("Block {} has {} tokens", retrieved.id, retrieved.used);
 /// }
 /// ```
+#[derive(Clone)]
 pub struct MemBlocks {
     blocks: HashMap<BlockId, Block>,
 }
@@ -548,6 +723,21 @@ impl MemBlocks {
             blocks: HashMap::new(),
         }
     }
+
+    /// Iterate all stored blocks (arbitrary order)
+    pub fn iter(&self) -> impl Iterator<Item = (&BlockId, &Block)> {
+        self.blocks.iter()
+    }
+
+    /// Number of stored blocks
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Check if no blocks are stored
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
 }
 
 impl Default for MemBlocks {
@@ -613,6 +803,10 @@ pub struct MemoryBackend {
     commit_chain_backend: MemCommitChain,
     #[allow(dead_code)] // Stored for potential future use
     peer_id: PeerId,
+    /// Make the next batch commit fail (see `fail_next_commit`)
+    fail_next_commit: bool,
+    /// Ticket secret of the current commit chain, rehashed on each restart
+    ticket_secret: [u8; 32],
 }
 
 impl MemoryBackend {
@@ -625,23 +819,69 @@ impl MemoryBackend {
 
     /// Create a new memory backend for a specific peer
     pub fn new_with_peer_id(peer_id: PeerId) -> Self {
+        Self::with_commit_chain_config(peer_id, CommitChainConfig::default())
+    }
+
+    /// Create a memory backend whose commit chain uses `config`
+    ///
+    /// The commit chain lives in the backend, so this is where a node's
+    /// commit-chain behavior (tracked peers, sync target, expiry ages) is set.
+    pub fn with_commit_chain_config(peer_id: PeerId, config: CommitChainConfig) -> Self {
+        use rand::Rng;
+        Self::with_ticket_secret(peer_id, config, rand::thread_rng().gen())
+    }
+
+    /// Create a memory backend whose commit chain issues tickets from `ticket_secret`
+    ///
+    /// Simulations derive the secret from their seeded RNG so runs are
+    /// reproducible; `reset_runtime_state` rehashes it, so restarts stay
+    /// deterministic too.
+    pub fn with_ticket_secret(
+        peer_id: PeerId,
+        config: CommitChainConfig,
+        ticket_secret: [u8; 32],
+    ) -> Self {
         // Full range initially (will be updated by ec_peers as network evolves)
         let my_range = crate::ec_peers::PeerRange::new(0, u64::MAX);
         Self {
             tokens: MemTokens::new(),
             blocks: MemBlocks::new(),
-            commit_chain: EcCommitChain::new(peer_id, my_range, CommitChainConfig::default()),
+            commit_chain: EcCommitChain::with_secret(peer_id, my_range, config, ticket_secret),
             commit_chain_backend: MemCommitChain::new(),
             peer_id,
+            fail_next_commit: false,
+            ticket_secret,
         }
     }
 
     /// Reset non-persistent runtime state for a node restart while preserving
     /// stored tokens, blocks, and persisted commit-chain history.
+    /// The commit chain keeps its configuration; its ticket secret is the
+    /// hash of the previous one, so tickets from before the restart are void.
     pub fn reset_runtime_state(&mut self) {
         let my_range = crate::ec_peers::PeerRange::new(0, u64::MAX);
+        let config = self.commit_chain.config().clone();
+        self.ticket_secret = *blake3::hash(&self.ticket_secret).as_bytes();
         self.commit_chain =
-            EcCommitChain::new(self.peer_id, my_range, CommitChainConfig::default());
+            EcCommitChain::with_secret(self.peer_id, my_range, config, self.ticket_secret);
+    }
+
+    /// Create a fully independent deep copy of this backend
+    ///
+    /// Copies tokens, blocks, persisted commit-chain history and the runtime
+    /// commit chain (peer id, config, tracked peer logs and pending blocks).
+    /// Used by the simulator to fork a node's state and explore alternate
+    /// event schedules; mutating the snapshot never affects the original.
+    pub fn snapshot(&self) -> MemoryBackend {
+        Self {
+            tokens: self.tokens.clone(),
+            blocks: self.blocks.clone(),
+            commit_chain: self.commit_chain.clone(),
+            commit_chain_backend: self.commit_chain_backend.clone(),
+            peer_id: self.peer_id,
+            fail_next_commit: self.fail_next_commit,
+            ticket_secret: self.ticket_secret,
+        }
     }
 
     /// Get immutable reference to token storage
@@ -683,6 +923,14 @@ impl MemoryBackend {
     pub fn commit_chain_backend_mut(&mut self) -> &mut MemCommitChain {
         &mut self.commit_chain_backend
     }
+
+    /// Make the next batch commit fail without applying anything (for testing)
+    ///
+    /// Memory writes cannot fail, so this is how error paths around
+    /// `StorageBatch::commit` are exercised.
+    pub fn fail_next_commit(&mut self) {
+        self.fail_next_commit = true;
+    }
 }
 
 impl Default for MemoryBackend {
@@ -697,15 +945,25 @@ impl Default for MemoryBackend {
 
 /// Batch for memory backend
 ///
-/// Collects operations in memory and applies them all at commit time.
-/// Not truly atomic (no rollback), but matches the API for consistency.
+/// Stages operations in memory and applies them only at commit time, after the
+/// commit can no longer fail. `rollback`, a failed commit, or dropping the batch
+/// leaves the backend untouched.
 pub struct MemoryBatch<'a> {
     backend: &'a mut MemoryBackend,
     blocks: Vec<Block>,
     /// Local token updates (from mempool) - become Local trust
     local_tokens: Vec<(TokenId, BlockId, BlockId, EcTime)>,
     /// Sync token updates (from commit chain) - use two-slot state machine
-    sync_tokens: Vec<(TokenId, BlockId, BlockId, EcTime, PeerId)>,
+    sync_tokens: Vec<(
+        TokenId,
+        BlockId,
+        BlockId,
+        EcTime,
+        PeerId,
+        ConflictResolution,
+    )>,
+    /// Aged-out pending mappings to drop (from commit chain)
+    expired_pending: Vec<(TokenId, BlockId)>,
 }
 
 impl<'a> StorageBatch for MemoryBatch<'a> {
@@ -724,12 +982,22 @@ impl<'a> StorageBatch for MemoryBatch<'a> {
         parent: &BlockId,
         time: EcTime,
         source_peer: PeerId,
+        resolution: ConflictResolution,
     ) {
         self.sync_tokens
-            .push((*token, *block, *parent, time, source_peer));
+            .push((*token, *block, *parent, time, source_peer, resolution));
+    }
+
+    fn expire_pending(&mut self, token: &TokenId, block: &BlockId) {
+        self.expired_pending.push((*token, *block));
     }
 
     fn commit(self: Box<Self>) -> Result<(), Box<dyn std::error::Error>> {
+        // Fail before applying anything, so a failed commit changes nothing
+        if std::mem::take(&mut self.backend.fail_next_commit) {
+            return Err("injected batch commit failure".into());
+        }
+
         // Apply all blocks
         for block in &self.blocks {
             self.backend.blocks.save(block);
@@ -741,10 +1009,20 @@ impl<'a> StorageBatch for MemoryBatch<'a> {
         }
 
         // Apply sync token updates (two-slot state machine)
-        for (token, block, parent, time, source_peer) in &self.sync_tokens {
-            self.backend
-                .tokens
-                .update_token_sync(token, block, parent, *time, *source_peer);
+        for (token, block, parent, time, source_peer, resolution) in &self.sync_tokens {
+            self.backend.tokens.update_token_sync(
+                token,
+                block,
+                parent,
+                *time,
+                *source_peer,
+                *resolution,
+            );
+        }
+
+        // Drop aged-out pending mappings (after sync, so fresh updates win)
+        for (token, block) in &self.expired_pending {
+            self.backend.tokens.expire_pending(token, block);
         }
 
         // Create commit block if we committed any blocks
@@ -768,6 +1046,10 @@ impl<'a> StorageBatch for MemoryBatch<'a> {
         Ok(())
     }
 
+    fn rollback(self: Box<Self>) {
+        // Staged operations are simply dropped
+    }
+
     fn block_count(&self) -> usize {
         self.blocks.len()
     }
@@ -780,16 +1062,15 @@ impl BatchedBackend for MemoryBackend {
             blocks: Vec::new(),
             local_tokens: Vec::new(),
             sync_tokens: Vec::new(),
+            expired_pending: Vec::new(),
         })
     }
 }
 
 // Implement EcTokens for MemoryBackend (compatibility wrapper only)
 impl EcTokens for MemoryBackend {
-    fn lookup(&self, _token: &TokenId) -> Option<&BlockTime> {
-        unimplemented!(
-            "EcTokens::lookup not supported for MemoryBackend - use EcTokensV2::lookup_current or TokenStorageBackend::lookup instead"
-        )
+    fn lookup(&self, token: &TokenId) -> Option<&BlockTime> {
+        EcTokens::lookup(&self.tokens, token)
     }
 
     fn set(&mut self, token: &TokenId, block: &BlockId, parent: &BlockId, time: EcTime) {
@@ -826,12 +1107,34 @@ impl TokenStorageBackend for MemoryBackend {
         TokenStorageBackend::set(&mut self.tokens, token, block, parent, time);
     }
 
+    fn remove(&mut self, token: &TokenId) -> Option<BlockTime> {
+        TokenStorageBackend::remove(&mut self.tokens, token)
+    }
+
     fn search_signature(
         &self,
         lookup_token: &TokenId,
-        signature_chunks: &[u16; crate::ec_proof_of_storage::SIGNATURE_CHUNKS],
+        signature_chunks: &[u16],
+        params: &SignatureParams,
+        forward_chunks: usize,
+        max_steps: usize,
     ) -> crate::ec_proof_of_storage::SignatureSearchResult {
-        TokenStorageBackend::search_signature(&self.tokens, lookup_token, signature_chunks)
+        TokenStorageBackend::search_signature(
+            &self.tokens,
+            lookup_token,
+            signature_chunks,
+            params,
+            forward_chunks,
+            max_steps,
+        )
+    }
+
+    fn range_after(&self, token: &TokenId) -> Box<dyn Iterator<Item = (TokenId, BlockTime)> + '_> {
+        TokenStorageBackend::range_after(&self.tokens, token)
+    }
+
+    fn count_in_range(&self, start: &TokenId, end: &TokenId) -> usize {
+        TokenStorageBackend::count_in_range(&self.tokens, start, end)
     }
 
     fn len(&self) -> usize {
@@ -864,6 +1167,10 @@ impl crate::ec_interface::EcCommitChainAccess for MemoryBackend {
         self.commit_chain_backend.get_head()
     }
 
+    fn set_commit_chain_config(&mut self, config: CommitChainConfig) {
+        self.commit_chain.set_config(config);
+    }
+
     fn query_commit_block(&self, block_id: CommitBlockId) -> Option<CommitBlock> {
         self.commit_chain_backend.lookup(&block_id)
     }
@@ -873,15 +1180,14 @@ impl crate::ec_interface::EcCommitChainAccess for MemoryBackend {
         block: CommitBlock,
         sender: PeerId,
         ticket: crate::ec_interface::MessageTicket,
-        _current_time: EcTime,
+        current_time: EcTime,
     ) -> Option<crate::ec_interface::ParentBlockRequest> {
         // handle_commit_block expects &dyn EcBlocks as the last parameter
-        let accepted = self
+        if self
             .commit_chain
-            .handle_commit_block(block, sender, ticket, &self.blocks);
-        // Return None since we don't implement parent block requests yet
-        if accepted {
-            None
+            .handle_commit_block(block, sender, ticket, &self.blocks)
+        {
+            self.commit_chain.parent_request(sender, current_time)
         } else {
             None
         }
@@ -895,22 +1201,31 @@ impl crate::ec_interface::EcCommitChainAccess for MemoryBackend {
         self.commit_chain.handle_block(block, ticket)
     }
 
+    fn handle_block_batch(
+        &mut self,
+        sender: crate::ec_interface::PeerId,
+        blocks: &[crate::ec_interface::Block],
+    ) -> usize {
+        self.commit_chain.handle_block_batch(sender, blocks)
+    }
+
     fn commit_chain_tick(
         &mut self,
         peers: &crate::ec_peers::EcPeers,
         mempool: &mut crate::ec_mempool::EcMemPool,
         time: EcTime,
+        event_sink: &mut dyn EventSink,
     ) -> Vec<(PeerId, crate::ec_commit_chain::TickMessage)> {
         // Temporarily move commit_chain out to avoid borrow conflicts
         // (tick needs mutable access to both commit_chain and storage)
         let my_range = crate::ec_peers::PeerRange::new(0, u64::MAX);
         let mut commit_chain = std::mem::replace(
             &mut self.commit_chain,
-            EcCommitChain::new(0, my_range, CommitChainConfig::default()),
+            EcCommitChain::with_secret(0, my_range, CommitChainConfig::default(), [0; 32]),
         );
 
         // Call tick with self as storage and mempool for Local protection
-        let messages = commit_chain.tick(peers, self, mempool, time);
+        let messages = commit_chain.tick(peers, self, mempool, time, event_sink);
 
         // Restore commit_chain
         self.commit_chain = commit_chain;
@@ -927,6 +1242,7 @@ impl crate::ec_interface::EcCommitChainAccess for MemoryBackend {
 ///
 /// Stores CommitBlocks in a HashMap for fast lookup. Tracks the current head
 /// of the commit chain. For MVP/simulation use only.
+#[derive(Clone)]
 pub struct MemCommitChain {
     blocks: HashMap<CommitBlockId, CommitBlock>,
     head: Option<CommitBlockId>,
@@ -974,7 +1290,7 @@ impl MemCommitChain {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ec_interface::TokenBlock;
+    use crate::ec_interface::{NoOpSink, TokenBlock, TOKENS_PER_BLOCK};
 
     // ========================================================================
     // Token Storage Tests
@@ -1019,6 +1335,184 @@ mod tests {
         assert_eq!(result.block, block2, "Should update with newer mapping");
     }
 
+    #[test]
+    fn test_mem_tokens_remove() {
+        use crate::ec_interface::GENESIS_BLOCK_ID;
+        let mut backend = MemoryBackend::new();
+        for token in [100, 200, 300] {
+            TokenStorageBackend::set(&mut backend, &token, &token, &GENESIS_BLOCK_ID, 10);
+        }
+
+        let removed = TokenStorageBackend::remove(&mut backend, &200).unwrap();
+        assert_eq!(removed.block, 200);
+        assert!(TokenStorageBackend::lookup(&backend, &200).is_none());
+        assert_eq!(TokenStorageBackend::len(&backend), 2);
+        assert!(TokenStorageBackend::remove(&mut backend, &200).is_none());
+
+        // Range scans skip the removed token and views stay aligned
+        let above: Vec<TokenId> = TokenStorageBackend::range_after(&backend, &0)
+            .map(|(token, _)| token)
+            .collect();
+        assert_eq!(above, vec![100, 300]);
+        assert_eq!(EcTokens::lookup(&backend.tokens, &300).unwrap().block, 300);
+
+        // A pending-only token is removed and its pending mapping returned
+        let highest = ConflictResolution::HighestBlockId;
+        backend
+            .tokens
+            .update_token_sync(&250, &7, &GENESIS_BLOCK_ID, 30, 1, highest);
+        let removed = TokenStorageBackend::remove(&mut backend, &250).unwrap();
+        assert_eq!((removed.block, removed.time), (7, 30));
+        assert_eq!(TokenStorageBackend::len(&backend), 2);
+        assert!(TokenStorageBackend::remove(&mut backend, &250).is_none());
+    }
+
+    #[test]
+    fn test_mem_tokens_retain_range() {
+        use crate::ec_interface::GENESIS_BLOCK_ID;
+        let step = TokenId::MAX / 16;
+        let all: Vec<TokenId> = (0..16).map(|i| i * step).collect();
+        let populated = || {
+            MemTokens::from_mappings(all.iter().map(|t| (*t, *t, GENESIS_BLOCK_ID, 1)).collect())
+        };
+        let stored = |storage: &MemTokens| -> Vec<TokenId> {
+            storage.tokens.iter().map(|(token, _)| *token).collect()
+        };
+
+        // Central range keeps only tokens inside it, still sorted
+        let mut storage = populated();
+        storage.retain_range(4 * step, 11 * step);
+        let kept: Vec<TokenId> = all[4..=11].to_vec();
+        assert_eq!(stored(&storage), kept);
+        for token in &all {
+            assert_eq!(
+                TokenStorageBackend::lookup(&storage, token).is_some(),
+                kept.contains(token)
+            );
+        }
+
+        // Wrapped range keeps both ends of the id space
+        let mut storage = populated();
+        storage.retain_range(14 * step, 2 * step);
+        let kept: Vec<TokenId> = [&all[..=2], &all[14..]].concat();
+        assert_eq!(stored(&storage), kept);
+        assert_eq!(EcTokens::lookup(&storage, &all[15]).unwrap().block, all[15]);
+    }
+
+    #[test]
+    fn test_mem_tokens_ec_tokens_lookup() {
+        use crate::ec_interface::GENESIS_BLOCK_ID;
+        let mut storage = MemTokens::new();
+        let tokens: &dyn EcTokens = &storage;
+        assert!(tokens.lookup(&100).is_none());
+
+        EcTokens::set(&mut storage, &100, &1, &GENESIS_BLOCK_ID, 10);
+        EcTokens::set(&mut storage, &100, &2, &1, 5); // Older time, ignored
+        let tokens: &dyn EcTokens = &storage;
+        let found = tokens.lookup(&100).unwrap();
+        assert_eq!(found.block, 1);
+        assert_eq!(found.time, 10);
+
+        EcTokens::set(&mut storage, &100, &3, &1, 20);
+        assert_eq!(EcTokens::lookup(&storage, &100).unwrap().block, 3);
+
+        // Pending-only tokens stay invisible until confirmed by a second peer
+        let highest = ConflictResolution::HighestBlockId;
+        storage.update_token_sync(&200, &7, &GENESIS_BLOCK_ID, 30, 1, highest);
+        assert!(EcTokens::lookup(&storage, &200).is_none());
+        storage.update_token_sync(&200, &7, &GENESIS_BLOCK_ID, 30, 2, highest);
+        assert_eq!(EcTokens::lookup(&storage, &200).unwrap().block, 7);
+
+        // Views stay aligned after inserting in front of existing tokens
+        EcTokens::set(&mut storage, &50, &9, &GENESIS_BLOCK_ID, 1);
+        assert_eq!(EcTokens::lookup(&storage, &50).unwrap().block, 9);
+        assert_eq!(EcTokens::lookup(&storage, &100).unwrap().block, 3);
+        assert_eq!(EcTokens::lookup(&storage, &200).unwrap().block, 7);
+    }
+
+    #[test]
+    fn test_conflict_resolution_strategies_pick_different_winners() {
+        use crate::ec_interface::GENESIS_BLOCK_ID;
+
+        let winner = |resolution| {
+            let mut tokens = MemTokens::new();
+            // Same competing updates, each from a different peer
+            tokens.update_token_sync(&1, &100, &GENESIS_BLOCK_ID, 50, 1, resolution);
+            tokens.update_token_sync(&1, &300, &GENESIS_BLOCK_ID, 70, 2, resolution);
+            tokens.update_token_sync(&1, &200, &GENESIS_BLOCK_ID, 40, 3, resolution);
+            let pending = tokens.lookup_state(&1).unwrap().pending.unwrap();
+            (pending.block, pending.source_peer)
+        };
+
+        assert_eq!(winner(ConflictResolution::HighestBlockId), (300, 2));
+        assert_eq!(winner(ConflictResolution::EarliestTime), (200, 3));
+        assert_eq!(winner(ConflictResolution::FirstSeen), (100, 1));
+
+        // The winner still needs a second peer; its reporter was reset
+        let mut tokens = MemTokens::new();
+        let earliest = ConflictResolution::EarliestTime;
+        tokens.update_token_sync(&1, &300, &GENESIS_BLOCK_ID, 70, 2, earliest);
+        tokens.update_token_sync(&1, &200, &GENESIS_BLOCK_ID, 40, 3, earliest);
+        tokens.update_token_sync(&1, &200, &GENESIS_BLOCK_ID, 40, 3, earliest);
+        assert!(tokens.lookup_state(&1).unwrap().current.is_none());
+        tokens.update_token_sync(&1, &200, &GENESIS_BLOCK_ID, 40, 4, earliest);
+        assert_eq!(tokens.lookup_current(&1).unwrap().block, 200);
+    }
+
+    #[test]
+    fn test_mem_tokens_extend_sorted_matches_set() {
+        use rand::{Rng, SeedableRng};
+        let mut rng = rand::rngs::StdRng::seed_from_u64(15);
+
+        // Pre-existing tokens, one of them pending-only
+        let mut one_by_one = MemTokens::new();
+        for token in (0..500u64).map(|i| i * 7) {
+            TokenStorageBackend::set(&mut one_by_one, &token, &1, &0, 50);
+        }
+        one_by_one.update_token_sync(&3, &9, &0, 10, 1, ConflictResolution::HighestBlockId);
+        let mut bulk = one_by_one.clone();
+
+        // Duplicates, older and newer times, and fresh tokens
+        let mappings: Vec<_> = (0..5_000)
+            .map(|i| {
+                let token = rng.gen_range(0..4_000u64);
+                (token, i as u64 + 2, 1, rng.gen_range(0..100u64))
+            })
+            .collect();
+
+        for (token, block, parent, time) in &mappings {
+            TokenStorageBackend::set(&mut one_by_one, token, block, parent, *time);
+        }
+        bulk.extend_sorted(mappings);
+
+        assert_eq!(bulk.tokens.len(), one_by_one.tokens.len());
+        for ((t1, s1), (t2, s2)) in bulk.tokens.iter().zip(&one_by_one.tokens) {
+            assert_eq!(t1, t2);
+            assert_eq!(s1, s2, "token {}", t1);
+            assert_eq!(
+                EcTokens::lookup(&bulk, t1).copied().map(|b| b.block),
+                s2.current.map(|c| c.block)
+            );
+        }
+    }
+
+    #[test]
+    fn test_mem_tokens_extend_sorted_large_load() {
+        // 100k tokens in reverse order would shift the whole Vec on every `set`;
+        // the bulk path sorts and merges once
+        let count = 100_000u64;
+        let mut storage = MemTokens::new();
+        storage.extend_sorted((0..count).rev().map(|i| (i * 3, i + 1, 0, 1)));
+
+        assert_eq!(TokenStorageBackend::len(&storage), count as usize);
+        assert!(storage.tokens.windows(2).all(|w| w[0].0 < w[1].0));
+        assert_eq!(
+            TokenStorageBackend::lookup(&storage, &300).unwrap().block,
+            101
+        );
+        assert!(TokenStorageBackend::lookup(&storage, &301).is_none());
+    }
+
     #[test]
     fn test_mem_tokens_with_proof_system() {
         use crate::ec_interface::GENESIS_BLOCK_ID;
@@ -1046,7 +1540,329 @@ mod tests {
 
         if let Some(sig) = result {
             assert_eq!(sig.answer.id, token);
-            assert_eq!(sig.signature.len(), 10);
+            assert_eq!(
+                sig.signature.len(),
+                crate::ec_interface::TOKENS_SIGNATURE_SIZE
+            );
+        }
+    }
+
+    #[test]
+    fn test_mem_tokens_search_respects_max_steps() {
+        use crate::ec_interface::GENESIS_BLOCK_ID;
+        let mut storage = MemTokens::new();
+
+        // Large sparse store: every token has its low 10 bits clear, so no
+        // token can match a chunk of 0x3FF and an unbounded search walks the
+        // whole ring twice
+        let count: u64 = 200_000;
+        for i in 1..=count {
+            TokenStorageBackend::set(&mut storage, &(i << 10), &i, &GENESIS_BLOCK_ID, 1);
+        }
+        let lookup = (count / 2) << 10;
+        let chunks = [0x3FFu16; 10];
+        let params = SignatureParams::default();
+
+        let unbounded = TokenStorageBackend::search_signature(
+            &storage,
+            &lookup,
+            &chunks,
+            &params,
+            5,
+            usize::MAX,
+        );
+        assert!(!unbounded.complete);
+        assert!(unbounded.steps >= 2 * (count as usize - 1));
+
+        let proof = ProofOfStorage::with_params_and_max_steps(params, 1_000);
+        let bounded =
+            proof.search_by_signature(&storage, &lookup, &chunks, params.forward_chunks());
+        assert!(!bounded.complete);
+        assert!(bounded.tokens.is_empty());
+        assert_eq!(bounded.steps, 1_000);
+
+        // generate_signature reports the exhausted search as no signature
+        assert!(proof.generate_signature(&storage, &lookup, &7).is_none());
+    }
+
+    #[test]
+    fn test_mem_tokens_search_splits_steps_by_direction() {
+        use crate::ec_interface::GENESIS_BLOCK_ID;
+        let mut storage = MemTokens::new();
+
+        // Slot n holds token n << 10 with the low bits below; everything else is 0
+        let low_bits = [(102, 1), (104, 2), (99, 3), (97, 4)];
+        for slot in 1..=200u64 {
+            let low = low_bits
+                .iter()
+                .find(|(s, _)| *s == slot)
+                .map_or(0, |(_, low)| *low);
+            TokenStorageBackend::set(
+                &mut storage,
+                &(slot << 10 | low),
+                &slot,
+                &GENESIS_BLOCK_ID,
+                1,
+            );
+        }
+        let params = SignatureParams {
+            chunks: 4,
+            bits: 10,
+        };
+
+        // Forward walks 101..=104 for chunks 1, 2; backward walks 99..=97 for 3, 4
+        let result = TokenStorageBackend::search_signature(
+            &storage,
+            &(100 << 10),
+            &[1, 2, 3, 4],
+            &params,
+            2,
+            usize::MAX,
+        );
+        assert!(result.complete);
+        assert_eq!(result.forward_steps, 4);
+        assert_eq!(result.backward_steps, 3);
+        assert_eq!(result.steps, result.forward_steps + result.backward_steps);
+        assert_eq!(result.chunks_matched, 4);
+        assert_eq!(result.chunks_matched, result.tokens.len());
+
+        // A budget that runs out going forward leaves nothing for backward
+        let bounded = TokenStorageBackend::search_signature(
+            &storage,
+            &(100 << 10),
+            &[1, 2, 3, 4],
+            &params,
+            2,
+            3,
+        );
+        assert_eq!((bounded.forward_steps, bounded.backward_steps), (3, 0));
+        assert_eq!(bounded.steps, 3);
+        assert_eq!(bounded.chunks_matched, 1);
+    }
+
+    #[test]
+    fn test_mem_tokens_forward_heavy_split_on_one_sided_store() {
+        use crate::ec_interface::GENESIS_BLOCK_ID;
+        let mut storage = MemTokens::new();
+
+        // Every token sits above the lookup point. Slots 1..=7 just above it
+        // carry chunks 1..=7, the top three slots carry 8, 9, 10 (highest
+        // first, as the backward pass wraps in from the top); filler is 0.
+        let count = 300u64;
+        for slot in 1..=count {
+            let low = match slot {
+                1..=7 => slot,
+                _ if slot > count - 3 => 8 + (count - slot),
+                _ => 0,
+            };
+            let token = (1_000 + slot) << 10 | low;
+            TokenStorageBackend::set(&mut storage, &token, &slot, &GENESIS_BLOCK_ID, 1);
+        }
+        let lookup = 1_000 << 10;
+        let chunks: Vec<u16> = (1..=10).collect();
+        let params = SignatureParams::default();
+        let budget = 50;
+
+        // Even split: chunks 6 and 7 are left to the backward pass, which has
+        // to walk down through all the filler to reach them
+        let even = TokenStorageBackend::search_signature(
+            &storage,
+            &lookup,
+            &chunks,
+            &params,
+            params.forward_chunks(),
+            budget,
+        );
+        assert_eq!(params.forward_chunks(), 5);
+        assert!(!even.complete);
+        assert_eq!(even.steps, budget);
+
+        // 7/3 split: seven forward steps, and the backward pass finds its
+        // three chunks at the top of the ring almost immediately
+        let skewed =
+            TokenStorageBackend::search_signature(&storage, &lookup, &chunks, &params, 7, budget);
+        assert!(skewed.complete);
+        assert_eq!(skewed.forward_steps, 7);
+        assert!(skewed.steps < 12);
+    }
+
+    #[test]
+    fn test_mem_tokens_range_empty_store() {
+        let storage = MemTokens::new();
+        assert_eq!(storage.range(0, TokenId::MAX).count(), 0);
+        assert_eq!(storage.range(100, 10).count(), 0);
+        assert_eq!(storage.range_after(&0).count(), 0);
+        assert_eq!(storage.range_before(&TokenId::MAX).count(), 0);
+    }
+
+    #[test]
+    fn test_mem_tokens_range_single_token() {
+        use crate::ec_interface::GENESIS_BLOCK_ID;
+        let mut storage = MemTokens::new();
+        TokenStorageBackend::set(&mut storage, &500, &7, &GENESIS_BLOCK_ID, 3);
+
+        let found: Vec<_> = storage.range(500, 501).collect();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].0, 500);
+        assert_eq!(found[0].1.block, 7);
+        assert_eq!(found[0].1.time, 3);
+        assert_eq!(storage.range(400, 500).count(), 0); // end is exclusive
+        assert_eq!(storage.range(500, 500).count(), 0);
+        assert_eq!(
+            storage
+                .range_after(&499)
+                .map(|(t, _)| t)
+                .collect::<Vec<_>>(),
+            vec![500]
+        );
+        assert_eq!(storage.range_after(&500).count(), 0);
+        assert_eq!(
+            storage
+                .range_before(&501)
+                .map(|(t, _)| t)
+                .collect::<Vec<_>>(),
+            vec![500]
+        );
+        assert_eq!(storage.range_before(&500).count(), 0);
+    }
+
+    #[test]
+    fn test_mem_tokens_range_wraparound() {
+        use crate::ec_interface::GENESIS_BLOCK_ID;
+        let mut storage = MemTokens::new();
+        for token in [
+            0,
+            1,
+            100,
+            TokenId::MAX - 100,
+            TokenId::MAX - 1,
+            TokenId::MAX,
+        ] {
+            TokenStorageBackend::set(&mut storage, &token, &1, &GENESIS_BLOCK_ID, 1);
+        }
+
+        // Range across the top of the ring continues from zero
+        let wrapped: Vec<_> = storage.range(TokenId::MAX - 1, 2).map(|(t, _)| t).collect();
+        assert_eq!(wrapped, vec![TokenId::MAX - 1, TokenId::MAX, 0, 1]);
+
+        // Ranges ending just before the top / starting at zero don't wrap
+        let top: Vec<_> = storage
+            .range(TokenId::MAX - 100, TokenId::MAX)
+            .map(|(t, _)| t)
+            .collect();
+        assert_eq!(top, vec![TokenId::MAX - 100, TokenId::MAX - 1]);
+        let bottom: Vec<_> = storage.range(0, 100).map(|(t, _)| t).collect();
+        assert_eq!(bottom, vec![0, 1]);
+
+        // range_after / range_before stop at the ends of the token space
+        assert_eq!(
+            storage
+                .range_after(&(TokenId::MAX - 1))
+                .map(|(t, _)| t)
+                .collect::<Vec<_>>(),
+            vec![TokenId::MAX]
+        );
+        assert_eq!(storage.range_after(&TokenId::MAX).count(), 0);
+        assert_eq!(
+            storage.range_before(&1).map(|(t, _)| t).collect::<Vec<_>>(),
+            vec![0]
+        );
+        assert_eq!(
+            storage
+                .range_before(&101)
+                .take(2)
+                .map(|(t, _)| t)
+                .collect::<Vec<_>>(),
+            vec![100, 1]
+        );
+    }
+
+    #[test]
+    fn test_count_in_range_matches_default_walk() {
+        use crate::ec_interface::GENESIS_BLOCK_ID;
+
+        /// Forwards everything but count_in_range, so the trait default is used
+        struct DefaultCount<'a>(&'a MemTokens);
+
+        impl<'a> TokenStorageBackend for DefaultCount<'a> {
+            fn lookup(&self, token: &TokenId) -> Option<BlockTime> {
+                TokenStorageBackend::lookup(self.0, token)
+            }
+
+            fn set(
+                &mut self,
+                _token: &TokenId,
+                _block: &BlockId,
+                _parent: &BlockId,
+                _time: EcTime,
+            ) {
+                unreachable!()
+            }
+
+            fn remove(&mut self, _token: &TokenId) -> Option<BlockTime> {
+                unreachable!()
+            }
+
+            fn search_signature(
+                &self,
+                _lookup_token: &TokenId,
+                _signature_chunks: &[u16],
+                _params: &SignatureParams,
+                _forward_chunks: usize,
+                _max_steps: usize,
+            ) -> crate::ec_proof_of_storage::SignatureSearchResult {
+                unreachable!()
+            }
+
+            fn range_after(
+                &self,
+                token: &TokenId,
+            ) -> Box<dyn Iterator<Item = (TokenId, BlockTime)> + '_> {
+                TokenStorageBackend::range_after(self.0, token)
+            }
+
+            fn len(&self) -> usize {
+                TokenStorageBackend::len(self.0)
+            }
+        }
+
+        let empty = MemTokens::new();
+        for storage in [&empty as &dyn TokenStorageBackend, &DefaultCount(&empty)] {
+            assert_eq!(storage.count_in_range(&0, &TokenId::MAX), 0);
+            assert_eq!(storage.count_in_range(&100, &10), 0);
+        }
+
+        let mut storage = MemTokens::new();
+        for token in [0, 1, 100, 200, TokenId::MAX - 1, TokenId::MAX] {
+            TokenStorageBackend::set(&mut storage, &token, &1, &GENESIS_BLOCK_ID, 1);
+        }
+        // Pending-only tokens have no current mapping and are not counted
+        let highest = ConflictResolution::HighestBlockId;
+        for token in [150, TokenId::MAX - 2] {
+            storage.update_token_sync(&token, &1, &GENESIS_BLOCK_ID, 1, 7, highest);
+        }
+        assert!(TokenStorageBackend::lookup(&storage, &150).is_none());
+        let cases = [
+            // (start, end, expected)
+            (1, 200, 2),              // start inclusive, end exclusive
+            (2, 100, 0),              // gap between tokens
+            (100, 100, 0),            // empty segment
+            (0, TokenId::MAX, 5),     // everything but the top token
+            (TokenId::MAX - 1, 2, 4), // wraps past MAX to 0 and 1
+            (TokenId::MAX, 0, 1),     // wraps with an empty low side
+            (201, 200, 5),            // wraps round to just below 200
+        ];
+        for (start, end, expected) in cases {
+            assert_eq!(
+                storage.count_in_range(&start, &end),
+                expected,
+                "{start}..{end}"
+            );
+            assert_eq!(
+                DefaultCount(&storage).count_in_range(&start, &end),
+                expected,
+                "default {start}..{end}"
+            );
         }
     }
 
@@ -1137,6 +1953,34 @@ mod tests {
         assert_eq!(retrieved.used, 2);
     }
 
+    #[test]
+    fn test_mem_blocks_iter_yields_all_blocks() {
+        let mut storage = MemBlocks::new();
+        assert!(storage.is_empty());
+        assert_eq!(storage.iter().count(), 0);
+
+        for (id, time) in [(10, 1000), (20, 2000), (30, 3000)] {
+            storage.save(&Block {
+                id,
+                time,
+                used: 1,
+                parts: [TokenBlock::default(); 6],
+                signatures: [None; 6],
+            });
+        }
+
+        assert_eq!(storage.len(), 3);
+        let mut seen: Vec<_> = storage
+            .iter()
+            .map(|(id, block)| {
+                assert_eq!(*id, block.id);
+                (*id, block.time)
+            })
+            .collect();
+        seen.sort_unstable();
+        assert_eq!(seen, vec![(10, 1000), (20, 2000), (30, 3000)]);
+    }
+
     // ========================================================================
     // Combined Backend Tests
     // ========================================================================
@@ -1359,4 +2203,299 @@ mod tests {
 
         // Should succeed with no changes
     }
+
+    #[test]
+    fn test_memory_backend_snapshot_is_independent() {
+        let block = |id: BlockId| Block {
+            id,
+            time: 100,
+            used: 0,
+            parts: [TokenBlock::default(); 6],
+            signatures: [None; 6],
+        };
+
+        let mut backend = MemoryBackend::new_with_peer_id(42);
+        TokenStorageBackend::set(backend.tokens_mut(), &1, &10, &0, 100);
+        backend.blocks_mut().save(&block(10));
+
+        let snapshot = backend.snapshot();
+
+        // Mutate the original after snapshotting
+        TokenStorageBackend::set(backend.tokens_mut(), &1, &11, &10, 200);
+        TokenStorageBackend::set(backend.tokens_mut(), &2, &12, &0, 200);
+        backend.blocks_mut().save(&block(11));
+
+        let mapping = TokenStorageBackend::lookup(snapshot.tokens(), &1).unwrap();
+        assert_eq!(mapping.block, 10);
+        assert_eq!(mapping.time, 100);
+        assert!(TokenStorageBackend::lookup(snapshot.tokens(), &2).is_none());
+        assert!(snapshot.blocks().exists(&10));
+        assert!(!snapshot.blocks().exists(&11));
+
+        assert_eq!(
+            TokenStorageBackend::lookup(backend.tokens(), &1)
+                .unwrap()
+                .block,
+            11
+        );
+        assert!(TokenStorageBackend::lookup(backend.tokens(), &2).is_some());
+    }
+
+    #[test]
+    fn test_commit_block_with_known_blocks_surfaces_parent_request() {
+        use crate::ec_commit_chain::TickMessage;
+        use crate::ec_interface::EcCommitChainAccess;
+
+        let mut backend = MemoryBackend::new_with_peer_id(500);
+        let mut peers = crate::ec_peers::EcPeers::new(500);
+        let mut mempool = crate::ec_mempool::EcMemPool::new();
+
+        // Peer 42's head commits block 10, which we already store
+        backend.blocks_mut().save(&Block {
+            id: 10,
+            time: 900,
+            used: 0,
+            parts: [TokenBlock::default(); 6],
+            signatures: [None; 6],
+        });
+        let head = CommitBlock::new(999, 77, 1000, vec![10]);
+        peers.update_peer(&42, 0);
+        peers.update_peer_commit_chain_head(&42, head.id);
+
+        let ticket = match &backend.commit_chain_tick(&peers, &mut mempool, 1000, &mut NoOpSink)[..]
+        {
+            [(
+                42,
+                TickMessage::QueryCommitBlock {
+                    block_id: 999,
+                    ticket,
+                },
+            )] => *ticket,
+            other => panic!("expected a head query to peer 42, got {:?}", other),
+        };
+
+        let request = backend.handle_commit_block(head, 42, ticket, 1000).unwrap();
+        assert_eq!(request.receiver, 42);
+        assert_eq!(request.block_id, 77);
+
+        // The tick doesn't query the parent a second time
+        let messages = backend.commit_chain_tick(&peers, &mut mempool, 1001, &mut NoOpSink);
+        assert!(messages.is_empty());
+    }
+
+    #[test]
+    fn test_parent_requests_per_tick_are_capped_between_ticks() {
+        use crate::ec_commit_chain::{CommitChainConfig, TickMessage};
+        use crate::ec_interface::EcCommitChainAccess;
+
+        let config = CommitChainConfig {
+            parent_requests_per_tick: 3,
+            ..Default::default()
+        };
+        let mut backend = MemoryBackend::with_commit_chain_config(500, config);
+        let mut peers = crate::ec_peers::EcPeers::new(500);
+        let mut mempool = crate::ec_mempool::EcMemPool::new();
+
+        // Peer 42 serves a deep chain 1000 <- 999 <- ... whose blocks we all store
+        backend.blocks_mut().save(&Block {
+            id: 10,
+            time: 900,
+            used: 0,
+            parts: [TokenBlock::default(); TOKENS_PER_BLOCK],
+            signatures: [None; TOKENS_PER_BLOCK],
+        });
+        let commit_block = |id: CommitBlockId| CommitBlock::new(id, id - 1, id, vec![10]);
+        peers.update_peer(&42, 0);
+        peers.update_peer_commit_chain_head(&42, 1000);
+
+        let ticket = match &backend.commit_chain_tick(&peers, &mut mempool, 1000, &mut NoOpSink)[..]
+        {
+            [(42, TickMessage::QueryCommitBlock { ticket, .. })] => *ticket,
+            other => panic!("expected a head query to peer 42, got {:?}", other),
+        };
+
+        // Answer every parent request at once, as an eager peer would
+        let mut answer = backend.handle_commit_block(commit_block(1000), 42, ticket, 1000);
+        let mut requested = Vec::new();
+        while let Some(request) = answer {
+            assert_eq!(request.receiver, 42);
+            requested.push(request.block_id);
+            answer = backend.handle_commit_block(
+                commit_block(request.block_id),
+                42,
+                request.ticket,
+                1000,
+            );
+        }
+        assert_eq!(requested, vec![999, 998, 997]);
+
+        // The tick steps the trace once and refills the budget
+        let messages = backend.commit_chain_tick(&peers, &mut mempool, 1001, &mut NoOpSink);
+        let ticket = match &messages[..] {
+            [(
+                42,
+                TickMessage::QueryCommitBlock {
+                    block_id: 996,
+                    ticket,
+                },
+            )] => *ticket,
+            other => panic!("expected the tick to query 996, got {:?}", other),
+        };
+        let request = backend.handle_commit_block(commit_block(996), 42, ticket, 1001);
+        assert_eq!(request.map(|r| r.block_id), Some(995));
+    }
+
+    #[test]
+    fn test_commit_chain_tick_runs_every_sync_interval() {
+        use crate::ec_commit_chain::{CommitChainConfig, TickMessage};
+        use crate::ec_interface::EcCommitChainAccess;
+
+        let config = CommitChainConfig {
+            sync_interval: 10,
+            ..Default::default()
+        };
+        let mut backend = MemoryBackend::with_commit_chain_config(500, config);
+        let mut peers = crate::ec_peers::EcPeers::new(500);
+        let mut mempool = crate::ec_mempool::EcMemPool::new();
+        peers.update_peer(&42, 0);
+        peers.update_peer_commit_chain_head(&42, 999);
+
+        let ticket = match &backend.commit_chain_tick(&peers, &mut mempool, 0, &mut NoOpSink)[..] {
+            [(42, TickMessage::QueryCommitBlock { ticket, .. })] => *ticket,
+            other => panic!("expected a head query to peer 42, got {:?}", other),
+        };
+
+        // Block 10 never arrives, so every sync tick asks for it again
+        let head = CommitBlock::new(999, 77, 1000, vec![10]);
+        assert!(backend.handle_commit_block(head, 42, ticket, 0).is_none());
+
+        let synced: Vec<EcTime> = (1..=20)
+            .filter(|&time| {
+                !backend
+                    .commit_chain_tick(&peers, &mut mempool, time, &mut NoOpSink)
+                    .is_empty()
+            })
+            .collect();
+        assert_eq!(synced, vec![10, 20]);
+    }
+
+    #[test]
+    fn test_ticket_secret_makes_commit_chain_tickets_reproducible() {
+        use crate::ec_commit_chain::{CommitChainConfig, TickMessage};
+        use crate::ec_interface::EcCommitChainAccess;
+
+        let mut peers = crate::ec_peers::EcPeers::new(500);
+        peers.update_peer(&42, 0);
+        peers.update_peer_commit_chain_head(&42, 999);
+        let head_ticket = |backend: &mut MemoryBackend| {
+            let mut mempool = crate::ec_mempool::EcMemPool::new();
+            match &backend.commit_chain_tick(&peers, &mut mempool, 1000, &mut NoOpSink)[..] {
+                [(42, TickMessage::QueryCommitBlock { ticket, .. })] => *ticket,
+                other => panic!("expected a head query to peer 42, got {:?}", other),
+            }
+        };
+        let backend =
+            || MemoryBackend::with_ticket_secret(500, CommitChainConfig::default(), [7; 32]);
+
+        let (mut a, mut b) = (backend(), backend());
+        let before = head_ticket(&mut a);
+        assert_eq!(before, head_ticket(&mut b));
+
+        // Restarts derive a fresh secret, identically on both
+        a.reset_runtime_state();
+        b.reset_runtime_state();
+        let after = head_ticket(&mut a);
+        assert_ne!(after, before);
+        assert_eq!(after, head_ticket(&mut b));
+    }
+
+    #[test]
+    fn test_commit_chain_config_limits_tracked_peers() {
+        use crate::ec_commit_chain::{CommitChainConfig, TickMessage};
+        use crate::ec_interface::EcCommitChainAccess;
+
+        let mut peers = crate::ec_peers::EcPeers::new(500);
+        for peer_id in [100, 200, 300, 400] {
+            peers.update_peer(&peer_id, 0);
+            peers.update_peer_commit_chain_head(&peer_id, 999);
+        }
+        let queried = |backend: &mut MemoryBackend| {
+            let mut mempool = crate::ec_mempool::EcMemPool::new();
+            backend
+                .commit_chain_tick(&peers, &mut mempool, 1000, &mut NoOpSink)
+                .iter()
+                .filter(|(_, message)| matches!(message, TickMessage::QueryCommitBlock { .. }))
+                .count()
+        };
+
+        assert_eq!(queried(&mut MemoryBackend::new_with_peer_id(500)), 4);
+
+        let config = CommitChainConfig {
+            max_tracked_peers: 1,
+            ..Default::default()
+        };
+        let mut backend = MemoryBackend::with_commit_chain_config(500, config);
+        assert_eq!(queried(&mut backend), 1);
+
+        // A restart rebuilds the chain with the same configuration
+        backend.reset_runtime_state();
+        assert_eq!(backend.commit_chain().config().max_tracked_peers, 1);
+        assert_eq!(queried(&mut backend), 1);
+    }
+
+    #[test]
+    fn test_rolled_back_or_failed_batch_leaves_backend_unchanged() {
+        use crate::ec_interface::EcCommitChainAccess;
+
+        fn block(id: BlockId) -> Block {
+            Block {
+                id,
+                time: 100,
+                used: 1,
+                parts: [TokenBlock::default(); 6],
+                signatures: [None; 6],
+            }
+        }
+        fn stage(backend: &mut MemoryBackend) -> Box<dyn StorageBatch + '_> {
+            let mut batch = backend.begin_batch();
+            batch.save_block(&block(11));
+            batch.update_token(&1, &11, &10, 200);
+            batch.update_token(&2, &11, &0, 200);
+            batch
+        }
+        let unchanged = |backend: &MemoryBackend| {
+            assert_eq!(
+                TokenStorageBackend::lookup(backend.tokens(), &1)
+                    .unwrap()
+                    .block,
+                10
+            );
+            assert!(TokenStorageBackend::lookup(backend.tokens(), &2).is_none());
+            assert!(!backend.blocks().exists(&11));
+        };
+
+        let mut backend = MemoryBackend::new_with_peer_id(42);
+        TokenStorageBackend::set(backend.tokens_mut(), &1, &10, &0, 100);
+        backend.blocks_mut().save(&block(10));
+
+        stage(&mut backend).rollback();
+        unchanged(&backend);
+        assert_eq!(backend.get_commit_chain_head(), None);
+
+        backend.fail_next_commit();
+        assert!(stage(&mut backend).commit().is_err());
+        unchanged(&backend);
+        assert_eq!(backend.get_commit_chain_head(), None);
+
+        // The injected failure is one-shot
+        stage(&mut backend).commit().unwrap();
+        assert_eq!(
+            TokenStorageBackend::lookup(backend.tokens(), &1)
+                .unwrap()
+                .block,
+            11
+        );
+        assert!(backend.blocks().exists(&11));
+        assert!(backend.get_commit_chain_head().is_some());
+    }
 }
@@ -4,11 +4,12 @@ use indexmap::IndexMap;
 use std::cmp::Reverse;
 
 use crate::ec_interface::{
-    Block, BlockId, EcBlocks, EcTime, EcTokensV2, Event, EventSink, PeerId, PublicKeyReference,
-    Signature, TokenId, SOME_STEPS_INTO_THE_FUTURE, TOKENS_PER_BLOCK, VOTE_THRESHOLD,
+    Block, BlockError, BlockId, EcBlocks, EcTime, EcTokensV2, Event, EventSink, PeerId,
+    PublicKeyReference, Signature, TokenId, SOME_STEPS_INTO_THE_FUTURE, TOKENS_PER_BLOCK,
+    VOTE_THRESHOLD,
 };
 use crate::ec_mempool::BlockState::Pending;
-use crate::ec_peers::{EcPeers, PeerRange};
+use crate::ec_peers::{EcPeers, PeerManagerConfig, PeerRange};
 
 #[derive(PartialEq, Clone, Debug)]
 pub enum BlockState {
@@ -123,6 +124,8 @@ struct PoolBlockState {
     vote_sequence: [u8; TOKENS_PER_BLOCK + 1],
     /// Last time we requested missing parents for this block (cooldown tracking)
     last_parent_fetch: EcTime,
+    /// Submitter-supplied priority; None falls back to the token count
+    priority: Option<u64>,
 }
 
 impl PoolBlockState {
@@ -139,9 +142,16 @@ impl PoolBlockState {
             competing_block: None,
             vote_sequence: [0; TOKENS_PER_BLOCK + 1],
             last_parent_fetch: 0,
+            priority: None,
         }
     }
 
+    /// Sort key for candidate ordering: highest priority first, then oldest block
+    fn candidate_key(&self, block: &Block, block_id: BlockId) -> (Reverse<u64>, EcTime, BlockId) {
+        let priority = self.priority.unwrap_or(block.used as u64);
+        (Reverse(priority), block.time, block_id)
+    }
+
     fn vote(&mut self, peer: &PeerId, vote: u8, time: EcTime, reply: bool) {
         let pv = self
             .votes
@@ -189,11 +199,63 @@ pub struct InitialVoteRequest {
     pub vote: u8,
 }
 
+/// Voting policy of the mempool
+#[derive(Debug, Clone)]
+pub struct MemPoolConfig {
+    /// Net positive votes a block needs, in each token's range and in the
+    /// witness range, before it commits (default: `VOTE_THRESHOLD + 1`)
+    pub commit_threshold: usize,
+
+    /// See `PeerManagerConfig::vote_request_active_rounds` (default: 4)
+    pub vote_request_active_rounds: u8,
+
+    /// See `PeerManagerConfig::vote_request_pairs_per_tick` (default: 1)
+    pub vote_request_pairs_per_tick: u8,
+
+    /// Ticks an entry may stay in the pool before it is evicted (default: 200)
+    ///
+    /// Applies to entries that never reach quorum; committed entries are
+    /// dropped too, as their blocks are already in storage.
+    pub max_block_age: EcTime,
+}
+
+impl Default for MemPoolConfig {
+    fn default() -> Self {
+        Self {
+            commit_threshold: VOTE_THRESHOLD as usize + 1,
+            vote_request_active_rounds: 4,
+            vote_request_pairs_per_tick: 1,
+            max_block_age: 200,
+        }
+    }
+}
+
+impl MemPoolConfig {
+    /// Policy equivalent to a "balance must exceed `vote_balance_threshold`" rule
+    pub fn from_vote_balance_threshold(vote_balance_threshold: i64) -> Self {
+        Self {
+            commit_threshold: vote_balance_threshold.saturating_add(1).max(0) as usize,
+            ..Self::default()
+        }
+    }
+}
+
+impl From<&PeerManagerConfig> for MemPoolConfig {
+    fn from(config: &PeerManagerConfig) -> Self {
+        Self {
+            vote_request_active_rounds: config.vote_request_active_rounds,
+            vote_request_pairs_per_tick: config.vote_request_pairs_per_tick,
+            ..Self::from_vote_balance_threshold(config.vote_balance_threshold)
+        }
+    }
+}
+
 pub struct EcMemPool {
     pool: IndexMap<BlockId, PoolBlockState>,
     vote_balance_threshold: i64,
     vote_request_active_rounds: u8,
     vote_request_pairs_per_tick: u8,
+    max_block_age: EcTime,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -212,6 +274,41 @@ pub struct MempoolDiagnostics {
     pub pending_age_200_plus: usize,
 }
 
+/// Why a block was refused admission to the mempool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockRejection {
+    /// The block is already in the pool or committed
+    Duplicate,
+    /// `used` is 0: the block updates no token
+    EmptyBlock,
+    /// `used` doesn't leave room in the block's parts (must be below `TOKENS_PER_BLOCK`)
+    InvalidUsedCount { used: u8 },
+    /// Block time is more than `SOME_STEPS_INTO_THE_FUTURE` ahead of ours
+    TimeInFuture { time: EcTime },
+    /// Parts or signatures don't agree with `used` (see `Block::validate`)
+    Malformed(BlockError),
+    /// `token` is already spent from the same parent by `conflicting_block`
+    DoubleSpend {
+        token: TokenId,
+        conflicting_block: BlockId,
+    },
+    /// Block time is not after our current mapping of `token` (storage would ignore the update)
+    StaleTime {
+        token: TokenId,
+        mapping_time: EcTime,
+    },
+}
+
+/// Read-only view of one mempool entry (see `EcMemPool::block_status`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockStatus {
+    pub state: BlockState,
+    /// False while the entry is only a vote placeholder awaiting the block itself
+    pub has_block: bool,
+    /// Number of distinct peers that have voted on the block
+    pub votes: usize,
+}
+
 fn validate_signature(key: &PublicKeyReference, signature: &Signature) -> bool {
     // TODO real validation of signature <-> public-key-hash
     key == signature
@@ -241,11 +338,24 @@ fn validate_with_parent(parent: &Block, block: &Block, i: usize) -> bool {
 
 impl EcMemPool {
     pub fn new() -> Self {
-        Self::with_vote_balance_threshold(VOTE_THRESHOLD)
+        Self::with_config(MemPoolConfig::default())
+    }
+
+    pub fn with_config(config: MemPoolConfig) -> Self {
+        Self {
+            pool: IndexMap::new(),
+            // Internally a balance must strictly exceed the threshold
+            vote_balance_threshold: config.commit_threshold as i64 - 1,
+            vote_request_active_rounds: config.vote_request_active_rounds.max(1),
+            vote_request_pairs_per_tick: config.vote_request_pairs_per_tick.max(1),
+            max_block_age: config.max_block_age,
+        }
     }
 
     pub fn with_vote_balance_threshold(vote_balance_threshold: i64) -> Self {
-        Self::with_vote_policy(vote_balance_threshold, 0, 4, 1)
+        Self::with_config(MemPoolConfig::from_vote_balance_threshold(
+            vote_balance_threshold,
+        ))
     }
 
     pub fn with_vote_policy(
@@ -254,12 +364,11 @@ impl EcMemPool {
         vote_request_active_rounds: u8,
         vote_request_pairs_per_tick: u8,
     ) -> Self {
-        Self {
-            pool: IndexMap::new(),
-            vote_balance_threshold,
-            vote_request_active_rounds: vote_request_active_rounds.max(1),
-            vote_request_pairs_per_tick: vote_request_pairs_per_tick.max(1),
-        }
+        Self::with_config(MemPoolConfig {
+            vote_request_active_rounds,
+            vote_request_pairs_per_tick,
+            ..MemPoolConfig::from_vote_balance_threshold(vote_balance_threshold)
+        })
     }
 
     pub fn diagnostics(&self, time: EcTime) -> MempoolDiagnostics {
@@ -311,14 +420,81 @@ impl EcMemPool {
         diagnostics
     }
 
+    /// State and vote tally of a block in the pool
+    ///
+    /// Returns None for blocks the pool doesn't know, including committed
+    /// blocks once `cleanup_expired` has dropped them (use the block store for those).
+    pub fn block_status(&self, block_id: &BlockId) -> Option<BlockStatus> {
+        self.pool.get(block_id).map(|state| BlockStatus {
+            state: state.state.clone(),
+            has_block: state.block.is_some(),
+            votes: state.votes.len(),
+        })
+    }
+
+    /// Ids of blocks still collecting votes, in arrival order
+    pub fn pending_blocks(&self) -> impl Iterator<Item = BlockId> + '_ {
+        self.pool
+            .iter()
+            .filter(|(_, state)| state.state == Pending)
+            .map(|(block_id, _)| *block_id)
+    }
+
+    /// Top `n` pending blocks in voting/commit order
+    ///
+    /// Blocks are ordered by priority descending (token count when none was
+    /// supplied), ties broken by block time and then block id.
+    pub fn next_candidates(&self, n: usize) -> Vec<BlockId> {
+        let mut candidates: Vec<_> = self
+            .pool
+            .iter()
+            .filter(|(_, state)| state.state == Pending)
+            .filter_map(|(block_id, state)| {
+                let block = state.block.as_ref()?;
+                Some(state.candidate_key(block, *block_id))
+            })
+            .collect();
+        candidates.sort_unstable();
+        candidates
+            .into_iter()
+            .take(n)
+            .map(|(_, _, id)| id)
+            .collect()
+    }
+
     /// Clean up expired blocks from the pool
     ///
-    /// Removes blocks that are too old (haven't committed within the timeout period).
+    /// Removes entries older than `max_block_age`, together with the votes
+    /// collected for them, so an evicted block id can be submitted again fresh.
+    /// Uncommitted evictions are logged as a `BlockStateChange` to "evicted".
     /// Should be called at the start of each tick before evaluation.
-    pub(crate) fn cleanup_expired(&mut self, time: EcTime) {
-        // TODO: Make timeout configurable? Currently 200 ticks
-        self.pool
-            .retain(|_, state| time.saturating_sub(state.time) < 200);
+    pub(crate) fn cleanup_expired(
+        &mut self,
+        time: EcTime,
+        id: PeerId,
+        event_sink: &mut dyn EventSink,
+    ) {
+        let max_block_age = self.max_block_age;
+        self.pool.retain(|block_id, state| {
+            if time.saturating_sub(state.time) < max_block_age {
+                return true;
+            }
+            let from_state = match state.state {
+                BlockState::Pending => "pending",
+                BlockState::Blocked => "blocked",
+                BlockState::Commit => return false,
+            };
+            event_sink.log(
+                time,
+                id,
+                Event::BlockStateChange {
+                    block_id: *block_id,
+                    from_state,
+                    to_state: "evicted",
+                },
+            );
+            false
+        });
     }
 
     /// Evaluate all pending blocks and determine which can proceed to commit
@@ -357,11 +533,18 @@ impl EcMemPool {
             for i in 0..block.used as usize {
                 let token_id = block.parts[i].token;
                 let last_mapping = block.parts[i].last;
-                let current_mapping = tokens.lookup_current(&token_id).map_or(0, |t| t.block);
+                let current = tokens.lookup_current(&token_id);
+                let current_mapping = current.map_or(0, |t| t.block);
 
                 if current_mapping == last_mapping {
-                    // Chain is correct - we can verify this
-                    vote |= 1 << i;
+                    if current.is_some_and(|t| block.time <= t.time) {
+                        // Not newer than our mapping: committing would leave the token
+                        // unchanged. Never commit it here; it ages out of the pool.
+                        can_commit = false;
+                    } else {
+                        // Chain is correct - we can verify this
+                        vote |= 1 << i;
+                    }
                 } else if current_mapping == 0 {
                     // We don't have this token - cannot verify
                     // vote bit stays 0 (negative vote)
@@ -401,6 +584,11 @@ impl EcMemPool {
             // Blocks with reorg/missing history stay in mempool but won't be processed this tick
         }
 
+        // Vote and commit in candidate order (see next_candidates)
+        evaluations.sort_by_cached_key(|evaluation| {
+            self.pool[&evaluation.block_id].candidate_key(&evaluation.block, evaluation.block_id)
+        });
+
         (evaluations, messages)
     }
 
@@ -537,7 +725,9 @@ impl EcMemPool {
             return false;
         }
 
-        if block.used as usize >= TOKENS_PER_BLOCK || block.time > time + SOME_STEPS_INTO_THE_FUTURE
+        if block.used as usize >= TOKENS_PER_BLOCK
+            || block.time > time + SOME_STEPS_INTO_THE_FUTURE
+            || block.validate().is_err()
         {
             // TODO same token only once
 
@@ -562,21 +752,105 @@ impl EcMemPool {
     }
 
     pub(crate) fn block(&mut self, block: &Block, time: EcTime) -> bool {
-        self.add_block(block, time)
+        self.block_with_priority(block, time, None)
+    }
+
+    pub(crate) fn block_with_priority(
+        &mut self,
+        block: &Block,
+        time: EcTime,
+        priority: Option<u64>,
+    ) -> bool {
+        if !self.add_block(block, time) {
+            return false;
+        }
+        if let Some(state) = self.pool.get_mut(&block.id) {
+            state.priority = priority;
+        }
+        true
     }
 
     fn blocks_conflict(existing: &Block, new_block: &Block) -> bool {
+        Self::conflicting_token(existing, new_block).is_some()
+    }
+
+    /// First token both blocks update from the same parent
+    fn conflicting_token(existing: &Block, new_block: &Block) -> Option<TokenId> {
         for existing_idx in 0..existing.used as usize {
             for new_idx in 0..new_block.used as usize {
                 if existing.parts[existing_idx].token == new_block.parts[new_idx].token
                     && existing.parts[existing_idx].last == new_block.parts[new_idx].last
                 {
-                    return true;
+                    return Some(new_block.parts[new_idx].token);
                 }
             }
         }
 
-        false
+        None
+    }
+
+    /// Check that `block` is well formed, new, and spends no parent twice
+    ///
+    /// Shape and time limits match the ones `add_block` enforces by marking
+    /// the block Blocked. A parent counts as spent when our committed mapping
+    /// for the token has it as parent, or when a pending or committed pool
+    /// entry updates the token from it. Blocked entries have already lost and
+    /// are ignored. The block must also be newer than every current mapping it
+    /// touches, since storage keeps the newer mapping.
+    pub(crate) fn check_admission(
+        &self,
+        block: &Block,
+        tokens: &dyn EcTokensV2,
+        blocks: &dyn EcBlocks,
+        time: EcTime,
+    ) -> Result<(), BlockRejection> {
+        if block.used == 0 {
+            return Err(BlockRejection::EmptyBlock);
+        }
+        if block.used as usize >= TOKENS_PER_BLOCK {
+            return Err(BlockRejection::InvalidUsedCount { used: block.used });
+        }
+        if block.time > time + SOME_STEPS_INTO_THE_FUTURE {
+            return Err(BlockRejection::TimeInFuture { time: block.time });
+        }
+        block.validate().map_err(BlockRejection::Malformed)?;
+        if self.query(&block.id, blocks).is_some() {
+            return Err(BlockRejection::Duplicate);
+        }
+
+        for part in block.parts.iter().take(block.used as usize) {
+            if let Some(current) = tokens.lookup_current(&part.token) {
+                if current.parent == part.last && current.block != block.id {
+                    return Err(BlockRejection::DoubleSpend {
+                        token: part.token,
+                        conflicting_block: current.block,
+                    });
+                }
+                if block.time <= current.time {
+                    return Err(BlockRejection::StaleTime {
+                        token: part.token,
+                        mapping_time: current.time,
+                    });
+                }
+            }
+        }
+
+        for (&existing_block_id, existing_state) in &self.pool {
+            if existing_block_id == block.id || existing_state.state == BlockState::Blocked {
+                continue;
+            }
+            let Some(existing_block) = existing_state.block else {
+                continue;
+            };
+            if let Some(token) = Self::conflicting_token(&existing_block, block) {
+                return Err(BlockRejection::DoubleSpend {
+                    token,
+                    conflicting_block: existing_block_id,
+                });
+            }
+        }
+
+        Ok(())
     }
 
     fn vote_mask_for_block(block: &Block, tokens: &dyn EcTokensV2) -> u8 {
@@ -1009,11 +1283,13 @@ mod tests {
     use std::rc::Rc;
 
     use crate::ec_interface::{
-        EcTokens, EcTokensV2, NoOpSink, StorageBatch, TokenBlock, TokenState, TrustedMapping,
+        ConflictResolution, EcTokens, EcTokensV2, NoOpSink, StorageBatch, TokenBlock, TokenState,
+        TrustedMapping,
     };
     use crate::ec_peers::{EcPeers, PeerManagerConfig};
     use rand::SeedableRng;
 
+    #[derive(Default)]
     struct MockEcBlocks {
         blocks: hashbrown::HashMap<BlockId, Block>,
     }
@@ -1083,14 +1359,21 @@ mod tests {
             _parent: &BlockId,
             _time: EcTime,
             _source_peer: PeerId,
+            _resolution: ConflictResolution,
         ) {
             panic!("sync updates are not used in mempool tests");
         }
 
+        fn expire_pending(&mut self, _token: &TokenId, _block: &BlockId) {
+            panic!("sync updates are not used in mempool tests");
+        }
+
         fn commit(self: Box<Self>) -> Result<(), Box<dyn std::error::Error>> {
             Ok(())
         }
 
+        fn rollback(self: Box<Self>) {}
+
         fn block_count(&self) -> usize {
             self.saved_blocks.len()
         }
@@ -1172,6 +1455,245 @@ mod tests {
         assert_eq!(mem_pool.query(&block_id, &*blocks.borrow()), Some(block));
     }
 
+    #[test]
+    fn block_status_reports_votes_and_commit_transition() {
+        let block = test_block(100, 250, 0);
+        let mut mem_pool = EcMemPool::with_vote_balance_threshold(1);
+        let peers = test_peers();
+
+        // A vote can arrive before the block itself
+        mem_pool.vote(&block.id, 0b0000_0001, &100, 10, true);
+        assert_eq!(
+            mem_pool.block_status(&block.id),
+            Some(BlockStatus {
+                state: BlockState::Pending,
+                has_block: false,
+                votes: 1,
+            })
+        );
+        assert!(mem_pool.block_status(&999).is_none());
+
+        assert!(mem_pool.block(&block, 10));
+        mem_pool.vote(&block.id, 0b0000_0001, &200, 10, true);
+        // A repeated vote from the same peer doesn't add to the tally
+        mem_pool.vote(&block.id, 0b0000_0001, &200, 11, true);
+        let status = mem_pool.block_status(&block.id).unwrap();
+        assert!(status.has_block);
+        assert_eq!(status.votes, 2);
+        assert_eq!(
+            mem_pool.pending_blocks().collect::<Vec<_>>(),
+            vec![block.id]
+        );
+
+        mem_pool.vote(&block.id, 0b0000_0001, &300, 11, true);
+        let evaluation = BlockEvaluation {
+            block_id: block.id,
+            block,
+            vote_mask: 0b0000_0001,
+        };
+        let mut batch = TestBatch::default();
+        let (_, commits) = mem_pool.tick_with_evaluations(
+            &peers,
+            12,
+            55,
+            &mut NoOpSink,
+            std::slice::from_ref(&evaluation),
+            &mut batch,
+        );
+
+        assert_eq!(commits.len(), 1);
+        assert_eq!(
+            mem_pool.block_status(&block.id),
+            Some(BlockStatus {
+                state: BlockState::Commit,
+                has_block: true,
+                votes: 3,
+            })
+        );
+        assert_eq!(mem_pool.pending_blocks().count(), 0);
+    }
+
+    #[test]
+    fn default_config_keeps_vote_threshold_rule() {
+        let config = MemPoolConfig::default();
+        assert_eq!(config.commit_threshold, VOTE_THRESHOLD as usize + 1);
+        assert_eq!(EcMemPool::new().vote_balance_threshold, VOTE_THRESHOLD);
+
+        let from_peers = MemPoolConfig::from(&PeerManagerConfig::default());
+        assert_eq!(from_peers.commit_threshold, config.commit_threshold);
+    }
+
+    #[test]
+    fn block_commits_exactly_at_configured_threshold() {
+        for commit_threshold in [2, 5] {
+            let block = test_block(100, 250, 0);
+            let mut mem_pool = EcMemPool::with_config(MemPoolConfig {
+                commit_threshold,
+                ..MemPoolConfig::default()
+            });
+            let peers = test_peers();
+            let evaluation = BlockEvaluation {
+                block_id: block.id,
+                block,
+                vote_mask: 0b0000_0001,
+            };
+            let mut batch = TestBatch::default();
+            assert!(mem_pool.block(&block, 10));
+
+            for (n, voter) in [100, 200, 300, 400, 500].into_iter().enumerate() {
+                let votes = n + 1;
+                if votes > commit_threshold {
+                    break;
+                }
+                mem_pool.vote(&block.id, 0b0000_0001, &voter, 10, true);
+                let (_, commits) = mem_pool.tick_with_evaluations(
+                    &peers,
+                    10,
+                    55,
+                    &mut NoOpSink,
+                    std::slice::from_ref(&evaluation),
+                    &mut batch,
+                );
+
+                let committed =
+                    mem_pool.block_status(&block.id).unwrap().state == BlockState::Commit;
+                assert_eq!(
+                    committed,
+                    votes == commit_threshold,
+                    "threshold {}: state after vote {}",
+                    commit_threshold,
+                    votes
+                );
+                assert_eq!(commits.len(), usize::from(committed));
+            }
+        }
+    }
+
+    #[test]
+    fn admission_rejects_spending_committed_parent() {
+        let mem_pool = EcMemPool::new();
+        let blocks = MockEcBlocks::default();
+        let mut tokens = MockTokens::default();
+        tokens.tokens.insert(
+            250,
+            TokenState {
+                current: Some(TrustedMapping {
+                    block: 8,
+                    parent: 7,
+                    time: 5,
+                    source: crate::ec_interface::TrustSource::Confirmed,
+                }),
+                pending: None,
+            },
+        );
+
+        // Parent 7 was already spent by committed block 8
+        assert_eq!(
+            mem_pool.check_admission(&test_block(100, 250, 7), &tokens, &blocks, 10),
+            Err(BlockRejection::DoubleSpend {
+                token: 250,
+                conflicting_block: 8,
+            })
+        );
+        // Spending the current head is fine
+        assert_eq!(
+            mem_pool.check_admission(&test_block(101, 250, 8), &tokens, &blocks, 10),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn stale_time_block_is_rejected_and_never_committed() {
+        let mut mem_pool = EcMemPool::with_config(MemPoolConfig {
+            commit_threshold: 2,
+            ..MemPoolConfig::default()
+        });
+        let peers = test_peers();
+        let blocks = MockEcBlocks::default();
+        let mut tokens = MockTokens::default();
+        tokens.tokens.insert(
+            250,
+            TokenState {
+                current: Some(TrustedMapping {
+                    block: 8,
+                    parent: 7,
+                    time: 10,
+                    source: crate::ec_interface::TrustSource::Confirmed,
+                }),
+                pending: None,
+            },
+        );
+
+        // Spends the current head, but at the mapping's own time
+        let stale = test_block(101, 250, 8);
+        assert_eq!(
+            mem_pool.check_admission(&stale, &tokens, &blocks, 10),
+            Err(BlockRejection::StaleTime {
+                token: 250,
+                mapping_time: 10,
+            })
+        );
+        let fresh = Block { time: 11, ..stale };
+        assert_eq!(
+            mem_pool.check_admission(&fresh, &tokens, &blocks, 10),
+            Ok(())
+        );
+
+        // Arriving from a peer it may gather votes, but this node never commits it
+        assert!(mem_pool.block(&stale, 10));
+        for voter in [100, 200] {
+            mem_pool.vote(&stale.id, 0b0000_0001, &voter, 10, true);
+        }
+        let (evaluations, _) = mem_pool.evaluate_pending_blocks(&tokens, 10, 55, &mut NoOpSink);
+        assert!(evaluations.is_empty());
+        let mut batch = TestBatch::default();
+        let (_, commits) =
+            mem_pool.tick_with_evaluations(&peers, 10, 55, &mut NoOpSink, &evaluations, &mut batch);
+        assert!(commits.is_empty());
+        assert!(batch.updated_tokens.is_empty());
+    }
+
+    #[test]
+    fn candidates_commit_in_priority_order() {
+        let mut mem_pool = EcMemPool::with_config(MemPoolConfig {
+            commit_threshold: 2,
+            ..MemPoolConfig::default()
+        });
+        let peers = test_peers();
+        let tokens = MockTokens::default();
+
+        // (block id, token, time, priority): low and high priorities interleaved
+        let submissions = [
+            (1, 201, 10, Some(1)),
+            (2, 202, 10, Some(9)),
+            (3, 203, 9, Some(1)),
+            (4, 204, 10, None), // falls back to used = 1
+            (5, 205, 10, Some(5)),
+        ];
+        for (id, token, time, priority) in submissions {
+            let mut block = test_block(id, token, 0);
+            block.time = time;
+            assert!(mem_pool.block_with_priority(&block, 10, priority));
+            for voter in [100, 200] {
+                mem_pool.vote(&id, 0b0000_0001, &voter, 10, true);
+            }
+        }
+
+        let expected = vec![2, 5, 3, 1, 4];
+        assert_eq!(mem_pool.next_candidates(2), vec![2, 5]);
+        assert_eq!(mem_pool.next_candidates(10), expected);
+
+        let (evaluations, _) = mem_pool.evaluate_pending_blocks(&tokens, 10, 55, &mut NoOpSink);
+        let mut batch = TestBatch::default();
+        let (_, commits) =
+            mem_pool.tick_with_evaluations(&peers, 10, 55, &mut NoOpSink, &evaluations, &mut batch);
+
+        let committed: Vec<_> = commits.iter().map(|c| c.committed_block_id).collect();
+        assert_eq!(committed, expected);
+        assert_eq!(batch.saved_blocks, expected);
+        assert!(mem_pool.next_candidates(10).is_empty());
+    }
+
     #[test]
     fn vote_requests_sort_highest_block_id_first_within_token() {
         let mut requests = vec![
@@ -1,15 +1,19 @@
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::rc::Rc;
 
-use rand::Rng;
+use rand::{Rng, SeedableRng};
 
+use crate::ec_commit_chain::CommitChainConfig;
 use crate::ec_interface::{
-    BatchRequestItem, BatchedBackend, Block, BlockId, BlockUseCase, EcBlocks, EcCommitChainAccess,
-    EcTime, EcTokensV2, Event, EventSink, Message, MessageEnvelope, MessageTicket, NoOpSink,
-    PeerId, TokenId,
+    BatchRequestItem, BatchedBackend, Block, BlockId, BlockUseCase, CommitBlockId, EcBlocks,
+    EcCommitChainAccess, EcTime, EcTokensV2, Event, EventSink, Message, MessageEnvelope,
+    MessageTicket, NoOpSink, PeerId, TimeSource, TokenId,
+};
+use crate::ec_mempool::{
+    BlockRejection, BlockState, BlockStatus, EcMemPool, InitialVoteRequest, MemPoolConfig,
+    MempoolDiagnostics,
 };
-use crate::ec_mempool::{BlockState, EcMemPool, InitialVoteRequest, MempoolDiagnostics};
 use crate::ec_peers::{EcPeers, PeerAction, PeerManagerConfig};
 use crate::ec_proof_of_storage::TokenStorageBackend;
 use crate::ec_ticket_manager::TicketManager;
@@ -33,6 +37,12 @@ pub struct EcNode<
     enable_request_batching: bool,
     enable_commit_chain_sync: bool,
     batch_vote_replies: bool,
+    /// Tick output held back by `tick_budgeted`, oldest first
+    deferred_outbound: VecDeque<MessageEnvelope>,
+    /// Deferred messages dropped for outliving `election_timeout`
+    dropped_deferred: usize,
+    /// Clock read by `tick` and `handle_message`; None counts ticks instead
+    time_source: Option<Box<dyn TimeSource>>,
 }
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -50,6 +60,8 @@ impl<
     > EcNode<B, T>
 {
     /// Create a new node with default NoOpSink (zero overhead)
+    ///
+    /// Uses default peer and commit chain configurations.
     pub fn new(
         backend: Rc<RefCell<B>>,
         id: PeerId,
@@ -57,17 +69,34 @@ impl<
         token_storage: T,
         rng: rand::rngs::StdRng,
     ) -> Self {
-        Self::new_with_peer_config_and_sink(
+        Self::with_config(
             backend,
             id,
             time,
             token_storage,
             PeerManagerConfig::default(),
-            Box::new(NoOpSink),
+            CommitChainConfig::default(),
             rng,
         )
     }
 
+    /// Create a node with explicit peer and commit chain configurations
+    ///
+    /// The commit chain lives in the backend, so `commit_config` is applied to
+    /// it through `EcCommitChainAccess::set_commit_chain_config`.
+    pub fn with_config(
+        backend: Rc<RefCell<B>>,
+        id: PeerId,
+        time: EcTime,
+        token_storage: T,
+        peer_config: PeerManagerConfig,
+        commit_config: CommitChainConfig,
+        rng: rand::rngs::StdRng,
+    ) -> Self {
+        backend.borrow_mut().set_commit_chain_config(commit_config);
+        Self::new_with_peer_config(backend, id, time, token_storage, peer_config, rng)
+    }
+
     pub fn new_with_peer_config(
         backend: Rc<RefCell<B>>,
         id: PeerId,
@@ -115,24 +144,45 @@ impl<
         peer_config: PeerManagerConfig,
         event_sink: Box<dyn EventSink>,
         rng: rand::rngs::StdRng,
+    ) -> Self {
+        let mempool_config = MemPoolConfig::from(&peer_config);
+        Self::new_with_mempool_config(
+            backend,
+            id,
+            time,
+            token_storage,
+            peer_config,
+            mempool_config,
+            event_sink,
+            rng,
+        )
+    }
+
+    /// Create a node whose mempool voting policy is set independently of `peer_config`
+    ///
+    /// The other constructors derive it from `peer_config.vote_balance_threshold`
+    /// and the vote request cadence settings.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_with_mempool_config(
+        backend: Rc<RefCell<B>>,
+        id: PeerId,
+        time: EcTime,
+        token_storage: T,
+        peer_config: PeerManagerConfig,
+        mempool_config: MemPoolConfig,
+        event_sink: Box<dyn EventSink>,
+        mut rng: rand::rngs::StdRng,
     ) -> Self {
         let enable_request_batching = peer_config.enable_request_batching;
         let enable_commit_chain_sync = peer_config.enable_commit_chain_sync;
         let batch_vote_replies = peer_config.batch_vote_replies;
-        let vote_balance_threshold = peer_config.vote_balance_threshold;
-        let vote_request_resend_cooldown = peer_config.vote_request_resend_cooldown;
-        let vote_request_active_rounds = peer_config.vote_request_active_rounds;
-        let vote_request_pairs_per_tick = peer_config.vote_request_pairs_per_tick;
+        // Derive the peer manager's RNG from ours so seeded nodes are reproducible
+        let peers_rng = rand::rngs::StdRng::from_seed(rng.gen());
         Self {
-            mem_pool: EcMemPool::with_vote_policy(
-                vote_balance_threshold,
-                vote_request_resend_cooldown,
-                vote_request_active_rounds,
-                vote_request_pairs_per_tick,
-            ),
+            mem_pool: EcMemPool::with_config(mempool_config),
             backend,
             token_storage,
-            peers: EcPeers::with_config(id, peer_config),
+            peers: EcPeers::with_config_and_rng(id, peer_config, peers_rng),
             peer_id: id,
             time,
             ticket_manager: TicketManager::new(100), // 100 tick rotation period for simulation
@@ -142,6 +192,9 @@ impl<
             enable_request_batching,
             enable_commit_chain_sync,
             batch_vote_replies,
+            deferred_outbound: VecDeque::new(),
+            dropped_deferred: 0,
+            time_source: None,
         }
     }
 
@@ -149,6 +202,26 @@ impl<
         self.peer_id
     }
 
+    /// Read time from `source` instead of counting ticks
+    ///
+    /// `tick` and `handle_message` then take the node's time from the source.
+    /// Time never moves backwards: a source reading behind the node is ignored.
+    pub fn set_time_source(&mut self, source: Box<dyn TimeSource>) {
+        self.time_source = Some(source);
+        self.sync_time();
+    }
+
+    pub fn get_time(&self) -> EcTime {
+        self.time
+    }
+
+    /// Catch up with the time source, if there is one
+    fn sync_time(&mut self) {
+        if let Some(source) = &self.time_source {
+            self.time = self.time.max(source.now());
+        }
+    }
+
     pub fn seed_peer(&mut self, peer: &PeerId) {
         self.peers.update_peer(peer, self.time);
     }
@@ -161,6 +234,16 @@ impl<
         self.peers.seed_genesis_token(token)
     }
 
+    /// Network address of a known peer (for transports that route by address)
+    pub fn peer_addr(&self, peer: &PeerId) -> Option<std::net::SocketAddr> {
+        self.peers.peer_addr(peer)
+    }
+
+    /// Record the network address of a known peer; false if the peer is unknown
+    pub fn set_peer_addr(&mut self, peer: &PeerId, addr: std::net::SocketAddr) -> bool {
+        self.peers.set_peer_addr(peer, addr)
+    }
+
     pub fn num_peers(&self) -> usize {
         self.peers.num_peers()
     }
@@ -189,8 +272,46 @@ impl<
         self.peers.num_peers_with_commit_chain_heads()
     }
 
-    pub fn block(&mut self, block: &Block) {
-        let _ = self.mem_pool.block(block, self.time);
+    /// Election totals: (started, completed, timed out, split-brain)
+    pub fn election_stats(&self) -> (usize, usize, usize, usize) {
+        self.peers.get_election_stats()
+    }
+
+    /// Admit a block to the mempool, returning its id
+    ///
+    /// Refuses malformed blocks, blocks already known, and double-spends (see
+    /// `BlockRejection`). Blocks received from peers or passed to
+    /// `submit_local_block` take the reactive path instead, where conflicting
+    /// blocks compete and the highest block id wins, so every node converges on
+    /// the same block.
+    pub fn block(&mut self, block: &Block) -> Result<BlockId, BlockRejection> {
+        self.admit_block(block, None)
+    }
+
+    /// Like `block`, but vote and commit ahead of lower-priority blocks
+    ///
+    /// Without a priority a block ranks by its token count.
+    pub fn block_with_priority(
+        &mut self,
+        block: &Block,
+        priority: u64,
+    ) -> Result<BlockId, BlockRejection> {
+        self.admit_block(block, Some(priority))
+    }
+
+    fn admit_block(
+        &mut self,
+        block: &Block,
+        priority: Option<u64>,
+    ) -> Result<BlockId, BlockRejection> {
+        {
+            let backend = self.backend.borrow();
+            self.mem_pool
+                .check_admission(block, &*backend, &*backend, self.time)?;
+        }
+        self.mem_pool
+            .block_with_priority(block, self.time, priority);
+        Ok(block.id)
     }
 
     pub fn submit_local_block(
@@ -220,6 +341,18 @@ impl<
         self.mem_pool.diagnostics(self.time)
     }
 
+    pub fn block_status(&self, block_id: &BlockId) -> Option<BlockStatus> {
+        self.mem_pool.block_status(block_id)
+    }
+
+    pub fn pending_blocks(&self) -> impl Iterator<Item = BlockId> + '_ {
+        self.mem_pool.pending_blocks()
+    }
+
+    pub fn next_candidates(&self, n: usize) -> Vec<BlockId> {
+        self.mem_pool.next_candidates(n)
+    }
+
     pub fn vote_ingress_diagnostics(&self) -> VoteIngressDiagnostics {
         self.vote_diagnostics
     }
@@ -257,7 +390,11 @@ impl<
      * We should also investigate if (like in earlier prototypes) we can reduce the votes by only sending to trusted nodes that hasn't responded yet.
      */
     pub fn tick(&mut self, outbound_messages: &mut Vec<MessageEnvelope>) {
-        self.time += 1;
+        if self.time_source.is_some() {
+            self.sync_time();
+        } else {
+            self.time += 1;
+        }
         let mut local_responses = Vec::new();
         let responses = &mut local_responses;
 
@@ -267,7 +404,8 @@ impl<
         // Process mempool in phases
         let mut messages = {
             // Phase 0: Cleanup expired blocks
-            self.mem_pool.cleanup_expired(self.time);
+            self.mem_pool
+                .cleanup_expired(self.time, self.peer_id, &mut *self.event_sink);
 
             // Phase 1: Evaluate pending blocks (immutable borrow)
             // This checks token chains and generates block/parent repair requests.
@@ -388,7 +526,12 @@ impl<
         // Periodically query nearby peers to keep our commit chain up to date
         let sync_actions = if self.enable_commit_chain_sync {
             let mut backend = self.backend.borrow_mut();
-            backend.commit_chain_tick(&self.peers, &mut self.mem_pool, self.time)
+            backend.commit_chain_tick(
+                &self.peers,
+                &mut self.mem_pool,
+                self.time,
+                &mut *self.event_sink,
+            )
         } else {
             Vec::new()
         };
@@ -396,19 +539,22 @@ impl<
         let head_of_chain = self.backend.borrow().get_commit_chain_head().unwrap_or(0);
 
         for action in peer_actions {
-            match action {
-                PeerAction::SendQuery { receiver, .. }
-                | PeerAction::SendInvitation { receiver, .. } => {
-                    responses.push(action.into_envelope(
-                        self.peer_id,
-                        receiver,
-                        self.time,
-                        head_of_chain,
-                    ));
-                }
-                PeerAction::SendAnswer { .. } | PeerAction::SendReferral { .. } => {
-                    unreachable!("EcPeers::tick only produces query/invitation actions")
-                }
+            responses.extend(self.tick_action_envelope(action, head_of_chain));
+        }
+
+        // Advertise our commit chain head to every Connected peer
+        let interval = self.peers.config().commit_head_interval;
+        if head_of_chain > 0 && interval > 0 && self.time.is_multiple_of(interval) {
+            for &receiver in self.peers.get_active_peers() {
+                responses.push(MessageEnvelope {
+                    sender: self.peer_id,
+                    receiver,
+                    ticket: 0,
+                    time: self.time,
+                    message: Message::CommitHead {
+                        head: head_of_chain,
+                    },
+                });
             }
         }
 
@@ -432,6 +578,20 @@ impl<
                         },
                     });
                 }
+                TickMessage::QueryBlockBatch { block_ids } => {
+                    // Keyed on the peer asked, so the whole Blocks reply checks against its
+                    // sender; the commit chain then keeps only the blocks that peer was asked for
+                    let ticket = self
+                        .ticket_manager
+                        .generate_ticket(receiver, BlockUseCase::CommitChain);
+                    responses.push(MessageEnvelope {
+                        sender: self.peer_id,
+                        receiver,
+                        ticket,
+                        time: self.time,
+                        message: Message::QueryBlocks { block_ids, ticket },
+                    });
+                }
                 TickMessage::QueryCommitBlock { block_id, ticket } => {
                     // For commit blocks, use the specified receiver (peer from tracked_peers)
                     responses.push(MessageEnvelope {
@@ -449,6 +609,99 @@ impl<
         outbound_messages.extend(local_responses);
     }
 
+    /// Envelope for an action returned by `EcPeers::tick`
+    ///
+    /// The tick only starts conversations; answers, referrals and forwards
+    /// reply to a query and have no receiver here, so they are logged and dropped.
+    fn tick_action_envelope(
+        &self,
+        action: PeerAction,
+        head_of_chain: CommitBlockId,
+    ) -> Option<MessageEnvelope> {
+        match action {
+            PeerAction::SendQuery { receiver, .. }
+            | PeerAction::SendInvitation { receiver, .. }
+            | PeerAction::SendPeerList { receiver, .. }
+            | PeerAction::SendKeepalive { receiver } => {
+                Some(action.into_envelope(self.peer_id, receiver, self.time, head_of_chain))
+            }
+            other => {
+                log::warn!(
+                    "Peer {} dropping unroutable tick action {:?}",
+                    self.peer_id,
+                    other
+                );
+                None
+            }
+        }
+    }
+
+    /// Like `tick`, but emit at most `max_out` messages per call
+    ///
+    /// This tick's messages queue behind any deferred from earlier calls. Up to
+    /// `max_out` are then taken round-robin across message kinds (oldest first
+    /// within a kind), so a burst of one kind can't starve the others. The
+    /// rest wait for the next call. Deferred messages keep their original
+    /// `time` and tickets, and are dropped once older than the peer
+    /// manager's `election_timeout`: by then the elections they serve have
+    /// ended, and this keeps the queue bounded under sustained overload.
+    /// `handle_message` replies are not budgeted.
+    pub fn tick_budgeted(&mut self, outbound_messages: &mut Vec<MessageEnvelope>, max_out: usize) {
+        let mut produced = Vec::new();
+        self.tick(&mut produced);
+        self.deferred_outbound.extend(produced);
+
+        let max_age = self.peers.config().election_timeout;
+        let queued = self.deferred_outbound.len();
+        let now = self.time;
+        self.deferred_outbound
+            .retain(|env| now.saturating_sub(env.time) <= max_age);
+        self.dropped_deferred += queued - self.deferred_outbound.len();
+
+        // Group queue positions by kind, kinds in order of first appearance
+        let mut kinds: Vec<(std::mem::Discriminant<Message>, VecDeque<usize>)> = Vec::new();
+        for (idx, env) in self.deferred_outbound.iter().enumerate() {
+            let kind = std::mem::discriminant(&env.message);
+            match kinds.iter_mut().find(|(k, _)| *k == kind) {
+                Some((_, positions)) => positions.push_back(idx),
+                None => kinds.push((kind, VecDeque::from([idx]))),
+            }
+        }
+
+        let mut selected = vec![false; self.deferred_outbound.len()];
+        let mut remaining = max_out.min(self.deferred_outbound.len());
+        while remaining > 0 {
+            for (_, positions) in kinds.iter_mut() {
+                if remaining == 0 {
+                    break;
+                }
+                if let Some(idx) = positions.pop_front() {
+                    selected[idx] = true;
+                    remaining -= 1;
+                }
+            }
+        }
+
+        let queued = std::mem::take(&mut self.deferred_outbound);
+        for (env, send) in queued.into_iter().zip(selected) {
+            if send {
+                outbound_messages.push(env);
+            } else {
+                self.deferred_outbound.push_back(env);
+            }
+        }
+    }
+
+    /// Messages held back by `tick_budgeted` for a later call
+    pub fn num_deferred_messages(&self) -> usize {
+        self.deferred_outbound.len()
+    }
+
+    /// Deferred messages `tick_budgeted` dropped as stale
+    pub fn num_dropped_deferred_messages(&self) -> usize {
+        self.dropped_deferred
+    }
+
     /*
     Vote cases:
 
@@ -498,12 +751,44 @@ impl<
         msg: &MessageEnvelope,
         outbound_messages: &mut Vec<MessageEnvelope>,
     ) {
+        self.sync_time();
         let mut local_responses = Vec::new();
         self.handle_message_inner(msg, &mut local_responses);
         self.coalesce_request_batches(&mut local_responses);
         outbound_messages.extend(local_responses);
     }
 
+    /// Handle a burst of messages in one pass
+    ///
+    /// Produces the same envelopes as calling `handle_message` for each input in
+    /// order, except that identical envelopes triggered by several inputs (e.g.
+    /// repeated queries for the same block) are sent once.
+    pub fn handle_messages(
+        &mut self,
+        msgs: &[MessageEnvelope],
+        outbound_messages: &mut Vec<MessageEnvelope>,
+    ) {
+        self.sync_time();
+        let mut local_responses = Vec::new();
+        for msg in msgs {
+            let mut responses = Vec::new();
+            self.handle_message_inner(msg, &mut responses);
+            self.coalesce_request_batches(&mut responses);
+            local_responses.extend(responses);
+        }
+
+        // Key on receiver, ticket and canonical wire bytes (envelopes aren't Hash)
+        let mut seen = std::collections::HashSet::new();
+        local_responses.retain(|envelope| {
+            let mut key = Vec::with_capacity(64);
+            key.extend_from_slice(&envelope.receiver.to_le_bytes());
+            key.extend_from_slice(&envelope.ticket.to_le_bytes());
+            envelope.message.encode(&mut key);
+            seen.insert(key)
+        });
+        outbound_messages.extend(local_responses);
+    }
+
     fn emit_reactive_initial_vote_requests(
         &self,
         requests: &[InitialVoteRequest],
@@ -742,11 +1027,16 @@ impl<
             } => {
                 let receiver = if *target == 0 { msg.sender } else { *target };
 
-                // Forward to EcPeers for token lookup
-                if let Some(action) =
-                    self.peers
-                        .handle_query(&self.token_storage, *token_id, *ticket, msg.sender)
-                {
+                // Forward to EcPeers for token lookup. The answer goes to `receiver`,
+                // so the signature must be bound to it rather than to a forwarder.
+                let may_forward = *target == 0;
+                if let Some(action) = self.peers.handle_query(
+                    &self.token_storage,
+                    *token_id,
+                    *ticket,
+                    receiver,
+                    may_forward,
+                ) {
                     // Convert PeerAction to MessageEnvelope
                     match action {
                         PeerAction::SendAnswer { .. } => {
@@ -792,8 +1082,17 @@ impl<
                                 responses.push(self.send_referral(receiver, token, ticket));
                             }
                         }
-                        // handle_query only returns SendAnswer or SendReferral
-                        _ => unreachable!("handle_query only returns SendAnswer or SendReferral"),
+                        PeerAction::ForwardQuery { receiver, .. } => {
+                            responses.push(action.into_envelope(
+                                self.peer_id,
+                                receiver,
+                                self.time,
+                                0,
+                            ));
+                        }
+                        other => {
+                            log::warn!("Dropping unexpected query action: {:?}", other);
+                        }
                     }
                 }
             }
@@ -914,6 +1213,59 @@ impl<
                     // Referral handled by peer manager
                 }
             }
+            Message::PeerList { peers } => {
+                self.peers.handle_peer_list(peers, msg.sender, self.time);
+            }
+            Message::Keepalive { head_of_chain } => {
+                self.peers.handle_keepalive(msg.sender, self.time);
+                // After handle_keepalive, so a newly identified sender keeps its head too
+                if *head_of_chain > 0 {
+                    self.peers
+                        .update_peer_commit_chain_head(&msg.sender, *head_of_chain);
+                }
+            }
+            Message::CommitHead { head } => {
+                self.peers.handle_commit_head(msg.sender, *head);
+            }
+            Message::QueryBlocks { block_ids, ticket } => {
+                // Answer with what we have; no forwarding or referrals for batches
+                let backend = self.backend.borrow();
+                let blocks: Vec<Block> = block_ids
+                    .iter()
+                    .filter_map(|block_id| self.mem_pool.query(block_id, &*backend))
+                    .collect();
+                if !blocks.is_empty() {
+                    responses.push(MessageEnvelope {
+                        sender: self.peer_id,
+                        receiver: msg.sender,
+                        ticket: *ticket,
+                        time: self.time,
+                        message: Message::Blocks { blocks },
+                    });
+                }
+            }
+            Message::Blocks { blocks } => {
+                if self.ticket_manager.validate_ticket(msg.ticket, msg.sender)
+                    == Some(BlockUseCase::CommitChain)
+                {
+                    let accepted = self
+                        .backend
+                        .borrow_mut()
+                        .handle_block_batch(msg.sender, blocks);
+                    if accepted < blocks.len() {
+                        log::debug!(
+                            "Dropped {} unrequested blocks from peer {}",
+                            blocks.len() - accepted,
+                            msg.sender
+                        );
+                    }
+                } else {
+                    log::debug!(
+                        "Rejected block batch from peer {} - invalid ticket",
+                        msg.sender
+                    );
+                }
+            }
             Message::QueryCommitBlock { block_id, ticket } => {
                 // Query our commit chain for the requested block
                 let backend = self.backend.borrow();
@@ -1142,13 +1494,728 @@ mod tests {
 
     use rand::SeedableRng;
 
-    use crate::ec_interface::{BatchRequestItem, Message, MessageEnvelope, TokenBlock};
+    use crate::ec_interface::{
+        BatchRequestItem, ManualTimeSource, Message, MessageEnvelope, NoOpSink, TokenBlock,
+    };
     use crate::ec_memory_backend::{MemTokens, MemoryBackend};
-    use crate::ec_peers::PeerManagerConfig;
-    use crate::ec_proof_of_storage::TokenStorageBackend;
+    use crate::ec_mempool::{BlockRejection, BlockState, MemPoolConfig};
+    use crate::ec_peers::{PeerAction, PeerManagerConfig};
+    use crate::ec_proof_of_storage::{ProofOfStorage, TokenStorageBackend};
 
     use super::EcNode;
 
+    #[test]
+    fn peer_counts_and_election_stats_match_peer_manager() {
+        let backend = Rc::new(RefCell::new(MemoryBackend::new_with_peer_id(1)));
+        let rng = rand::rngs::StdRng::from_seed([65u8; 32]);
+        let mut node = EcNode::new(backend, 1, 0, MemTokens::new(), rng);
+
+        node.seed_peer(&(1 << 60));
+        node.seed_peer(&(5 << 60));
+        for peer in [2 << 60, 3 << 60, 9 << 60] {
+            assert!(node.add_identified_peer(peer));
+        }
+        let mut outbound = Vec::new();
+        for _ in 0..3 {
+            node.tick(&mut outbound);
+        }
+
+        assert_eq!(node.num_connected_peers(), 2);
+        assert_eq!(node.num_connected_peers(), node.peers.num_connected());
+        assert_eq!(node.num_identified_peers(), node.peers.num_identified());
+        assert_eq!(node.num_pending_peers(), node.peers.num_pending());
+        assert_eq!(node.election_stats(), node.peers.get_election_stats());
+    }
+
+    #[test]
+    fn tick_budgeted_caps_each_call_and_eventually_sends_everything() {
+        let node = |seed: u8| {
+            let backend = Rc::new(RefCell::new(MemoryBackend::new_with_peer_id(1)));
+            let rng = rand::rngs::StdRng::from_seed([seed; 32]);
+            let mut node = EcNode::new(backend, 1, 0, MemTokens::new(), rng);
+            for i in 1..8u64 {
+                node.seed_peer(&(i << 60));
+                assert!(node.add_identified_peer((i << 60) + (1 << 59)));
+            }
+            node
+        };
+        let mut budgeted = node(66);
+        let mut reference = node(66);
+
+        // Identical nodes: the reference shows what each tick intends to send
+        let mut intended = Vec::new();
+        let mut sent = Vec::new();
+        for _ in 0..40 {
+            let mut out = Vec::new();
+            reference.tick(&mut out);
+            intended.push(out);
+
+            let mut out = Vec::new();
+            budgeted.tick_budgeted(&mut out, 3);
+            assert!(out.len() <= 3);
+
+            // Queries, invitations and keepalives are all backlogged: one of each
+            let kinds: Vec<_> = out
+                .iter()
+                .map(|env| std::mem::discriminant(&env.message))
+                .collect();
+            assert_eq!(kinds.len(), 3);
+            assert!(kinds
+                .iter()
+                .enumerate()
+                .all(|(i, k)| !kinds[..i].contains(k)));
+            sent.extend(out);
+        }
+        let total: usize = intended.iter().map(Vec::len).sum();
+        assert!(total > 3 * 40);
+        assert_eq!(
+            sent.len()
+                + budgeted.num_deferred_messages()
+                + budgeted.num_dropped_deferred_messages(),
+            total
+        );
+
+        // Everything the first ticks intended has gone out by now
+        let key =
+            |env: &MessageEnvelope| (env.receiver, env.time, std::mem::discriminant(&env.message));
+        let mut sent_keys: Vec<_> = sent.iter().map(key).collect();
+        for env in intended[..3].iter().flatten() {
+            let pos = sent_keys.iter().position(|k| *k == key(env));
+            sent_keys.swap_remove(pos.expect("early message never sent"));
+        }
+    }
+
+    #[test]
+    fn tick_budgeted_drops_stale_messages_under_sustained_overload() {
+        let node = |seed: u8| {
+            let backend = Rc::new(RefCell::new(MemoryBackend::new_with_peer_id(1)));
+            let rng = rand::rngs::StdRng::from_seed([seed; 32]);
+            let config = PeerManagerConfig {
+                election_timeout: 10,
+                ..Default::default()
+            };
+            let mut node =
+                EcNode::new_with_peer_config(backend, 1, 0, MemTokens::new(), config, rng);
+            for i in 1..8u64 {
+                node.seed_peer(&(i << 60));
+                assert!(node.add_identified_peer((i << 60) + (1 << 59)));
+            }
+            node
+        };
+        let mut budgeted = node(72);
+        let mut reference = node(72);
+
+        // One message per tick can't keep up with what the node produces
+        let mut intended = Vec::new();
+        for _ in 0..200 {
+            let mut out = Vec::new();
+            reference.tick(&mut out);
+            intended.push(out.len());
+
+            budgeted.tick_budgeted(&mut Vec::new(), 1);
+            let now = budgeted.get_time();
+            assert!(budgeted
+                .deferred_outbound
+                .iter()
+                .all(|env| now - env.time <= 10));
+
+            // At most the output of the last election_timeout + 1 ticks is queued
+            let window: usize = intended.iter().rev().take(11).sum();
+            assert!(budgeted.num_deferred_messages() <= window);
+        }
+        let total: usize = intended.iter().sum();
+        assert!(total > 2 * 200);
+        assert!(budgeted.num_dropped_deferred_messages() > 0);
+    }
+
+    #[test]
+    fn tick_reads_time_from_manual_time_source() {
+        let backend = Rc::new(RefCell::new(MemoryBackend::new_with_peer_id(1)));
+        let rng = rand::rngs::StdRng::from_seed([67u8; 32]);
+        let mut node = EcNode::new(backend, 1, 0, MemTokens::new(), rng);
+        node.seed_peer(&(1 << 60));
+
+        let clock = ManualTimeSource::new(10);
+        node.set_time_source(Box::new(clock.clone()));
+        assert_eq!(node.get_time(), 10);
+
+        // The clock can be driven from another thread
+        fn assert_send_sync<T: Send + Sync>(_: &T) {}
+        assert_send_sync(&clock);
+
+        // Ticks no longer advance time on their own
+        let mut outbound = Vec::new();
+        node.tick(&mut outbound);
+        node.tick(&mut outbound);
+        assert_eq!(node.get_time(), 10);
+
+        clock.advance(5);
+        outbound.clear();
+        node.tick(&mut outbound);
+        assert_eq!(node.get_time(), 15);
+        assert!(outbound.iter().all(|env| env.time == 15));
+
+        // Messages pick up the clock too; it never runs backwards
+        clock.set(20);
+        let keepalive = MessageEnvelope {
+            sender: 1 << 60,
+            receiver: 1,
+            ticket: 0,
+            time: 20,
+            message: Message::Keepalive { head_of_chain: 0 },
+        };
+        node.handle_message(&keepalive, &mut Vec::new());
+        assert_eq!(node.get_time(), 20);
+        clock.set(3);
+        node.tick(&mut outbound);
+        assert_eq!(node.get_time(), 20);
+    }
+
+    #[test]
+    fn keepalive_advertises_commit_chain_head() {
+        let backend = Rc::new(RefCell::new(MemoryBackend::new_with_peer_id(1)));
+        let rng = rand::rngs::StdRng::from_seed([68u8; 32]);
+        let mut node = EcNode::new(backend, 1, 0, MemTokens::new(), rng);
+        let (connected, unknown) = (1 << 60, 1 << 61);
+        node.seed_peer(&connected);
+
+        let keepalive = |sender, head_of_chain| MessageEnvelope {
+            sender,
+            receiver: 1,
+            ticket: 0,
+            time: 0,
+            message: Message::Keepalive { head_of_chain },
+        };
+
+        // Outgoing keepalives carry our head (none yet)
+        let mut outbound = Vec::new();
+        node.tick(&mut outbound);
+        let ours = Message::Keepalive { head_of_chain: 0 };
+        assert!(outbound
+            .iter()
+            .any(|env| env.receiver == connected && env.message == ours));
+
+        node.handle_message(&keepalive(connected, 42), &mut Vec::new());
+        assert!(node.peers.is_peer_connected_or_pending(&connected));
+        assert_eq!(node.peers.get_peer_commit_chain_head(&connected), Some(42));
+
+        // A peer without a chain does not erase what we know
+        node.handle_message(&keepalive(connected, 0), &mut Vec::new());
+        assert_eq!(node.peers.get_peer_commit_chain_head(&connected), Some(42));
+
+        // An unknown sender is identified together with its head
+        node.handle_message(&keepalive(unknown, 7), &mut Vec::new());
+        assert!(!node.peers.is_peer_connected_or_pending(&unknown));
+        assert_eq!(node.peers.get_peer_commit_chain_head(&unknown), Some(7));
+        assert_eq!(node.num_peers_with_commit_chain_heads(), 2);
+    }
+
+    #[test]
+    fn with_config_applies_commit_chain_config_to_backend() {
+        use crate::ec_commit_chain::CommitChainConfig;
+
+        let backend = Rc::new(RefCell::new(MemoryBackend::new_with_peer_id(1)));
+        let rng = rand::rngs::StdRng::from_seed([73u8; 32]);
+        let commit_config = CommitChainConfig {
+            max_tracked_peers: 1,
+            ..Default::default()
+        };
+        let peer_config = PeerManagerConfig::default();
+        let mut node = EcNode::with_config(
+            backend.clone(),
+            1,
+            0,
+            MemTokens::new(),
+            peer_config,
+            commit_config,
+            rng,
+        );
+        assert_eq!(
+            backend.borrow().commit_chain().config().max_tracked_peers,
+            1
+        );
+
+        // Four peers advertise heads, but only one is traced
+        for i in 1..5u64 {
+            node.seed_peer(&(i << 60));
+            let head = MessageEnvelope {
+                sender: i << 60,
+                receiver: 1,
+                ticket: 0,
+                time: 0,
+                message: Message::CommitHead { head: 100 + i },
+            };
+            node.handle_message(&head, &mut Vec::new());
+        }
+        let mut out = Vec::new();
+        node.tick(&mut out);
+        let queries = out
+            .iter()
+            .filter(|env| matches!(env.message, Message::QueryCommitBlock { .. }))
+            .count();
+        assert_eq!(queries, 1);
+    }
+
+    #[test]
+    fn unroutable_tick_action_is_dropped() {
+        let backend = Rc::new(RefCell::new(MemoryBackend::new_with_peer_id(1)));
+        let rng = rand::rngs::StdRng::from_seed([71u8; 32]);
+        let node = EcNode::new(backend, 1, 0, MemTokens::new(), rng);
+
+        let referral = PeerAction::SendReferral {
+            token: 5,
+            ticket: 0,
+            suggested_peers: [2, 3],
+        };
+        assert!(node.tick_action_envelope(referral, 0).is_none());
+
+        let keepalive = PeerAction::SendKeepalive { receiver: 2 };
+        let envelope = node.tick_action_envelope(keepalive, 9).unwrap();
+        assert_eq!(envelope.receiver, 2);
+        assert_eq!(envelope.message, Message::Keepalive { head_of_chain: 9 });
+    }
+
+    #[test]
+    fn advertised_head_starts_commit_chain_sync() {
+        use crate::ec_interface::{BatchedBackend, EcCommitChainAccess};
+
+        let (a, b) = (1 << 60, 1 << 61);
+        let backend_a = Rc::new(RefCell::new(MemoryBackend::new_with_peer_id(a)));
+        let backend_b = Rc::new(RefCell::new(MemoryBackend::new_with_peer_id(b)));
+        let rng = |seed| rand::rngs::StdRng::from_seed([seed; 32]);
+        // No keepalives: the head travels in CommitHead only
+        let config = PeerManagerConfig {
+            keepalives_per_tick: 0,
+            commit_head_interval: 1,
+            ..Default::default()
+        };
+        let mut node_a = EcNode::new_with_peer_config(
+            backend_a.clone(),
+            a,
+            0,
+            MemTokens::new(),
+            config,
+            rng(69),
+        );
+        let mut node_b = EcNode::new(backend_b, b, 0, MemTokens::new(), rng(70));
+        node_a.seed_peer(&b);
+        node_b.seed_peer(&a);
+
+        // A commits a block, which extends its commit chain
+        let block = crate::ec_interface::Block {
+            id: 5,
+            time: 3,
+            used: 0,
+            parts: [TokenBlock::default(); crate::ec_interface::TOKENS_PER_BLOCK],
+            signatures: [None; crate::ec_interface::TOKENS_PER_BLOCK],
+        };
+        {
+            let mut storage = backend_a.borrow_mut();
+            let mut batch = storage.begin_batch();
+            batch.save_block(&block);
+            batch.commit().unwrap();
+        }
+        let head = backend_a.borrow().get_commit_chain_head().unwrap();
+
+        // A advertises the head; B starts tracking A and asks for it
+        let mut from_a = Vec::new();
+        node_a.tick(&mut from_a);
+        assert!(from_a
+            .iter()
+            .any(|env| env.receiver == b && env.message == Message::CommitHead { head }));
+        assert!(!from_a
+            .iter()
+            .any(|env| matches!(env.message, Message::Keepalive { .. })));
+        let mut from_b = Vec::new();
+        node_b.handle_messages(&from_a, &mut Vec::new());
+        node_b.tick(&mut from_b);
+        let query = from_b
+            .iter()
+            .find(|env| {
+                env.receiver == a
+                    && matches!(env.message, Message::QueryCommitBlock { block_id, .. }
+                        if block_id == head)
+            })
+            .expect("B queries A's advertised head");
+
+        let mut answer = Vec::new();
+        node_a.handle_message(query, &mut answer);
+        match &answer[..] {
+            [MessageEnvelope {
+                message: Message::CommitBlock { block: commit },
+                ..
+            }] => {
+                assert_eq!(commit.id, head);
+                assert_eq!(commit.committed_blocks, vec![block.id]);
+            }
+            other => panic!("expected A's head commit block, got {:?}", other),
+        }
+    }
+
+    /// Ask a middle peer for a token only `owner` stores; count messages until answered
+    fn query_via_middle_peer(forward_queries: bool) -> usize {
+        use rand::Rng;
+        use std::collections::VecDeque;
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(70);
+        let mappings: Vec<_> = (0..50_000)
+            .map(|_| (rng.gen::<u64>(), rng.gen::<u64>(), 0, 1))
+            .collect();
+        let token = mappings[0].0;
+
+        // The owner sits next to the token; `far`, across the ring, is never reached
+        let (querier, middle) = (token ^ (1 << 62), token ^ (3 << 61));
+        let (owner, far) = (token.wrapping_add(1), token ^ (1 << 63));
+        let mut owner_storage = MemoryBackend::new();
+        *owner_storage.tokens_mut() = MemTokens::from_mappings(mappings);
+        let expected = ProofOfStorage::new()
+            .generate_signature(owner_storage.tokens_mut(), &token, &querier)
+            .expect("owner can prove storage to the querier");
+
+        let config = PeerManagerConfig {
+            forward_queries,
+            ..Default::default()
+        };
+        let node = |id: u64, storage: MemoryBackend| {
+            let backend = Rc::new(RefCell::new(MemoryBackend::new_with_peer_id(id)));
+            let rng = rand::rngs::StdRng::seed_from_u64(id);
+            EcNode::new_with_peer_config(backend, id, 0, storage, config.clone(), rng)
+        };
+        let mut nodes = std::collections::HashMap::new();
+        nodes.insert(querier, node(querier, MemoryBackend::new()));
+        nodes.insert(middle, node(middle, MemoryBackend::new()));
+        nodes.insert(owner, node(owner, owner_storage));
+        // Owner and far are Connected to the middle peer, owner closer to the token.
+        // Only forward mode connects the querier: the node's own 2/3 random
+        // forwarding for Connected senders would otherwise muddy the baseline.
+        nodes.get_mut(&middle).unwrap().seed_peer(&owner);
+        nodes.get_mut(&middle).unwrap().seed_peer(&far);
+        if forward_queries {
+            nodes.get_mut(&middle).unwrap().seed_peer(&querier);
+        }
+
+        let query = |receiver| MessageEnvelope {
+            sender: querier,
+            receiver,
+            ticket: 77,
+            time: 0,
+            message: Message::QueryToken {
+                token_id: token,
+                target: 0,
+                ticket: 77,
+            },
+        };
+        let mut queue = VecDeque::from([query(middle)]);
+        let mut delivered = 0;
+        while let Some(env) = queue.pop_front() {
+            delivered += 1;
+            assert!(delivered < 20, "query never resolved");
+            if env.receiver == querier {
+                match env.message {
+                    Message::Answer {
+                        answer, signature, ..
+                    } => {
+                        assert_eq!(env.sender, owner);
+                        assert_eq!((answer, signature), (expected.answer, expected.signature));
+                        return delivered;
+                    }
+                    // The querier follows the referral to the suggested peer it can reach
+                    Message::Referral { high, low, .. } => {
+                        let next = if nodes.contains_key(&high) { high } else { low };
+                        queue.push_back(query(next));
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            let mut out = Vec::new();
+            nodes
+                .get_mut(&env.receiver)
+                .unwrap()
+                .handle_message(&env, &mut out);
+            queue.extend(out.into_iter().filter(|e| nodes.contains_key(&e.receiver)));
+        }
+        panic!("message queue drained without an answer");
+    }
+
+    #[test]
+    fn forwarded_query_resolves_with_fewer_messages_than_referral() {
+        // Referral: query to middle, referral back, query to owner, answer
+        assert_eq!(query_via_middle_peer(false), 4);
+        // Forward: query to middle, forwarded to owner, answer (signed for the querier)
+        assert_eq!(query_via_middle_peer(true), 3);
+    }
+
+    #[test]
+    fn block_rejects_second_spend_of_same_parent() {
+        let backend = Rc::new(RefCell::new(MemoryBackend::new_with_peer_id(1)));
+        let rng = rand::rngs::StdRng::from_seed([43u8; 32]);
+        let mut node = EcNode::new(backend, 1, 0, MemTokens::new(), rng);
+
+        let spend = |id| {
+            let mut block = crate::ec_interface::Block {
+                id,
+                time: 0,
+                used: 1,
+                parts: Default::default(),
+                signatures: [None; crate::ec_interface::TOKENS_PER_BLOCK],
+            };
+            block.parts[0] = TokenBlock {
+                token: 11,
+                last: 7,
+                key: 1,
+            };
+            block
+        };
+
+        assert_eq!(node.block(&spend(500)), Ok(500));
+        assert_eq!(
+            node.block(&spend(600)),
+            Err(BlockRejection::DoubleSpend {
+                token: 11,
+                conflicting_block: 500,
+            })
+        );
+        assert!(node.knows_block(&500));
+        assert!(!node.knows_block(&600));
+    }
+
+    #[test]
+    fn block_reports_why_it_was_rejected() {
+        let backend = Rc::new(RefCell::new(MemoryBackend::new_with_peer_id(1)));
+        let rng = rand::rngs::StdRng::from_seed([59u8; 32]);
+        let mut node = EcNode::new(backend, 1, 0, MemTokens::new(), rng);
+
+        let mut block = crate::ec_interface::Block {
+            id: 500,
+            time: 0,
+            used: 1,
+            parts: Default::default(),
+            signatures: [None; crate::ec_interface::TOKENS_PER_BLOCK],
+        };
+        block.parts[0].token = 11;
+
+        assert_eq!(node.block(&block), Ok(500));
+        assert_eq!(node.block(&block), Err(BlockRejection::Duplicate));
+
+        let empty = crate::ec_interface::Block {
+            id: 501,
+            used: 0,
+            ..block
+        };
+        assert_eq!(node.block(&empty), Err(BlockRejection::EmptyBlock));
+
+        let overfull = crate::ec_interface::Block {
+            id: 502,
+            used: crate::ec_interface::TOKENS_PER_BLOCK as u8,
+            ..block
+        };
+        assert_eq!(
+            node.block(&overfull),
+            Err(BlockRejection::InvalidUsedCount {
+                used: crate::ec_interface::TOKENS_PER_BLOCK as u8
+            })
+        );
+        assert!(!node.knows_block(&501));
+        assert!(!node.knows_block(&502));
+    }
+
+    #[test]
+    fn stale_block_is_evicted_and_can_be_resubmitted() {
+        let backend = Rc::new(RefCell::new(MemoryBackend::new_with_peer_id(1)));
+        let rng = rand::rngs::StdRng::from_seed([44u8; 32]);
+        let mempool_config = MemPoolConfig {
+            max_block_age: 5,
+            ..MemPoolConfig::default()
+        };
+        let mut node = EcNode::new_with_mempool_config(
+            backend,
+            1,
+            0,
+            MemTokens::new(),
+            PeerManagerConfig::default(),
+            mempool_config,
+            Box::new(NoOpSink),
+            rng,
+        );
+        node.seed_peer(&2);
+
+        let mut block = crate::ec_interface::Block {
+            id: 500,
+            time: 0,
+            used: 1,
+            parts: Default::default(),
+            signatures: [None; crate::ec_interface::TOKENS_PER_BLOCK],
+        };
+        block.parts[0].token = 11;
+        node.block(&block).unwrap();
+
+        let vote = MessageEnvelope {
+            sender: 2,
+            receiver: 1,
+            ticket: 0,
+            time: 0,
+            message: Message::Vote {
+                block_id: 500,
+                vote: 1,
+                reply: false,
+            },
+        };
+        node.handle_message(&vote, &mut Vec::new());
+        assert_eq!(node.block_status(&500).unwrap().votes, 1);
+
+        let mut outbound = Vec::new();
+        for _ in 0..4 {
+            node.tick(&mut outbound);
+        }
+        assert!(
+            node.block_status(&500).is_some(),
+            "not yet past max_block_age"
+        );
+
+        node.tick(&mut outbound);
+        assert!(node.block_status(&500).is_none());
+        assert!(!node.knows_block(&500));
+
+        // Re-submitted fresh: no votes carried over from the evicted entry
+        node.block(&block).unwrap();
+        let status = node.block_status(&500).unwrap();
+        assert_eq!(status.state, BlockState::Pending);
+        assert_eq!(status.votes, 0);
+    }
+
+    #[test]
+    fn query_blocks_answers_available_blocks_in_one_message() {
+        let backend = Rc::new(RefCell::new(MemoryBackend::new_with_peer_id(1)));
+        let rng = rand::rngs::StdRng::from_seed([51u8; 32]);
+        let mut node = EcNode::new(backend, 1, 0, MemTokens::new(), rng);
+        for (id, token) in [(500, 11), (501, 12)] {
+            let mut block = crate::ec_interface::Block {
+                id,
+                time: 0,
+                used: 1,
+                parts: Default::default(),
+                signatures: [None; crate::ec_interface::TOKENS_PER_BLOCK],
+            };
+            block.parts[0].token = token;
+            node.block(&block).unwrap();
+        }
+
+        let mut responses = Vec::new();
+        node.handle_message(
+            &MessageEnvelope {
+                sender: 2,
+                receiver: 1,
+                ticket: 9,
+                time: 1,
+                message: Message::QueryBlocks {
+                    block_ids: vec![500, 600, 501],
+                    ticket: 9,
+                },
+            },
+            &mut responses,
+        );
+
+        // Unknown 600 is skipped, not referred
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0].receiver, 2);
+        assert_eq!(responses[0].ticket, 9);
+        match &responses[0].message {
+            Message::Blocks { blocks } => {
+                let ids: Vec<_> = blocks.iter().map(|block| block.id).collect();
+                assert_eq!(ids, vec![500, 501]);
+            }
+            other => panic!("expected Blocks, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn handle_messages_matches_per_message_handling() {
+        let make_node = || {
+            let backend = Rc::new(RefCell::new(MemoryBackend::new_with_peer_id(1)));
+            let rng = rand::rngs::StdRng::from_seed([40u8; 32]);
+            let mut node = EcNode::new(backend, 1, 0, MemTokens::new(), rng);
+            for peer in [2, 3, 4] {
+                node.seed_peer(&peer);
+            }
+            let mut block = crate::ec_interface::Block {
+                id: 500,
+                time: 0,
+                used: 1,
+                parts: Default::default(),
+                signatures: [None; crate::ec_interface::TOKENS_PER_BLOCK],
+            };
+            block.parts[0].token = 11;
+            node.block(&block).unwrap();
+            node
+        };
+
+        let envelope = |sender, ticket, message| MessageEnvelope {
+            sender,
+            receiver: 1,
+            ticket,
+            time: 1,
+            message,
+        };
+        let query_known = Message::QueryBlock {
+            block_id: 500,
+            target: 0,
+            ticket: 9,
+        };
+        let inbound = vec![
+            envelope(2, 9, query_known.clone()),
+            // Same query relayed twice: one reply is enough
+            envelope(2, 9, query_known.clone()),
+            envelope(3, 9, query_known),
+            envelope(
+                99,
+                5,
+                Message::QueryBlock {
+                    block_id: 600,
+                    target: 0,
+                    ticket: 5,
+                },
+            ),
+            envelope(
+                4,
+                0,
+                Message::RequestBatch {
+                    items: vec![
+                        BatchRequestItem::Vote {
+                            block_id: 500,
+                            vote: 1,
+                            reply: true,
+                        },
+                        BatchRequestItem::QueryBlock {
+                            block_id: 500,
+                            target: 0,
+                            ticket: 7,
+                        },
+                    ],
+                },
+            ),
+            envelope(2, 0, Message::PeerList { peers: vec![5, 6] }),
+        ];
+
+        let mut one_by_one = make_node();
+        let mut single = Vec::new();
+        for msg in &inbound {
+            one_by_one.handle_message(msg, &mut single);
+        }
+
+        let mut batched_node = make_node();
+        let mut batched = Vec::new();
+        batched_node.handle_messages(&inbound, &mut batched);
+
+        assert!(!batched.is_empty());
+        assert!(
+            batched.len() < single.len(),
+            "duplicate reply should be dropped"
+        );
+        assert!(single.iter().all(|env| batched.contains(env)));
+        assert!(batched.iter().all(|env| single.contains(env)));
+    }
+
     #[test]
     fn pending_vote_request_does_not_fast_reply_before_terminal_state() {
         let backend = Rc::new(RefCell::new(MemoryBackend::new_with_peer_id(1)));
@@ -1161,7 +2228,7 @@ mod tests {
 
         let block = crate::ec_interface::Block {
             id: 77,
-            time: 0,
+            time: 1,
             used: 2,
             parts: [
                 TokenBlock {
@@ -1181,7 +2248,7 @@ mod tests {
             ],
             signatures: [None; crate::ec_interface::TOKENS_PER_BLOCK],
         };
-        node.block(&block);
+        node.block(&block).unwrap();
 
         let inbound = MessageEnvelope {
             sender: 2,
@@ -1215,7 +2282,7 @@ mod tests {
 
         let block = crate::ec_interface::Block {
             id: 88,
-            time: 0,
+            time: 1,
             used: 2,
             parts: [
                 TokenBlock {
@@ -1235,7 +2302,7 @@ mod tests {
             ],
             signatures: [None; crate::ec_interface::TOKENS_PER_BLOCK],
         };
-        node.block(&block);
+        node.block(&block).unwrap();
 
         let inbound = MessageEnvelope {
             sender: 2,
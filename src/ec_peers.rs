@@ -1,15 +1,16 @@
 use crate::ec_interface::{
-    CommitBlockId, EcTime, Message, MessageEnvelope, MessageTicket, PeerId, TokenId, TokenMapping,
-    TOKENS_SIGNATURE_SIZE, VOTE_THRESHOLD,
+    CommitBlockId, EcTime, Event, EventSink, Message, MessageEnvelope, MessageTicket, NoOpSink,
+    PeerId, TokenId, TokenMapping, TOKENS_SIGNATURE_SIZE, VOTE_THRESHOLD,
 };
 use crate::ec_peer_lifecycle_v2::{
     decide_answer_repair, AnswerOrigin, AnswerRepairConfig, AnswerRepairDecision,
 };
 use crate::ec_proof_of_storage::{
-    ElectionConfig, PeerElection, ProofOfStorage, TokenStorageBackend,
+    ring_distance_generic, ElectionConfig, PeerElection, ProofOfStorage, TokenStorageBackend,
 };
 use serde::{Deserialize, Serialize};
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::net::SocketAddr;
 
 // ============================================================================
 // Configuration
@@ -75,6 +76,18 @@ pub struct PeerSmallWorldConfig {
     pub distance_exponent: f64,
 }
 
+/// What ring distance counts as "far" (fraction 1.0) in distance-based
+/// invitation acceptance and pruning
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DistanceBasis {
+    /// Half the ring, the largest possible distance
+    #[default]
+    HalfRing,
+    /// Distance to the farthest known peer plus `OBSERVED_SPREAD_MARGIN`, so
+    /// a network clustered in a narrow id band still spans the full gradient
+    ObservedSpread,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PeerManagerConfig {
     // ===== Capacity Limits =====
@@ -87,6 +100,12 @@ pub struct PeerManagerConfig {
     /// Maximum number of tokens in sample collection (default: 1000)
     pub token_sample_max_capacity: usize,
 
+    /// How strongly token sample eviction prefers tokens far from our peer id
+    /// (default: 0.0 = uniform). Each excess token is evicted with probability
+    /// proportional to `ring_distance^sampling_bias`; with a positive bias new
+    /// tokens are accepted past capacity and trimmed on the next tick.
+    pub sampling_bias: f64,
+
     // ===== Election Parameters =====
     /// Number of elections to trigger per tick (default: 3)
     pub elections_per_tick: usize,
@@ -116,6 +135,31 @@ pub struct PeerManagerConfig {
     /// Maximum referral hops followed by each referral-only probe.
     pub referral_probe_hops: usize,
 
+    /// Interval between peer-exchange gossip rounds (0 = disabled).
+    ///
+    /// Each round sends our closest peers to one random Connected peer, which
+    /// folds them into Identified. Speeds up discovery in sparse bootstraps.
+    pub peer_exchange_interval: EcTime,
+
+    /// Number of peers shared per peer-exchange message.
+    pub peer_exchange_sample_size: usize,
+
+    /// Connected peers sent a keepalive each tick, rotating through all of
+    /// them (0 = disabled, default: 1).
+    ///
+    /// Keeps quiet links from hitting `connection_timeout`: every Connected
+    /// peer hears from us at least every `connected / keepalives_per_tick` ticks.
+    pub keepalives_per_tick: usize,
+
+    /// Interval between commit chain head advertisements (0 = disabled, default: 0).
+    ///
+    /// `EcNode::tick` sends `Message::CommitHead` to every Connected peer on
+    /// ticks that are a multiple of this. Keepalives already carry
+    /// `head_of_chain`, so this is only needed with `keepalives_per_tick: 0`,
+    /// where no other message advertises a new head to peers that are not
+    /// querying us.
+    pub commit_head_interval: EcTime,
+
     /// Desired number of locally discovered peer candidates before widening the
     /// local probe radius.
     pub local_discovery_target: usize,
@@ -126,6 +170,20 @@ pub struct PeerManagerConfig {
     /// Maximum time to wait for election before timeout (in ticks, default: 30)
     pub election_timeout: u64,
 
+    /// Ticks an election channel may stay unanswered before it expires and
+    /// ignores late answers and referrals (0 = never, default: 20)
+    pub channel_timeout: u64,
+
+    /// Minimum ticks between elections started by Invitations from the same
+    /// Identified peer (spam prevention, default: 60)
+    pub invitation_cooldown: EcTime,
+
+    /// Channels opened per election round, also when retrying a split brain (default: 4)
+    pub channels_per_election: usize,
+
+    /// Closest known peers considered as first hops for those channels (default: 8)
+    pub closest_candidates: usize,
+
     // ===== Timeout Parameters =====
     /// Timeout for Pending state before demoting to Identified (in ticks, default: 10)
     pub pending_timeout: u64,
@@ -136,6 +194,18 @@ pub struct PeerManagerConfig {
     /// Protection time for recently connected peers from pruning (in ticks, default: 600 = 10 min)
     pub prune_protection_time: u64,
 
+    /// Connected peers that won one of our elections within this many ticks are
+    /// exempt from distance-based pruning, so useful mid-ring peers don't flap
+    /// between Connected and Identified (0 = disabled, default: 600)
+    ///
+    /// Only applies outside the shaped/targeted modes, which already prune only
+    /// above their target band.
+    pub prune_contribution_window: u64,
+
+    /// Normalization of ring distance for invitation acceptance and pruning
+    /// outside the small-world mode (default: `HalfRing`)
+    pub distance_normalization: DistanceBasis,
+
     /// Number of connected peers to include on each side when estimating a local neighborhood.
     pub neighborhood_width: usize,
 
@@ -214,6 +284,17 @@ pub struct PeerManagerConfig {
     /// span is considered filled.
     pub answer_span_min_connected: usize,
 
+    /// Maximum tokens examined by one proof-of-storage signature search when
+    /// answering a query. An exhausted search falls back to a referral instead
+    /// of blocking the node (default: unbounded).
+    pub signature_search_max_steps: usize,
+
+    /// Forward queries from Connected peers that we can't answer to a
+    /// Connected peer among the two closest, instead of referring the querier
+    /// (default: false). The owner answers the original querier directly;
+    /// forwarded queries are not forwarded again.
+    pub forward_queries: bool,
+
     // ===== Election Configuration =====
     /// Configuration for PeerElection
     pub election_config: ElectionConfig,
@@ -226,6 +307,7 @@ impl Default for PeerManagerConfig {
             connected_max_capacity: 200,
             identified_max_capacity: 5000,
             token_sample_max_capacity: 1000,
+            sampling_bias: 0.0,
 
             // Election parameters
             elections_per_tick: 3,
@@ -234,14 +316,24 @@ impl Default for PeerManagerConfig {
             peer_id_election_only: false,
             referral_probes_per_tick: 0,
             referral_probe_hops: 5,
+            peer_exchange_interval: 0,
+            peer_exchange_sample_size: 8,
+            keepalives_per_tick: 1,
+            commit_head_interval: 0,
             local_discovery_target: 100,
             min_collection_time: 10,
             election_timeout: 30,
+            channel_timeout: 20,
+            invitation_cooldown: 60,
+            channels_per_election: 4,
+            closest_candidates: 8,
 
             // Timeout parameters
             pending_timeout: 10,
             connection_timeout: 300,
             prune_protection_time: 600,
+            prune_contribution_window: 600,
+            distance_normalization: DistanceBasis::HalfRing,
             neighborhood_width: 4,
             vote_target_count: 2,
             first_vote_target_count: 4,
@@ -260,6 +352,8 @@ impl Default for PeerManagerConfig {
             small_world: None,
             enable_answer_density_repair: false,
             answer_span_min_connected: 1,
+            signature_search_max_steps: usize::MAX,
+            forward_queries: false,
 
             // Election configuration
             election_config: ElectionConfig::default(),
@@ -298,6 +392,8 @@ pub enum PeerState {
         election_attempts: usize,
         /// Current quality score (0.0 - 1.0)
         quality_score: f64,
+        /// Last time this peer won one of our elections
+        last_win_at: Option<EcTime>,
     },
 }
 
@@ -313,6 +409,47 @@ impl PeerState {
     pub fn is_identified(&self) -> bool {
         matches!(self, PeerState::Identified { .. })
     }
+
+    pub fn kind(&self) -> PeerStateKind {
+        match self {
+            PeerState::Identified { .. } => PeerStateKind::Identified,
+            PeerState::Pending { .. } => PeerStateKind::Pending,
+            PeerState::Connected { .. } => PeerStateKind::Connected,
+        }
+    }
+}
+
+/// `PeerState` without its data, for filtering (see `EcPeers::peers_in_state`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PeerStateKind {
+    Identified,
+    Pending,
+    Connected,
+}
+
+/// Quality score assigned to newly Connected peers (neutral: no prune bias)
+const INITIAL_QUALITY_SCORE: f64 = 0.5;
+
+/// Per-election decay of `quality_score`
+///
+/// Each answered election multiplies the score by this factor and each win adds
+/// `1 - QUALITY_DECAY`, so the score tracks an exponentially decaying win ratio.
+const QUALITY_DECAY: f64 = 0.9;
+
+/// Slack added beyond the farthest known peer by `DistanceBasis::ObservedSpread`
+///
+/// Without it the farthest peer sits at fraction 1.0: its invitations are never
+/// accepted and it is always pruned at neutral quality, so the observed spread
+/// could only shrink. With 0.25 the farthest peer lands at fraction 0.8, and
+/// invitations from up to a quarter beyond it still have a chance.
+const OBSERVED_SPREAD_MARGIN: f64 = 0.25;
+
+/// Scale a prune probability/weight by peer quality
+///
+/// Neutral (1.0) at `INITIAL_QUALITY_SCORE`, 0.5 for perfect peers, 1.5 for
+/// peers that never win.
+fn quality_prune_factor(quality_score: f64) -> f64 {
+    (1.0 + INITIAL_QUALITY_SCORE - quality_score).clamp(0.5, 1.5)
 }
 
 /// Extended peer information with state
@@ -320,7 +457,21 @@ struct MemPeer {
     state: PeerState,
     /// Last known commit chain head for this peer (if available)
     commit_chain_head: Option<CommitBlockId>,
-    // TODO: network address, shared secret
+    /// Network address for routing messages to this peer (if known)
+    /// Kept across state transitions, dropped when the peer is removed
+    addr: Option<SocketAddr>,
+    // TODO: shared secret
+}
+
+/// Read-only copy of what `EcPeers` knows about one peer (see `EcPeers::peer_state`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PeerStateSnapshot {
+    /// Lifecycle state, including timestamps and quality score
+    pub state: PeerState,
+    /// Last known commit chain head (if learned)
+    pub commit_chain_head: Option<CommitBlockId>,
+    /// Network address (if known)
+    pub addr: Option<SocketAddr>,
 }
 
 // ============================================================================
@@ -328,7 +479,10 @@ struct MemPeer {
 // ============================================================================
 
 /// Actions that EcPeers requests EcNode to perform
+///
+/// Non-exhaustive: callers should log and drop actions they can't route.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum PeerAction {
     /// Send a Query message to a peer (for elections)
     SendQuery {
@@ -357,6 +511,25 @@ pub enum PeerAction {
         answer: TokenMapping,
         signature: [TokenMapping; TOKENS_SIGNATURE_SIZE],
     },
+
+    /// Send a peer-exchange list (sample of our closest peers)
+    SendPeerList {
+        receiver: PeerId,
+        peers: Vec<PeerId>,
+    },
+
+    /// Tell a Connected peer we are still here
+    SendKeepalive { receiver: PeerId },
+
+    /// Pass a query on to a Connected peer closer to the token
+    ///
+    /// The owner replies to `original_querier` (the Query's `target`).
+    ForwardQuery {
+        receiver: PeerId,
+        token: TokenId,
+        ticket: MessageTicket,
+        original_querier: PeerId,
+    },
 }
 
 impl PeerAction {
@@ -445,6 +618,39 @@ impl PeerAction {
                     head_of_chain,
                 },
             },
+
+            PeerAction::SendPeerList { receiver, peers } => MessageEnvelope {
+                sender,
+                receiver,
+                ticket: 0, // Unsolicited gossip, no ticket
+                time,
+                message: Message::PeerList { peers },
+            },
+
+            PeerAction::SendKeepalive { receiver } => MessageEnvelope {
+                sender,
+                receiver,
+                ticket: 0,
+                time,
+                message: Message::Keepalive { head_of_chain },
+            },
+
+            PeerAction::ForwardQuery {
+                receiver,
+                token,
+                ticket,
+                original_querier,
+            } => MessageEnvelope {
+                sender,
+                receiver,
+                ticket,
+                time,
+                message: Message::QueryToken {
+                    token_id: token,
+                    target: original_querier,
+                    ticket,
+                },
+            },
         }
     }
 }
@@ -457,6 +663,9 @@ impl PeerAction {
 struct OngoingElection {
     election: PeerElection,
     started_at: EcTime,
+
+    /// Set once the election reported a split brain and spawned extra channels
+    split_brain_seen: bool,
 }
 
 struct DiscoveryProbe {
@@ -474,33 +683,57 @@ struct DiscoveryProbe {
 /// This structure maintains a bounded collection of tokens used for triggering elections.
 /// Tokens are added from validated Answers, Invitations, and Referrals throughout the
 /// node's lifetime. The collection is continuously pruned via:
-/// - Uniform random eviction when at capacity
+/// - Random eviction when over capacity (uniform, or distance-weighted with a bias)
 /// - Removal when a token is selected for an election
 ///
 /// The combination of biased input (gradient routing provides nearby tokens) and
-/// uniform eviction naturally produces a Gaussian distribution centered on our peer ID.
+/// uniform eviction is meant to produce a distribution centered on our peer ID.
+/// When far tokens still dominate, a positive `bias` evicts them preferentially.
 struct TokenSampleCollection {
-    /// Flat set of sampled tokens
-    samples: HashSet<TokenId>,
+    /// Flat set of sampled tokens (ordered so seeded sampling is reproducible)
+    samples: BTreeSet<TokenId>,
 
     /// Maximum capacity
     max_capacity: usize,
+
+    /// Our peer ID, the center that distance-weighted eviction favors
+    center: PeerId,
+
+    /// Eviction weight exponent on ring distance to `center` (0.0 = uniform)
+    bias: f64,
 }
 
 impl TokenSampleCollection {
-    /// Create a new empty token sample collection
+    /// Create a new empty token sample collection with uniform eviction
     fn new(max_capacity: usize) -> Self {
         Self {
-            samples: HashSet::new(),
+            samples: BTreeSet::new(),
             max_capacity,
+            center: 0,
+            bias: 0.0,
         }
     }
 
+    /// Evict tokens far from `center` first, with weight exponent `bias`
+    fn with_bias(mut self, center: PeerId, bias: f64) -> Self {
+        self.center = center;
+        self.bias = bias;
+        self
+    }
+
     /// Add a token to the collection
     /// Returns true if token was added, false if already present or at capacity
+    ///
+    /// With a positive bias the collection accepts up to twice its capacity, so
+    /// nearby tokens arriving when full can displace far ones at the next eviction.
     fn add_token(&mut self, token: TokenId) -> bool {
         // If at capacity, don't add (eviction happens separately in tick)
-        if self.samples.len() >= self.max_capacity {
+        let limit = if self.bias > 0.0 {
+            self.max_capacity.saturating_mul(2)
+        } else {
+            self.max_capacity
+        };
+        if self.samples.len() >= limit {
             return false;
         }
 
@@ -508,12 +741,14 @@ impl TokenSampleCollection {
     }
 
     /// Sample tokens from an Answer message
-    /// Answer contains: 1 answer token + 10 signature tokens = 11 total
+    /// Answer contains: 1 answer token + active signature tokens (10 by default)
     /// Also adds the peer_id of the sender
+    ///
+    /// `signature` should be the active prefix of the Answer signature array.
     fn sample_from_answer(
         &mut self,
         answer: &TokenMapping,
-        signature: &[TokenMapping; TOKENS_SIGNATURE_SIZE],
+        signature: &[TokenMapping],
         sender_peer_id: PeerId,
     ) {
         // Add sender's peer ID as a token
@@ -549,6 +784,9 @@ impl TokenSampleCollection {
 
     /// Evict random tokens if over capacity
     /// Returns number of tokens evicted
+    ///
+    /// Uniform when `bias` is 0.0, otherwise each pick is weighted by
+    /// `(ring_distance(center, token) / 2^63)^bias`.
     fn evict_excess<R: rand::Rng>(&mut self, rng: &mut R) -> usize {
         if self.samples.len() <= self.max_capacity {
             return 0;
@@ -557,7 +795,11 @@ impl TokenSampleCollection {
         use rand::seq::IteratorRandom;
 
         let excess = self.samples.len() - self.max_capacity;
-        let to_evict: Vec<TokenId> = self.samples.iter().copied().choose_multiple(rng, excess);
+        let to_evict: Vec<TokenId> = if self.bias > 0.0 {
+            self.pick_weighted_by_distance(excess, rng)
+        } else {
+            self.samples.iter().copied().choose_multiple(rng, excess)
+        };
 
         for token in &to_evict {
             self.samples.remove(token);
@@ -565,6 +807,40 @@ impl TokenSampleCollection {
 
         to_evict.len()
     }
+
+    /// Pick `n` distinct tokens, favoring those far from `center`
+    fn pick_weighted_by_distance<R: rand::Rng>(&self, n: usize, rng: &mut R) -> Vec<TokenId> {
+        const MAX_RING_DISTANCE: f64 = (1u64 << 63) as f64;
+
+        // Floor the weight so tokens at distance 0 can still go once the rest are gone
+        let mut candidates: Vec<(TokenId, f64)> = self
+            .samples
+            .iter()
+            .map(|&token| {
+                let distance = ring_distance_generic(self.center, token) as f64;
+                let weight = (distance / MAX_RING_DISTANCE).powf(self.bias);
+                (token, weight.max(f64::MIN_POSITIVE))
+            })
+            .collect();
+
+        let mut picked = Vec::with_capacity(n);
+        for _ in 0..n.min(candidates.len()) {
+            let total_weight = candidates.iter().map(|(_, weight)| *weight).sum::<f64>();
+            let mut pick = rng.gen_range(0.0..total_weight);
+            let mut selected_idx = candidates.len() - 1;
+            for (idx, (_, weight)) in candidates.iter().enumerate() {
+                if pick <= *weight {
+                    selected_idx = idx;
+                    break;
+                }
+                pick -= *weight;
+            }
+
+            picked.push(candidates.swap_remove(selected_idx).0);
+        }
+
+        picked
+    }
 }
 
 impl OngoingElection {
@@ -572,6 +848,7 @@ impl OngoingElection {
         Self {
             election,
             started_at,
+            split_brain_seen: false,
         }
     }
 }
@@ -620,8 +897,28 @@ pub struct EcPeers {
 
     /// Total split-brain scenarios detected (lifetime counter)
     elections_splitbrain_total: usize,
+
+    /// Split-brain elections that later completed with a single winner
+    /// (lifetime counter, also counted as completed)
+    elections_splitbrain_resolved_total: usize,
+
+    /// Sink for peer lifecycle and election events (NoOpSink by default)
+    event_sink: Box<dyn EventSink>,
+
+    /// Banned peer IDs (never re-added, queried, or connected)
+    banned: HashSet<PeerId>,
+
+    /// Time of the last peer-exchange gossip we sent
+    last_peer_exchange_at: Option<EcTime>,
+
+    /// Position in `active` where the next keepalive round starts
+    keepalive_cursor: usize,
+
+    /// Tick-driven elections are suppressed while `time < until`
+    elections_paused_until: Option<EcTime>,
 }
 
+#[derive(Clone)]
 pub struct PeerRange {
     high: PeerId,
     low: PeerId,
@@ -650,36 +947,44 @@ impl EcPeers {
 
     /// Calculate ring distance between two peer IDs
     fn ring_distance(a: PeerId, b: PeerId) -> u64 {
-        let forward = b.wrapping_sub(a);
-        let backward = a.wrapping_sub(b);
-        forward.min(backward)
+        ring_distance_generic(a, b)
     }
 
-    /// Find closest peers to a target token (for election channels)
-    /// Walks BTreeMap in both directions from target
-    pub fn find_closest_peers(&self, target: TokenId, count: usize) -> Vec<PeerId> {
-        let mut candidates = Vec::new();
-
-        // Walk forward from target (including target)
-        let forward: Vec<_> = self
-            .peers
+    /// Up to `count` peers at or clockwise after `target`, in ring order
+    ///
+    /// Wraps past the top of the ring; each peer appears at most once.
+    pub fn find_successors(&self, target: TokenId, count: usize) -> Vec<PeerId> {
+        self.peers
             .range(target..)
+            .chain(self.peers.range(..target))
             .take(count)
             .map(|(id, _)| *id)
-            .collect();
+            .collect()
+    }
 
-        // Walk backward from target (excluding target)
-        let backward: Vec<_> = self
-            .peers
+    /// Up to `count` peers counter-clockwise before `target`, nearest first
+    ///
+    /// Wraps past the bottom of the ring, so a peer at `target` comes last.
+    pub fn find_predecessors(&self, target: TokenId, count: usize) -> Vec<PeerId> {
+        self.peers
             .range(..target)
             .rev()
+            .chain(self.peers.range(target..).rev())
             .take(count)
             .map(|(id, _)| *id)
-            .collect();
+            .collect()
+    }
 
-        // Collect all candidates
-        candidates.extend(forward);
-        candidates.extend(backward);
+    /// Find closest peers to a target token (for election channels)
+    /// Merges `count` successors and predecessors by ring distance
+    pub fn find_closest_peers(&self, target: TokenId, count: usize) -> Vec<PeerId> {
+        let mut candidates = self.find_successors(target, count);
+        for peer in self.find_predecessors(target, count) {
+            // Small rings: both walks can wrap onto the same peers
+            if !candidates.contains(&peer) {
+                candidates.push(peer);
+            }
+        }
 
         // Sort by distance from target
         candidates.sort_by_key(|&p| Self::ring_distance(p, target));
@@ -790,6 +1095,7 @@ impl EcPeers {
                 Ok(()) => {
                     // Answer successfully recorded
                     self.update_keepalive(peer_id, time);
+                    self.record_election_attempt(peer_id);
 
                     // Winner will be detected in process_elections()
                     // Sample tokens from Answer for future discovery
@@ -800,8 +1106,12 @@ impl EcPeers {
                             self.token_samples.add_token(answer.id);
                         }
                     } else {
-                        self.token_samples
-                            .sample_from_answer(answer, signature, peer_id);
+                        let chunks = self.config.election_config.signature_params.chunks;
+                        self.token_samples.sample_from_answer(
+                            answer,
+                            &signature[..chunks],
+                            peer_id,
+                        );
                     }
                 }
                 Err(_e) => {
@@ -853,10 +1163,8 @@ impl EcPeers {
                     // Peer is already known (from initial topology or discovery)
                     // Always respond regardless of distance (they're legitimate)
                     // Check per-peer invitation cooldown
-                    const INVITATION_COOLDOWN: EcTime = 60; // Minimum 60 ticks between invitations from same peer
-
                     if let Some(last_time) = last_invitation_election_at {
-                        if time - last_time > INVITATION_COOLDOWN {
+                        if time.saturating_sub(last_time) > self.config.invitation_cooldown {
                             trigger_election = true
                         }
                     } else {
@@ -945,8 +1253,11 @@ impl EcPeers {
                 Ok(next_peer) => {
                     // Election returned a suggested peer to try next
 
-                    // Create a new channel to the suggested peer
-                    if let Ok(new_ticket) = ongoing.election.create_channel(next_peer, time) {
+                    // Create a new channel to the suggested peer (never to a banned one)
+                    if self.banned.contains(&next_peer) {
+                        None
+                    } else if let Ok(new_ticket) = ongoing.election.create_channel(next_peer, time)
+                    {
                         Some(PeerAction::SendQuery {
                             receiver: next_peer,
                             token,
@@ -978,6 +1289,101 @@ impl EcPeers {
         action
     }
 
+    /// Handle a peer-exchange list from a Connected or Pending peer
+    ///
+    /// Folds the received peer IDs into Identified, skipping banned and already
+    /// known peers and stopping at `identified_max_capacity`. Lists from
+    /// unknown or banned senders are ignored.
+    ///
+    /// Returns the number of newly Identified peers.
+    pub fn handle_peer_list(&mut self, peers: &[PeerId], sender: PeerId, time: EcTime) -> usize {
+        if self.banned.contains(&sender) || !self.is_active(&sender) {
+            return 0;
+        }
+
+        let mut identified = self.num_identified();
+        let mut added = 0;
+        for &peer_id in peers {
+            if identified >= self.config.identified_max_capacity {
+                break;
+            }
+            if self.add_identified_peer(peer_id, time) {
+                identified += 1;
+                added += 1;
+            }
+        }
+
+        added
+    }
+
+    /// Build a peer-exchange message for a random Connected peer if one is due
+    fn peer_exchange_action(&mut self, time: EcTime) -> Option<PeerAction> {
+        use rand::Rng;
+        let interval = self.config.peer_exchange_interval;
+        if interval == 0 || self.active.is_empty() {
+            return None;
+        }
+        if self
+            .last_peer_exchange_at
+            .is_some_and(|last| time.saturating_sub(last) < interval)
+        {
+            return None;
+        }
+        self.last_peer_exchange_at = Some(time);
+
+        let receiver = self.active[self.rng.gen_range(0..self.active.len())];
+        let sample_size = self.config.peer_exchange_sample_size;
+        let peers: Vec<PeerId> = self
+            .find_closest_active_peers(self.peer_id, sample_size + 1)
+            .into_iter()
+            .filter(|&peer_id| peer_id != receiver)
+            .take(sample_size)
+            .collect();
+
+        if peers.is_empty() {
+            return None;
+        }
+
+        Some(PeerAction::SendPeerList { receiver, peers })
+    }
+
+    /// Handle a keepalive from `sender` (see `keepalives_per_tick`)
+    ///
+    /// - Connected: refresh `last_keepalive`
+    /// - Pending: promote to Connected, the sender evidently keeps us too
+    /// - Identified: unchanged, only an election connects it
+    /// - Unknown: added to Identified if there is room; banned senders are ignored
+    pub fn handle_keepalive(&mut self, sender: PeerId, time: EcTime) {
+        match self.peers.get(&sender).map(|peer| peer.state) {
+            Some(PeerState::Connected { .. }) => self.update_keepalive(sender, time),
+            Some(PeerState::Pending { .. }) => {
+                self.promote_to_connected(sender, time);
+            }
+            Some(PeerState::Identified { .. }) => {}
+            None => {
+                if self.num_identified() < self.config.identified_max_capacity {
+                    self.add_identified_peer(sender, time);
+                }
+            }
+        }
+    }
+
+    /// Keepalives for the next `keepalives_per_tick` Connected peers
+    fn keepalive_actions(&mut self) -> Vec<PeerAction> {
+        let count = self.config.keepalives_per_tick.min(self.active.len());
+        if count == 0 {
+            return Vec::new();
+        }
+
+        let start = self.keepalive_cursor % self.active.len();
+        self.keepalive_cursor = start + count;
+        (start..start + count)
+            .map(|i| PeerAction::SendKeepalive {
+                receiver: self.active[i % self.active.len()],
+            })
+            .collect()
+    }
+
     fn handle_discovery_referral(
         &mut self,
         ticket: MessageTicket,
@@ -1026,20 +1432,33 @@ impl EcPeers {
         })
     }
 
+    /// Whether `token` is stored locally
+    ///
+    /// A plain lookup, without the signature search `handle_query` runs to
+    /// build an Answer.
+    pub fn owns_token(&self, backend: &dyn TokenStorageBackend, token: &TokenId) -> bool {
+        backend.lookup(token).is_some()
+    }
+
     /// Handle a Query message - gateway to proof-of-storage system
     ///
     /// This is the main entry point for responding to queries. It:
-    /// 1. Checks if we own the requested token using proof-of-storage
+    /// 1. Checks if we own the requested token (`owns_token`), then runs the
+    ///    proof-of-storage signature search
     /// 2. If found: generates Answer with signature
-    /// 3. If not found: generates Referral with 2 closest Connected Peers
+    /// 3. If not found: generates Referral with 2 closest Connected Peers, or
+    ///    forwards the query when `forward_queries` is on and `may_forward`
     ///
     /// # Arguments
     /// - `token`: The token being queried
     /// - `ticket`: Message ticket for this query
-    /// - `querier`: The peer requesting the token
+    /// - `querier`: The peer the answer goes to (signatures are bound to it)
+    /// - `may_forward`: False for queries that were already forwarded
     ///
     /// # Returns
     /// - `Some(PeerAction::SendAnswer)`: If we own the token
+    /// - `Some(PeerAction::ForwardQuery)`: If forwarding, the querier is Connected
+    ///   and so is one of the two closest peers
     /// - `Some(PeerAction::SendReferral)`: If we don't own it but have peers to suggest
     /// - `None`: If we don't own the token and have no peers to suggest
     pub fn handle_query(
@@ -1048,12 +1467,16 @@ impl EcPeers {
         token: TokenId,
         ticket: MessageTicket,
         querier: PeerId,
+        may_forward: bool,
     ) -> Option<PeerAction> {
-        // Try to generate a signature (checks if we own the token)
-        if let Some(signature) =
+        // Only tokens we own need the signature search
+        let signature = if self.owns_token(token_storage, &token) {
             self.proof_system
                 .generate_signature(token_storage, &token, &querier)
-        {
+        } else {
+            None
+        };
+        if let Some(signature) = signature {
             // We own the token - send Answer
             return Some(PeerAction::SendAnswer {
                 answer: signature.answer,
@@ -1071,7 +1494,23 @@ impl EcPeers {
         let closest = self.find_closest_peers(token, 2);
 
         if closest.len() >= 2 {
-            // TODO forward Query for Connected peers instead of Referral
+            // Forwarding is a service for Connected peers only
+            let is_connected =
+                |peer: &PeerId| self.peers.get(peer).is_some_and(|p| p.state.is_connected());
+            if may_forward && self.config.forward_queries && is_connected(&querier) {
+                let next = closest
+                    .iter()
+                    .copied()
+                    .find(|peer| *peer != querier && is_connected(peer));
+                if let Some(receiver) = next {
+                    return Some(PeerAction::ForwardQuery {
+                        receiver,
+                        token,
+                        ticket,
+                        original_querier: querier,
+                    });
+                }
+            }
             Some(PeerAction::SendReferral {
                 token,
                 ticket,
@@ -1185,6 +1624,19 @@ impl EcPeers {
         *self.active.get(self.idx_adj(idx, adj)).unwrap()
     }
 
+    /// Like `peers_for`, without the time rotation
+    ///
+    /// Same answer every tick for as long as the active set is unchanged; the
+    /// time-mixed version spreads load across neighbors instead.
+    pub fn peers_for_stable(&self, key: &TokenId) -> [PeerId; 2] {
+        self.peers_for(key, 0)
+    }
+
+    /// Like `peer_for`, without the time rotation (see `peers_for_stable`)
+    pub fn peer_for_stable(&self, key: &TokenId) -> PeerId {
+        self.peer_for(key, 0)
+    }
+
     /// Get peer ID by index in active list
     pub fn for_index(&self, idx: usize) -> Option<PeerId> {
         self.active.get(idx).copied()
@@ -1252,8 +1704,8 @@ impl EcPeers {
     /// Update or add a peer (for backward compatibility with existing code)
     /// This is used by seed_peer in EcNode
     pub fn update_peer(&mut self, key: &PeerId, time: EcTime) {
-        if *key == self.peer_id {
-            return; // Never store self
+        if *key == self.peer_id || self.banned.contains(key) {
+            return; // Never store self or banned peers
         }
 
         // Check if peer already exists
@@ -1273,9 +1725,11 @@ impl EcPeers {
                         last_keepalive: time,
                         election_wins: 0,
                         election_attempts: 0,
-                        quality_score: 1.0, // Start with max quality
+                        quality_score: INITIAL_QUALITY_SCORE,
+                        last_win_at: None,
                     },
                     commit_chain_head: None, // Unknown until we get an Answer message
+                    addr: None,
                 },
             );
 
@@ -1287,7 +1741,66 @@ impl EcPeers {
 
             // Add peer ID to token samples (peer IDs are valid tokens for discovery)
             self.token_samples.add_token(*key);
+
+            self.emit(time, Event::PeerConnected { peer_id: *key });
+        }
+    }
+
+    /// Add a seed peer directly to Connected, optionally with its network address
+    pub fn add_seed_peer(&mut self, peer_id: PeerId, addr: Option<SocketAddr>, time: EcTime) {
+        self.update_peer(&peer_id, time);
+        if let Some(addr) = addr {
+            self.set_peer_addr(&peer_id, addr);
+        }
+    }
+
+    /// Set the network address for a known peer
+    ///
+    /// Returns false if the peer is unknown.
+    pub fn set_peer_addr(&mut self, peer_id: &PeerId, addr: SocketAddr) -> bool {
+        match self.peers.get_mut(peer_id) {
+            Some(peer) => {
+                peer.addr = Some(addr);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Get the network address for a peer
+    ///
+    /// Returns None if peer is unknown or its address has not been set.
+    pub fn peer_addr(&self, peer_id: &PeerId) -> Option<SocketAddr> {
+        self.peers.get(peer_id).and_then(|peer| peer.addr)
+    }
+
+    /// Ban a peer permanently
+    ///
+    /// A Connected peer is demoted first; the peer is then forgotten and will not
+    /// be re-added by referrals, answers, or seeding until unbanned.
+    /// Returns false if the peer was already banned.
+    pub fn ban_peer(&mut self, peer_id: PeerId, time: EcTime) -> bool {
+        if !self.banned.insert(peer_id) {
+            return false;
         }
+
+        self.demote_from_connected(peer_id, time);
+        self.peers.remove(&peer_id);
+        self.active.retain(|&p| p != peer_id);
+
+        true
+    }
+
+    /// Lift a ban so the peer can be discovered again
+    ///
+    /// Returns false if the peer was not banned.
+    pub fn unban_peer(&mut self, peer_id: PeerId) -> bool {
+        self.banned.remove(&peer_id)
+    }
+
+    /// Check if a peer is banned
+    pub fn is_banned(&self, peer_id: &PeerId) -> bool {
+        self.banned.contains(peer_id)
     }
 
     // ========================================================================
@@ -1301,6 +1814,10 @@ impl EcPeers {
             return false; // Never add self
         }
 
+        if self.banned.contains(&peer_id) {
+            return false; // Never re-add banned peers
+        }
+
         // Check if peer already exists
         if self.peers.contains_key(&peer_id) {
             return false; // Already known
@@ -1315,6 +1832,7 @@ impl EcPeers {
                     last_invitation_election_at: None,
                 },
                 commit_chain_head: None, // Unknown until we get an Answer message
+                addr: None,
             },
         );
 
@@ -1366,7 +1884,8 @@ impl EcPeers {
             last_keepalive: time,
             election_wins: 0,
             election_attempts: 0,
-            quality_score: 1.0, // Start with perfect quality
+            quality_score: INITIAL_QUALITY_SCORE,
+            last_win_at: None,
         };
 
         // Add to active list
@@ -1374,6 +1893,8 @@ impl EcPeers {
             self.active.insert(idx, peer_id);
         }
 
+        self.emit(time, Event::PeerConnected { peer_id });
+
         true
     }
 
@@ -1399,6 +1920,8 @@ impl EcPeers {
             self.active.remove(idx);
         }
 
+        self.emit(time, Event::PeerDemoted { peer_id });
+
         true
     }
 
@@ -1450,7 +1973,7 @@ impl EcPeers {
                 invitation_sent_at, ..
             } = peer.state
             {
-                if time - invitation_sent_at >= timeout_threshold {
+                if time.saturating_sub(invitation_sent_at) >= timeout_threshold {
                     timed_out.push(*peer_id);
                 }
             }
@@ -1472,7 +1995,7 @@ impl EcPeers {
 
         for (peer_id, peer) in &self.peers {
             if let PeerState::Connected { last_keepalive, .. } = peer.state {
-                if time - last_keepalive >= timeout_threshold {
+                if time.saturating_sub(last_keepalive) >= timeout_threshold {
                     timed_out.push(*peer_id);
                 }
             }
@@ -1530,11 +2053,13 @@ impl EcPeers {
                 .iter()
                 .filter_map(|(peer_id, peer)| {
                     if let PeerState::Connected {
-                        connected_since, ..
+                        connected_since,
+                        quality_score,
+                        ..
                     } = peer.state
                     {
                         self.small_world_prune_weight(*peer_id, connected_since, time)
-                            .map(|weight| (*peer_id, weight))
+                            .map(|weight| (*peer_id, weight * quality_prune_factor(quality_score)))
                     } else {
                         None
                     }
@@ -1584,11 +2109,13 @@ impl EcPeers {
                 .iter()
                 .filter_map(|(peer_id, peer)| {
                     if let PeerState::Connected {
-                        connected_since, ..
+                        connected_since,
+                        quality_score,
+                        ..
                     } = peer.state
                     {
                         self.shape_prune_weight(*peer_id, connected_since, time)
-                            .map(|weight| (*peer_id, weight))
+                            .map(|weight| (*peer_id, weight * quality_prune_factor(quality_score)))
                     } else {
                         None
                     }
@@ -1638,11 +2165,13 @@ impl EcPeers {
                 .iter()
                 .filter_map(|(peer_id, peer)| {
                     if let PeerState::Connected {
-                        connected_since, ..
+                        connected_since,
+                        quality_score,
+                        ..
                     } = peer.state
                     {
                         self.target_prune_weight(*peer_id, connected_since, time)
-                            .map(|weight| (*peer_id, weight))
+                            .map(|weight| (*peer_id, weight * quality_prune_factor(quality_score)))
                     } else {
                         None
                     }
@@ -1679,25 +2208,34 @@ impl EcPeers {
             return;
         }
 
-        let ring_size = u64::MAX as f64 / 2.0; // Half ring (max distance)
-
+        let basis = self.distance_basis();
         let to_demote: Vec<PeerId> = self
             .peers
             .iter()
             .filter_map(|(peer_id, peer)| {
                 if let PeerState::Connected {
-                    connected_since, ..
+                    connected_since,
+                    quality_score,
+                    last_win_at,
+                    ..
                 } = peer.state
                 {
-                    // Protect recently connected peers
-                    if time - connected_since < self.config.prune_protection_time {
+                    // Protect recently connected peers (this also holds off
+                    // re-pruning a peer that was just reconnected)
+                    if time.saturating_sub(connected_since) < self.config.prune_protection_time {
+                        return None;
+                    }
+
+                    // Hysteresis: keep peers that recently won one of our elections
+                    let window = self.config.prune_contribution_window;
+                    if last_win_at.is_some_and(|won| time.saturating_sub(won) < window) {
                         return None;
                     }
 
-                    // Calculate prune probability based on distance
+                    // Calculate prune probability based on distance and quality
                     let distance = Self::ring_distance(self.peer_id, *peer_id) as f64;
-                    let distance_fraction = distance / ring_size;
-                    let prune_prob = distance_fraction; // Linear (0.0 near, ~1.0 far)
+                    let prune_prob =
+                        Self::connected_prune_probability(distance / basis, quality_score);
 
                     if self.rng.gen_bool(prune_prob) {
                         Some(*peer_id)
@@ -1716,33 +2254,136 @@ impl EcPeers {
         }
     }
 
-    fn connected_target_bounds(&self) -> Option<(usize, usize, usize)> {
-        let target = self.config.connected_target?;
-        let hysteresis = self.config.connected_target_hysteresis;
-        Some((
-            target.saturating_sub(hysteresis),
-            target,
-            target.saturating_add(hysteresis),
-        ))
-    }
-
-    fn small_world_target_bounds(&self) -> Option<(usize, usize)> {
-        let small_world = self.config.small_world.as_ref()?;
-        Some((
-            small_world.peer_budget,
-            small_world
-                .peer_budget
-                .saturating_add(small_world.hysteresis),
-        ))
+    /// Prune probability for a Connected peer outside the shaped/targeted modes
+    ///
+    /// Linear in distance fraction (0.0 near, 1.0 far, see `distance_basis`),
+    /// scaled by `quality_prune_factor` so historically useful peers are kept longer.
+    fn connected_prune_probability(distance_fraction: f64, quality_score: f64) -> f64 {
+        (distance_fraction * quality_prune_factor(quality_score)).clamp(0.0, 1.0)
+    }
+
+    /// Ring distance that counts as fully far (see `DistanceBasis`)
+    fn distance_basis(&self) -> f64 {
+        let half_ring = u64::MAX as f64 / 2.0;
+        match self.config.distance_normalization {
+            DistanceBasis::HalfRing => half_ring,
+            DistanceBasis::ObservedSpread => self
+                .peers
+                .keys()
+                .map(|&peer_id| Self::ring_distance(self.peer_id, peer_id))
+                .max()
+                .map_or(half_ring, |farthest| {
+                    (farthest.max(1) as f64 * (1.0 + OBSERVED_SPREAD_MARGIN)).min(half_ring)
+                }),
+        }
     }
 
-    fn shape_target_bounds(&self) -> Option<(usize, usize)> {
-        let shape = self.config.shape_target.as_ref()?;
-        let target = self.shape_expected_connected_count().round() as usize;
-        Some((target, target.saturating_add(shape.hysteresis)))
+    /// Ring distance to `peer_id` as a fraction of `distance_basis`, in 0.0..=1.0
+    fn distance_fraction(&self, peer_id: PeerId) -> f64 {
+        let distance = Self::ring_distance(self.peer_id, peer_id) as f64;
+        (distance / self.distance_basis()).clamp(0.0, 1.0)
     }
 
-    fn is_above_connected_target(&self) -> bool {
+    /// Record that a Connected peer answered in one of our elections
+    fn record_election_attempt(&mut self, peer_id: PeerId) {
+        if let Some(MemPeer {
+            state:
+                PeerState::Connected {
+                    election_attempts,
+                    quality_score,
+                    ..
+                },
+            ..
+        }) = self.peers.get_mut(&peer_id)
+        {
+            *election_attempts += 1;
+            *quality_score *= QUALITY_DECAY;
+        }
+    }
+
+    /// Record that a Connected peer was elected winner
+    fn record_election_win(&mut self, peer_id: PeerId, time: EcTime) {
+        if let Some(MemPeer {
+            state:
+                PeerState::Connected {
+                    election_wins,
+                    quality_score,
+                    last_win_at,
+                    ..
+                },
+            ..
+        }) = self.peers.get_mut(&peer_id)
+        {
+            *election_wins += 1;
+            *quality_score = (*quality_score + (1.0 - QUALITY_DECAY)).min(1.0);
+            *last_win_at = Some(time);
+        }
+    }
+
+    /// Enforce the hard `connected_max_capacity` limit
+    /// Demotes the farthest non-protected Connected peers until at or below the cap
+    fn enforce_connected_capacity(&mut self, time: EcTime) {
+        let excess = self
+            .num_connected()
+            .saturating_sub(self.config.connected_max_capacity);
+        if excess == 0 {
+            return;
+        }
+
+        let mut candidates: Vec<(u64, PeerId)> = self
+            .peers
+            .iter()
+            .filter_map(|(peer_id, peer)| {
+                if let PeerState::Connected {
+                    connected_since, ..
+                } = peer.state
+                {
+                    // Protect recently connected peers
+                    if time.saturating_sub(connected_since) < self.config.prune_protection_time {
+                        return None;
+                    }
+                    Some((Self::ring_distance(self.peer_id, *peer_id), *peer_id))
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        // Farthest first (peer id breaks ties deterministically)
+        candidates.sort_unstable_by(|a, b| b.cmp(a));
+
+        for (_, peer_id) in candidates.into_iter().take(excess) {
+            self.demote_from_connected(peer_id, time);
+        }
+    }
+
+    fn connected_target_bounds(&self) -> Option<(usize, usize, usize)> {
+        let target = self.config.connected_target?;
+        let hysteresis = self.config.connected_target_hysteresis;
+        Some((
+            target.saturating_sub(hysteresis),
+            target,
+            target.saturating_add(hysteresis),
+        ))
+    }
+
+    fn small_world_target_bounds(&self) -> Option<(usize, usize)> {
+        let small_world = self.config.small_world.as_ref()?;
+        Some((
+            small_world.peer_budget,
+            small_world
+                .peer_budget
+                .saturating_add(small_world.hysteresis),
+        ))
+    }
+
+    fn shape_target_bounds(&self) -> Option<(usize, usize)> {
+        let shape = self.config.shape_target.as_ref()?;
+        let target = self.shape_expected_connected_count().round() as usize;
+        Some((target, target.saturating_add(shape.hysteresis)))
+    }
+
+    fn is_above_connected_target(&self) -> bool {
         if let Some((_, high)) = self.small_world_target_bounds() {
             return self.active.len() > high;
         }
@@ -2253,9 +2894,7 @@ impl EcPeers {
             return None;
         }
 
-        let distance = Self::ring_distance(self.peer_id, peer_id) as f64;
-        let ring_size = u64::MAX as f64 / 2.0;
-        let distance_fraction = (distance / ring_size).clamp(0.0, 1.0);
+        let distance_fraction = self.distance_fraction(peer_id);
         let (core_limit, fade_limit) = self.target_gradient_limits();
         let rank = self.known_distance_rank(peer_id);
 
@@ -2459,9 +3098,7 @@ impl EcPeers {
             return (1.0 - distance_fraction).powf(exponent).clamp(0.02, 1.0);
         }
 
-        let ring_size = u64::MAX as f64 / 2.0;
-        let distance = Self::ring_distance(self.peer_id, sender_peer_id) as f64;
-        let distance_fraction = (distance / ring_size).clamp(0.0, 1.0);
+        let distance_fraction = self.distance_fraction(sender_peer_id);
         let base_accept = (1.0 - distance_fraction).clamp(0.0, 1.0);
 
         if let Some(shape_probability) =
@@ -2631,6 +3268,8 @@ impl EcPeers {
     // ========================================================================
 
     /// Create a new peer manager with default configuration and random seed
+    ///
+    /// Not reproducible; use `with_config_and_rng` for simulations and tests.
     pub fn new(peer_id: PeerId) -> Self {
         use rand::{RngCore, SeedableRng};
         let mut seed = [0u8; 32];
@@ -2640,6 +3279,8 @@ impl EcPeers {
     }
 
     /// Create a new peer manager with custom configuration and random seed
+    ///
+    /// Not reproducible; use `with_config_and_rng` for simulations and tests.
     pub fn with_config(peer_id: PeerId, config: PeerManagerConfig) -> Self {
         use rand::{RngCore, SeedableRng};
         let mut seed = [0u8; 32];
@@ -2649,13 +3290,21 @@ impl EcPeers {
     }
 
     /// Create a new peer manager with custom configuration and specific RNG
+    ///
+    /// This is the reproducible entry point: all random choices (elections,
+    /// pruning, eviction, peer exchange) draw from `rng`, so two managers built
+    /// from the same seed and fed the same inputs make the same decisions.
     pub fn with_config_and_rng(
         peer_id: PeerId,
         config: PeerManagerConfig,
         rng: rand::rngs::StdRng,
     ) -> Self {
-        let proof_system = ProofOfStorage::new();
-        let token_samples = TokenSampleCollection::new(config.token_sample_max_capacity);
+        let proof_system = ProofOfStorage::with_params_and_max_steps(
+            config.election_config.signature_params,
+            config.signature_search_max_steps,
+        );
+        let token_samples = TokenSampleCollection::new(config.token_sample_max_capacity)
+            .with_bias(peer_id, config.sampling_bias);
 
         Self {
             peer_id,
@@ -2671,9 +3320,44 @@ impl EcPeers {
             elections_completed_total: 0,
             elections_timeout_total: 0,
             elections_splitbrain_total: 0,
+            elections_splitbrain_resolved_total: 0,
+            event_sink: Box::new(NoOpSink),
+            banned: HashSet::new(),
+            last_peer_exchange_at: None,
+            keepalive_cursor: 0,
+            elections_paused_until: None,
         }
     }
 
+    /// Replace the RNG driving all random peer decisions
+    ///
+    /// Lets tests make a manager built with `new` or `with_config` reproducible.
+    pub fn reseed(&mut self, rng: rand::rngs::StdRng) {
+        self.rng = rng;
+    }
+
+    /// Suppress tick-triggered elections until `until` (exclusive)
+    ///
+    /// Ongoing elections still complete and invitation-triggered elections
+    /// still start; only Phase 6 of `tick` is skipped.
+    pub fn pause_elections_until(&mut self, until: EcTime) {
+        self.elections_paused_until = Some(until);
+    }
+
+    /// Replace the event sink receiving peer lifecycle and election events
+    pub fn set_event_sink(&mut self, event_sink: Box<dyn EventSink>) {
+        self.event_sink = event_sink;
+    }
+
+    fn emit(&mut self, time: EcTime, event: Event) {
+        self.event_sink.log(time, self.peer_id, event);
+    }
+
+    /// Configuration this peer manager was created with
+    pub fn config(&self) -> &PeerManagerConfig {
+        &self.config
+    }
+
     /// Get number of peers (backward compatibility)
     pub fn num_peers(&self) -> usize {
         self.active.len()
@@ -2700,6 +3384,15 @@ impl EcPeers {
         self.peers.values().filter(|p| p.state.is_pending()).count()
     }
 
+    /// Ids of all peers currently in `kind`, in ascending (ring) order
+    pub fn peers_in_state(&self, kind: PeerStateKind) -> Vec<PeerId> {
+        self.peers
+            .iter()
+            .filter(|(_, peer)| peer.state.kind() == kind)
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
     /// Get total number of active elections
     pub fn num_active_elections(&self) -> usize {
         self.active_elections.len()
@@ -2723,6 +3416,14 @@ impl EcPeers {
         )
     }
 
+    /// Number of elections that hit a split brain and then completed
+    ///
+    /// These are included in the completed count of `get_election_stats`; the
+    /// split-brain count there only covers elections abandoned unresolved.
+    pub fn num_split_brains_resolved(&self) -> usize {
+        self.elections_splitbrain_resolved_total
+    }
+
     /// Get the active (Connected) peer IDs in sorted order
     /// Used by simulator for connectivity analysis
     pub fn get_active_peers(&self) -> &[PeerId] {
@@ -2738,6 +3439,17 @@ impl EcPeers {
             .and_then(|peer| peer.commit_chain_head)
     }
 
+    /// Snapshot of a peer's state, for debugging and inspection
+    ///
+    /// Returns None if the peer is unknown (never seen, evicted, or banned).
+    pub fn peer_state(&self, peer_id: &PeerId) -> Option<PeerStateSnapshot> {
+        self.peers.get(peer_id).map(|peer| PeerStateSnapshot {
+            state: peer.state,
+            commit_chain_head: peer.commit_chain_head,
+            addr: peer.addr,
+        })
+    }
+
     /// Check if a peer is in Connected or Pending state
     ///
     /// Returns true if the peer exists and is either Connected or Pending.
@@ -2763,9 +3475,46 @@ impl EcPeers {
         self.token_samples.add_token(token)
     }
 
+    /// Start an election for a chosen token, e.g. to find peers near a known id
+    ///
+    /// Queries go to the closest known peers to `token`, limited by
+    /// `channels_per_election` and the election's `max_channels`. Not affected by
+    /// `pause_elections_until`.
+    ///
+    /// # Returns
+    /// The initial `SendQuery` actions; empty if an election for `token` is
+    /// already active or no peer could be queried (no election is started then)
+    pub fn request_election(&mut self, token: TokenId, time: EcTime) -> Vec<PeerAction> {
+        if self.active_elections.contains_key(&token) {
+            return Vec::new();
+        }
+
+        let has_candidate = self
+            .find_closest_peers(token, self.config.closest_candidates)
+            .iter()
+            .any(|peer_id| *peer_id != self.peer_id && !self.banned.contains(peer_id));
+        if !has_candidate {
+            return Vec::new();
+        }
+
+        self.start_election(token, time)
+    }
+
+    /// Abandon the active election for `token`, e.g. once it is no longer relevant
+    ///
+    /// Drops the election with all its channels, so late Answers and Referrals for
+    /// it are ignored and no further queries are sent. Not counted in
+    /// `get_election_stats`.
+    ///
+    /// # Returns
+    /// Whether an election for `token` was active
+    pub fn cancel_election(&mut self, token: TokenId) -> bool {
+        self.active_elections.remove(&token).is_some()
+    }
+
     /// Update the commit chain head for a peer
     ///
-    /// Called when we receive an Answer message with head_of_chain field.
+    /// Called when we receive an Answer or Keepalive message with head_of_chain field.
     /// Only updates Identified or Connected peers.
     pub fn update_peer_commit_chain_head(&mut self, peer_id: &PeerId, head: CommitBlockId) {
         if let Some(peer) = self.peers.get_mut(peer_id) {
@@ -2773,6 +3522,16 @@ impl EcPeers {
         }
     }
 
+    /// Handle a commit chain head advertisement from `sender`
+    ///
+    /// Records the head for known peers; unlike a keepalive it does not
+    /// refresh liveness or identify unknown senders.
+    pub fn handle_commit_head(&mut self, sender: PeerId, head: CommitBlockId) {
+        if head > 0 {
+            self.update_peer_commit_chain_head(&sender, head);
+        }
+    }
+
     // ========================================================================
     // Election Management (Phase 3)
     // ========================================================================
@@ -2784,11 +3543,13 @@ impl EcPeers {
             return Vec::new(); // Election already running
         }
 
-        // Create new election
-        let election = PeerElection::new(
+        // Create new election (secret drawn from our RNG so seeded runs reproduce tickets)
+        use rand::Rng;
+        let election = PeerElection::with_secret(
             challenge_token,
             self.peer_id,
             self.config.election_config.clone(),
+            self.rng.gen(),
         );
 
         let ongoing = OngoingElection::new(election, time);
@@ -2797,9 +3558,15 @@ impl EcPeers {
 
         // Increment election counter
         self.elections_started_total += 1;
+        self.emit(
+            time,
+            Event::ElectionStarted {
+                token: challenge_token,
+            },
+        );
 
         // Spawn initial channels and return Query actions
-        self.spawn_election_channels(challenge_token, time)
+        self.spawn_election_channels(challenge_token, None, time)
     }
 
     /// Start a new peer election from an invitation (unsolicited Answer)
@@ -2818,13 +3585,15 @@ impl EcPeers {
         }
 
         // Create new election from invitation
-        let election = match PeerElection::from_invitation(
+        use rand::Rng;
+        let election = match PeerElection::from_invitation_with_secret(
             answer,
             signature,
             responder_peer,
             time,
             self.peer_id,
             self.config.election_config.clone(),
+            self.rng.gen(),
         ) {
             Ok(election) => election,
             Err(_) => {
@@ -2839,6 +3608,12 @@ impl EcPeers {
 
         // Increment election counter
         self.elections_started_total += 1;
+        self.emit(
+            time,
+            Event::ElectionStarted {
+                token: challenge_token,
+            },
+        );
 
         // Update last_invitation_election_at for spam prevention
         // If peer doesn't exist, add them to Identified state
@@ -2857,14 +3632,19 @@ impl EcPeers {
         }
 
         // Spawn initial channels and return Query actions
-        self.spawn_election_channels(challenge_token, time)
+        self.spawn_election_channels(challenge_token, None, time)
     }
 
     /// Spawn N channels for an election
     /// Returns PeerActions to send Query messages for the channels
+    ///
+    /// `additional: None` tries the `channels_per_election` closest candidates
+    /// (the initial spawn). `Some(n)` opens up to n new channels, skipping
+    /// candidates that already participate.
     fn spawn_election_channels(
         &mut self,
         challenge_token: TokenId,
+        additional: Option<usize>,
         time: EcTime,
     ) -> Vec<PeerAction> {
         // Check if election exists
@@ -2872,18 +3652,15 @@ impl EcPeers {
             return Vec::new(); // Election not found
         }
 
-        const CHANNELS_PER_ELECTION: usize = 4;
-        const CLOSEST_CANDIDATES: usize = 8;
-
         let mut actions = Vec::new();
         let mut candidates = Vec::new();
 
         // Add closest peers as additional candidates (for DHT-style routing)
-        let closest = self.find_closest_peers(challenge_token, CLOSEST_CANDIDATES);
+        let closest = self.find_closest_peers(challenge_token, self.config.closest_candidates);
 
         // Add closest peers, avoiding duplicates (challenge_token might be in closest list)
         for peer_id in closest {
-            if !candidates.contains(&peer_id) {
+            if !candidates.contains(&peer_id) && !self.banned.contains(&peer_id) {
                 candidates.push(peer_id);
             }
         }
@@ -2893,7 +3670,15 @@ impl EcPeers {
             return Vec::new();
         };
 
-        for first_hop in candidates.iter().take(CHANNELS_PER_ELECTION) {
+        let (tries, limit) = match additional {
+            None => (self.config.channels_per_election, usize::MAX),
+            Some(count) => (usize::MAX, count),
+        };
+        for first_hop in candidates.iter().take(tries) {
+            if actions.len() >= limit {
+                break;
+            }
+
             // Create channel
             match ongoing.election.create_channel(*first_hop, time) {
                 Ok(ticket) => {
@@ -2928,8 +3713,19 @@ impl EcPeers {
         let mut to_remove_timeout: Vec<TokenId> = Vec::new();
         let mut to_remove_splitbrain: Vec<TokenId> = Vec::new();
 
+        // Stop accepting answers on channels that went silent
+        if self.config.channel_timeout > 0 {
+            for ongoing in self.active_elections.values_mut() {
+                ongoing
+                    .election
+                    .expire_stale_channels(time, self.config.channel_timeout);
+            }
+        }
+
         // First pass: collect election results (only read, no mutable calls)
-        let tokens: Vec<TokenId> = self.active_elections.keys().copied().collect();
+        // Sorted so channel spawning consumes the RNG in a reproducible order
+        let mut tokens: Vec<TokenId> = self.active_elections.keys().copied().collect();
+        tokens.sort_unstable();
 
         for token in tokens {
             let Some(ongoing) = self.active_elections.get(&token) else {
@@ -2965,8 +3761,8 @@ impl EcPeers {
                     }
                 }
 
-                WinnerResult::NoConsensus => {
-                    // Not enough responses yet
+                WinnerResult::InsufficientResponses { .. } | WinnerResult::NoAgreement { .. } => {
+                    // Not enough responses or agreement yet
                     if elapsed >= self.config.election_timeout {
                         // Timeout - remove election
                         to_remove_timeout.push(token);
@@ -2977,19 +3773,27 @@ impl EcPeers {
 
         // Second pass: handle winners (needs mutable self)
         for (token, winner) in winners {
+            self.emit(time, Event::ElectionCompleted { token, winner });
             let new_actions = self.handle_election_success(token_storage, token, winner, time);
             actions.extend(new_actions);
         }
 
         // Spawn more channels for split-brain elections
-        for (token, _count) in to_resolve {
-            let spawned = self.spawn_election_channels(token, time);
+        for (token, count) in to_resolve {
+            if let Some(ongoing) = self.active_elections.get_mut(&token) {
+                ongoing.split_brain_seen = true;
+            }
+            let spawned = self.spawn_election_channels(token, Some(count), time);
             actions.extend(spawned);
         }
 
-        // Remove completed elections and update counter
+        // Remove completed elections and update counters
         for token in to_remove_completed {
-            self.active_elections.remove(&token);
+            if let Some(ongoing) = self.active_elections.remove(&token) {
+                if ongoing.split_brain_seen {
+                    self.elections_splitbrain_resolved_total += 1;
+                }
+            }
             self.elections_completed_total += 1;
         }
 
@@ -3003,11 +3807,25 @@ impl EcPeers {
         for token in to_remove_splitbrain {
             self.active_elections.remove(&token);
             self.elections_splitbrain_total += 1;
+            self.emit(time, Event::SplitBrainDetected { token });
         }
 
         actions
     }
 
+    /// Drop elections older than `election_timeout` whatever their state
+    ///
+    /// Backstop for `process_elections`, which only times out elections it
+    /// gets to evaluate (it skips those still inside `min_collection_time`).
+    /// Removals count as timeouts.
+    fn expire_elections(&mut self, time: EcTime) {
+        let timeout = self.config.election_timeout;
+        let before = self.active_elections.len();
+        self.active_elections
+            .retain(|_, ongoing| time.saturating_sub(ongoing.started_at) < timeout);
+        self.elections_timeout_total += before - self.active_elections.len();
+    }
+
     /// Handle successful election - add winner to peer list
     fn handle_election_success(
         &mut self,
@@ -3023,6 +3841,7 @@ impl EcPeers {
             return actions;
         }
 
+        self.record_election_win(winner, time);
         self.promote_to_pending(winner, _token, time);
         // Generate SendInvitation action
         if let Some(sig) =
@@ -3060,19 +3879,37 @@ impl EcPeers {
         // Phase 2: Process ongoing elections
         let election_actions = self.process_elections(token_storage, time);
         actions.extend(election_actions);
+        self.expire_elections(time);
 
         // Phase 3: Evict excess Identified peers (uniform random)
         self.evict_excess_identified();
 
-        // Phase 4: Evict excess TokenSamples (uniform random)
+        // Phase 4: Evict excess TokenSamples (uniform or distance-weighted random)
         self.token_samples.evict_excess(&mut self.rng);
 
         // Phase 5: Prune Connected peers by distance (distance-based probability)
         self.prune_connected_by_distance(time);
 
+        // Phase 5b: Enforce hard Connected capacity (farthest first)
+        self.enforce_connected_capacity(time);
+
         // Phase 6: Trigger new elections (pick and remove tokens, or use random tokens if low)
-        let new_election_actions = self.trigger_multiple_elections(token_storage, time);
-        actions.extend(new_election_actions);
+        // unless paused
+        if self
+            .elections_paused_until
+            .is_none_or(|until| time >= until)
+        {
+            let new_election_actions = self.trigger_multiple_elections(token_storage, time);
+            actions.extend(new_election_actions);
+        }
+
+        // Phase 7: Peer-exchange gossip to a random Connected peer
+        if let Some(action) = self.peer_exchange_action(time) {
+            actions.push(action);
+        }
+
+        // Phase 8: Keepalives to the next Connected peers in rotation
+        actions.extend(self.keepalive_actions());
 
         actions
     }
@@ -3087,6 +3924,7 @@ mod tests {
     use super::*;
     use crate::ec_interface::BlockId;
     use crate::ec_peer_lifecycle_v2::answer_span;
+    use crate::ec_proof_of_storage::SIGNATURE_CHUNKS;
 
     #[test]
     fn test_ring_distance_calculation() {
@@ -3114,6 +3952,27 @@ mod tests {
         assert!(dist >= half - 1 && dist <= half + 1);
     }
 
+    #[test]
+    fn test_successors_and_predecessors_follow_ring_order() {
+        let mut peers = EcPeers::new(1);
+        for id in [10, 20, 30, 40] {
+            peers.update_peer(&id, 0);
+        }
+
+        assert_eq!(peers.find_successors(25, 3), vec![30, 40, 10]);
+        assert_eq!(peers.find_successors(20, 2), vec![20, 30]);
+        assert_eq!(peers.find_successors(45, 2), vec![10, 20]);
+        assert_eq!(peers.find_successors(25, 10), vec![30, 40, 10, 20]);
+
+        assert_eq!(peers.find_predecessors(25, 3), vec![20, 10, 40]);
+        assert_eq!(peers.find_predecessors(5, 2), vec![40, 30]);
+        assert_eq!(peers.find_predecessors(20, 4), vec![10, 40, 30, 20]);
+
+        // Closest wraps across the ends of the ring too
+        assert_eq!(peers.find_closest_peers(u64::MAX - 5, 2), vec![10, 20]);
+        assert_eq!(peers.find_closest_peers(26, 10), vec![30, 20, 40, 10]);
+    }
+
     #[test]
     fn test_invitation_acceptance_probability() {
         // Test that acceptance probability decreases with distance
@@ -3258,6 +4117,49 @@ mod tests {
         assert_eq!(collection.samples.len(), 5);
     }
 
+    #[test]
+    fn test_token_sample_biased_eviction_keeps_tokens_near_peer_id() {
+        use rand::SeedableRng;
+        let peer_id: PeerId = 1 << 62;
+        let mean_distance = |bias: f64| {
+            let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+            let mut collection = TokenSampleCollection::new(100).with_bias(peer_id, bias);
+            for i in 0..1000u64 {
+                collection.samples.insert(i.wrapping_mul(u64::MAX / 1000));
+            }
+
+            assert_eq!(collection.evict_excess(&mut rng), 900);
+            let total: f64 = collection
+                .samples
+                .iter()
+                .map(|&token| ring_distance_generic(peer_id, token) as f64)
+                .sum();
+            total / collection.samples.len() as f64
+        };
+
+        // Uniform eviction keeps the input spread: about a quarter of the ring on average
+        let uniform = mean_distance(0.0);
+        let biased = mean_distance(4.0);
+        assert!(
+            uniform > (1u64 << 61) as f64 * 0.8,
+            "uniform mean {uniform}"
+        );
+        assert!(
+            biased < uniform / 2.0,
+            "biased mean {biased} vs uniform {uniform}"
+        );
+    }
+
+    #[test]
+    fn test_token_sample_biased_collection_accepts_past_capacity() {
+        let mut collection = TokenSampleCollection::new(5).with_bias(0, 1.0);
+        for i in 0..10 {
+            assert!(collection.add_token(i));
+        }
+        assert!(!collection.add_token(100));
+        assert_eq!(collection.samples.len(), 10);
+    }
+
     #[test]
     fn test_token_sample_from_answer() {
         let mut collection = TokenSampleCollection::new(100);
@@ -3321,6 +4223,7 @@ mod tests {
             election_wins: 5,
             election_attempts: 10,
             quality_score: 0.8,
+            last_win_at: Some(0),
         };
         assert!(!connected.is_identified());
         assert!(!connected.is_pending());
@@ -3398,6 +4301,26 @@ mod tests {
         assert_eq!(targets.iter().copied().collect::<HashSet<_>>().len(), 3);
     }
 
+    #[test]
+    fn test_stable_routing_ignores_time() {
+        let mut peers = EcPeers::new(55);
+        for peer_id in [10, 20, 30, 40, 50, 60, 70, 80, 90, 100, 110, 120] {
+            peers.update_peer(&peer_id, 0);
+        }
+
+        for key in [5, 33, 65, 118] {
+            let (pair, single) = (peers.peers_for_stable(&key), peers.peer_for_stable(&key));
+            let mut rotated = HashSet::new();
+            for time in 0..20 {
+                // The time-mixed version rotates through the neighborhood meanwhile
+                rotated.insert(peers.peer_for(&key, time));
+                assert_eq!(peers.peers_for_stable(&key), pair);
+                assert_eq!(peers.peer_for_stable(&key), single);
+            }
+            assert!(rotated.len() > 1);
+        }
+    }
+
     #[test]
     fn test_locality_aware_invitation_acceptance_prefers_core_over_far_when_over_target() {
         use rand::SeedableRng;
@@ -3583,11 +4506,11 @@ mod tests {
             crate::ec_proof_of_storage::extract_signature_chunks_from_256bit_hash(hash.as_bytes());
 
         let mut signature = [TokenMapping { id: 0, block: 0 }; TOKENS_SIGNATURE_SIZE];
-        for i in 0..TOKENS_SIGNATURE_SIZE {
+        for i in 0..SIGNATURE_CHUNKS {
             let base = if i < 5 {
                 high_base + (i as TokenId * 2_048)
             } else {
-                low_base + ((TOKENS_SIGNATURE_SIZE - 1 - i) as TokenId * 2_048)
+                low_base + ((SIGNATURE_CHUNKS - 1 - i) as TokenId * 2_048)
             };
             signature[i] = TokenMapping {
                 id: (base & !0x3ff) | chunks[i] as TokenId,
@@ -3606,23 +4529,938 @@ mod tests {
 
         fn set(&mut self, _token: &TokenId, _block: &BlockId, _parent: &BlockId, _time: EcTime) {}
 
+        fn remove(&mut self, _token: &TokenId) -> Option<crate::ec_interface::BlockTime> {
+            None
+        }
+
         fn search_signature(
             &self,
             _lookup_token: &TokenId,
-            _signature_chunks: &[u16; crate::ec_proof_of_storage::SIGNATURE_CHUNKS],
+            _signature_chunks: &[u16],
+            _params: &crate::ec_proof_of_storage::SignatureParams,
+            _forward_chunks: usize,
+            _max_steps: usize,
         ) -> crate::ec_proof_of_storage::SignatureSearchResult {
             crate::ec_proof_of_storage::SignatureSearchResult {
                 tokens: Vec::new(),
                 steps: 0,
+                forward_steps: 0,
+                backward_steps: 0,
+                chunks_matched: 0,
                 complete: false,
             }
         }
 
+        fn range_after(
+            &self,
+            _token: &TokenId,
+        ) -> Box<dyn Iterator<Item = (TokenId, crate::ec_interface::BlockTime)> + '_> {
+            Box::new(std::iter::empty())
+        }
+
         fn len(&self) -> usize {
             0
         }
     }
 
+    #[test]
+    fn test_observed_spread_forms_gradient_in_narrow_band() {
+        // Every peer id sits within a million of ours, a sliver of the ring
+        let me: PeerId = 1 << 40;
+        let (near, far) = (me + 10, me - 1_000_000);
+        let build = |distance_normalization| {
+            let config = PeerManagerConfig {
+                distance_normalization,
+                ..Default::default()
+            };
+            let mut peers = EcPeers::with_config(me, config);
+            for peer_id in [near, me + 500_000, me - 250_000, far] {
+                peers.add_identified_peer(peer_id, 0);
+            }
+            peers
+        };
+
+        // Half ring: everyone looks adjacent, so near and far are accepted alike
+        let half_ring = build(DistanceBasis::HalfRing);
+        assert!(half_ring.invitation_acceptance_probability(far) > 0.999);
+        assert!(half_ring.distance_fraction(far) < 1e-6);
+
+        // Observed spread: the farthest known peer is far, but within the margin
+        let observed = build(DistanceBasis::ObservedSpread);
+        assert!(observed.invitation_acceptance_probability(near) > 0.999);
+        let farthest = observed.invitation_acceptance_probability(far);
+        assert!((farthest - 0.2).abs() < 1e-6, "farthest {farthest}");
+        let mid = observed.invitation_acceptance_probability(me + 500_000);
+        assert!((mid - 0.6).abs() < 1e-6, "mid {mid}");
+
+        // Only invitations well beyond every known peer are always declined
+        assert_eq!(
+            observed.invitation_acceptance_probability(me - 1_300_000),
+            0.0
+        );
+
+        let prune = |peers: &EcPeers, peer_id| {
+            EcPeers::connected_prune_probability(peers.distance_fraction(peer_id), 1.0)
+        };
+        assert!(prune(&observed, near) < 0.001);
+        assert!(prune(&observed, far) > prune(&observed, me + 500_000));
+        let neutral_far = EcPeers::connected_prune_probability(
+            observed.distance_fraction(far),
+            INITIAL_QUALITY_SCORE,
+        );
+        assert!(
+            (neutral_far - 0.8).abs() < 1e-6,
+            "neutral far {neutral_far}"
+        );
+    }
+
+    #[test]
+    fn test_owns_token_checks_local_storage() {
+        let mut peers = EcPeers::new(500);
+        for peer_id in [100, 900] {
+            peers.update_peer(&peer_id, 0);
+        }
+        let mut storage = crate::ec_memory_backend::MemTokens::new();
+        TokenStorageBackend::set(&mut storage, &11, &100, &0, 0);
+
+        assert!(peers.owns_token(&storage, &11));
+        assert!(!peers.owns_token(&storage, &12));
+        assert!(!peers.owns_token(&EmptyTokenStorage, &11));
+
+        // Unowned tokens skip the signature search and are referred
+        match peers.handle_query(&storage, 12, 7, 300, false) {
+            Some(PeerAction::SendReferral {
+                token: 12,
+                ticket: 7,
+                suggested_peers,
+            }) => {
+                let mut suggested = suggested_peers;
+                suggested.sort_unstable();
+                assert_eq!(suggested, [100, 900]);
+            }
+            other => panic!("expected a referral, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_recent_election_winner_does_not_flap() {
+        use rand::SeedableRng;
+
+        let config = PeerManagerConfig {
+            prune_protection_time: 10,
+            prune_contribution_window: 50,
+            ..Default::default()
+        };
+        let rng = rand::rngs::StdRng::seed_from_u64(60);
+        let mut peers = EcPeers::with_config_and_rng(0, config, rng);
+
+        // Both a quarter ring away: prune probability ~0.5 per tick once unprotected
+        let useful: PeerId = u64::MAX / 4;
+        let idle: PeerId = u64::MAX / 4 + 1;
+        peers.update_peer(&useful, 0);
+        peers.update_peer(&idle, 0);
+
+        let mut flaps = [0usize; 2];
+        for time in 1..=1_000 {
+            if time % 25 == 1 {
+                peers.record_election_win(useful, time);
+            }
+            peers.prune_connected_by_distance(time);
+
+            // Demoted peers get re-elected straight away
+            for (flap, peer_id) in flaps.iter_mut().zip([useful, idle]) {
+                if !peers.peers[&peer_id].state.is_connected() {
+                    *flap += 1;
+                    assert!(peers.promote_to_connected(peer_id, time));
+                }
+            }
+        }
+
+        assert_eq!(flaps[0], 0, "useful peer flapped");
+        assert!(flaps[1] > 10, "idle peer flapped only {} times", flaps[1]);
+    }
+
+    #[test]
+    fn test_tick_enforces_connected_max_capacity() {
+        use rand::SeedableRng;
+
+        let rng = rand::rngs::StdRng::seed_from_u64(53);
+        let config = PeerManagerConfig::default();
+        let cap = config.connected_max_capacity;
+        let protection = config.prune_protection_time;
+
+        // Close peers so the probabilistic distance prune almost never fires
+        let mut peers = EcPeers::with_config_and_rng(0, config, rng);
+        let peer_ids: Vec<PeerId> = (1..=300u64).map(|i| i * 1_000).collect();
+        for peer_id in &peer_ids {
+            peers.update_peer(peer_id, 0);
+        }
+        assert_eq!(peers.num_connected(), 300);
+
+        // Recently connected peers are protected from the hard cap
+        for peer_id in &peer_ids {
+            peers.update_peer(peer_id, protection - 1);
+        }
+        peers.tick(&EmptyTokenStorage, protection - 1);
+        assert_eq!(peers.num_connected(), 300);
+
+        for step in 0..3 {
+            let time = protection + step * 10;
+            for peer_id in &peer_ids {
+                peers.update_peer(peer_id, time);
+            }
+            peers.tick(&EmptyTokenStorage, time);
+            assert!(peers.num_connected() <= cap);
+        }
+
+        assert_eq!(peers.num_connected(), cap);
+        // The farthest peers were demoted first
+        assert!(peer_ids[..cap]
+            .iter()
+            .all(|id| peers.peers[id].state.is_connected()));
+        assert!(peer_ids[cap..]
+            .iter()
+            .all(|id| !peers.peers[id].state.is_connected()));
+    }
+
+    #[test]
+    fn test_peer_addr_survives_state_transitions() {
+        let mut peers = EcPeers::new(1);
+        let addr: SocketAddr = "10.0.0.2:7000".parse().unwrap();
+
+        assert!(!peers.set_peer_addr(&2, addr));
+        assert!(peers.add_identified_peer(2, 0));
+        assert_eq!(peers.peer_addr(&2), None);
+        assert!(peers.set_peer_addr(&2, addr));
+
+        assert!(peers.promote_to_pending(2, 99, 10));
+        assert_eq!(peers.peer_addr(&2), Some(addr));
+        assert!(peers.promote_to_connected(2, 20));
+        assert_eq!(peers.peer_addr(&2), Some(addr));
+        assert!(peers.demote_from_connected(2, 30));
+        assert_eq!(peers.peer_addr(&2), Some(addr));
+
+        let seed_addr: SocketAddr = "10.0.0.3:7000".parse().unwrap();
+        peers.add_seed_peer(3, Some(seed_addr), 0);
+        assert!(peers.peers[&3].state.is_connected());
+        assert_eq!(peers.peer_addr(&3), Some(seed_addr));
+    }
+
+    #[test]
+    fn test_peer_state_snapshot_tracks_connection() {
+        let mut peers = EcPeers::new(1);
+        assert_eq!(peers.peer_state(&2), None);
+
+        peers.add_identified_peer(2, 5);
+        assert!(matches!(
+            peers.peer_state(&2).unwrap().state,
+            PeerState::Identified {
+                discovered_at: 5,
+                ..
+            }
+        ));
+
+        assert!(peers.promote_to_pending(2, 99, 10));
+        assert!(peers.promote_to_connected(2, 20));
+        let snapshot = peers.peer_state(&2).unwrap();
+        assert_eq!(snapshot.state.kind(), PeerStateKind::Connected);
+        assert!(matches!(
+            snapshot.state,
+            PeerState::Connected {
+                connected_since: 20,
+                last_keepalive: 20,
+                ..
+            }
+        ));
+        assert_eq!(snapshot.addr, None);
+    }
+
+    #[test]
+    fn test_peer_addr_cleared_on_removal() {
+        let config = PeerManagerConfig {
+            identified_max_capacity: 0,
+            ..PeerManagerConfig::default()
+        };
+        let mut peers = EcPeers::with_config(1, config);
+        let addr: SocketAddr = "10.0.0.2:7000".parse().unwrap();
+
+        peers.add_identified_peer(2, 0);
+        peers.set_peer_addr(&2, addr);
+        peers.evict_excess_identified();
+
+        assert!(!peers.peers.contains_key(&2));
+        assert_eq!(peers.peer_addr(&2), None);
+
+        // Re-adding the peer does not resurrect the old address
+        peers.add_identified_peer(2, 10);
+        assert_eq!(peers.peer_addr(&2), None);
+    }
+
+    struct CaptureSink(std::rc::Rc<std::cell::RefCell<Vec<Event>>>);
+
+    impl EventSink for CaptureSink {
+        fn log(&mut self, _round: EcTime, _peer: PeerId, event: Event) {
+            self.0.borrow_mut().push(event);
+        }
+    }
+
+    #[test]
+    fn test_channels_per_election_limits_initial_queries() {
+        use rand::SeedableRng;
+
+        let config = PeerManagerConfig {
+            channels_per_election: 2,
+            ..Default::default()
+        };
+        let rng = rand::rngs::StdRng::seed_from_u64(55);
+        let mut peers = EcPeers::with_config_and_rng(123, config, rng);
+        for id in [100, 200, 300, 400, 500, 600] {
+            peers.update_peer(&id, 0);
+        }
+
+        let actions = peers.start_election(340, 5);
+        let receivers: Vec<PeerId> = actions
+            .iter()
+            .filter_map(|action| match action {
+                PeerAction::SendQuery { receiver, .. } => Some(*receiver),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(receivers, vec![300, 400]);
+    }
+
+    #[test]
+    fn test_request_election_queries_closest_peers_to_token() {
+        use rand::SeedableRng;
+
+        let rng = rand::rngs::StdRng::seed_from_u64(57);
+        let mut peers = EcPeers::with_config_and_rng(123, PeerManagerConfig::default(), rng);
+
+        // No known peers: nothing to query, so no election is started
+        assert!(peers.request_election(640, 5).is_empty());
+        assert_eq!(peers.num_active_elections(), 0);
+
+        for id in [100, 200, 300, 400, 500, 600, 700, 800, 900] {
+            peers.update_peer(&id, 0);
+        }
+
+        let actions = peers.request_election(640, 5);
+        let mut receivers: Vec<PeerId> = actions
+            .iter()
+            .filter_map(|action| match action {
+                PeerAction::SendQuery {
+                    receiver, token, ..
+                } => {
+                    assert_eq!(*token, 640);
+                    Some(*receiver)
+                }
+                _ => None,
+            })
+            .collect();
+        receivers.sort_unstable();
+        assert_eq!(receivers, vec![500, 600, 700, 800]);
+        assert_eq!(peers.num_active_elections(), 1);
+
+        // Already running: no new queries
+        assert!(peers.request_election(640, 6).is_empty());
+    }
+
+    #[test]
+    fn test_cancel_election_stops_queries_for_token() {
+        use rand::SeedableRng;
+
+        let rng = rand::rngs::StdRng::seed_from_u64(58);
+        let mut peers = EcPeers::with_config_and_rng(123, PeerManagerConfig::default(), rng);
+        for id in [100, 200, 300, 400, 500, 600, 700, 800, 900] {
+            peers.update_peer(&id, 0);
+        }
+        peers.pause_elections_until(EcTime::MAX);
+
+        let actions = peers.request_election(640, 5);
+        let Some(PeerAction::SendQuery {
+            receiver, ticket, ..
+        }) = actions.first().cloned()
+        else {
+            panic!("expected an initial query");
+        };
+        assert_eq!(peers.num_active_elections(), 1);
+
+        assert!(peers.cancel_election(640));
+        assert_eq!(peers.num_active_elections(), 0);
+        assert!(!peers.cancel_election(640));
+
+        // A late referral no longer spawns a channel, and ticks send nothing for it
+        assert!(peers
+            .handle_referral(ticket, 640, [300, 900], receiver, 6)
+            .is_none());
+        let actions = peers.tick(&EmptyTokenStorage, 7);
+        assert!(!actions
+            .iter()
+            .any(|action| matches!(action, PeerAction::SendQuery { token: 640, .. })));
+    }
+
+    #[test]
+    fn test_silent_channels_expire_after_channel_timeout() {
+        use rand::SeedableRng;
+
+        let config = PeerManagerConfig {
+            channel_timeout: 10,
+            ..Default::default()
+        };
+        let rng = rand::rngs::StdRng::seed_from_u64(60);
+        let mut peers = EcPeers::with_config_and_rng(123, config, rng);
+        for id in [100, 200, 300, 400, 500, 600, 700, 800, 900] {
+            peers.update_peer(&id, 0);
+        }
+        peers.pause_elections_until(EcTime::MAX);
+
+        let queries: Vec<(PeerId, MessageTicket)> = peers
+            .request_election(640, 5)
+            .into_iter()
+            .filter_map(|action| match action {
+                PeerAction::SendQuery {
+                    receiver, ticket, ..
+                } => Some((receiver, ticket)),
+                _ => None,
+            })
+            .collect();
+        let [(early_sender, early), (late_sender, late), ..] = queries[..] else {
+            panic!("expected at least two queries, got {:?}", queries);
+        };
+
+        // Still within the timeout: the referral is followed up
+        peers.tick(&EmptyTokenStorage, 14);
+        assert!(peers
+            .handle_referral(early, 640, [300, 900], early_sender, 14)
+            .is_some());
+
+        // Processing the election at the timeout expires the silent channel
+        peers.tick(&EmptyTokenStorage, 15);
+        assert_eq!(peers.num_active_elections(), 1);
+        assert!(peers
+            .handle_referral(late, 640, [300, 900], late_sender, 15)
+            .is_none());
+    }
+
+    #[test]
+    fn test_referral_follow_ups_reproducible_with_same_seed() {
+        use rand::SeedableRng;
+
+        // Every query of a seeded election is referred to the same pair; the
+        // suggestion followed up must not depend on anything but the seed
+        let follow_ups = || {
+            let rng = rand::rngs::StdRng::seed_from_u64(59);
+            let mut peers = EcPeers::with_config_and_rng(123, PeerManagerConfig::default(), rng);
+            for id in [100, 200, 300, 400, 500, 600, 700, 800, 900, 1_000, 1_100] {
+                peers.update_peer(&id, 0);
+            }
+            let queries: Vec<(PeerId, MessageTicket)> = peers
+                .request_election(640, 5)
+                .into_iter()
+                .filter_map(|action| match action {
+                    PeerAction::SendQuery {
+                        receiver, ticket, ..
+                    } => Some((receiver, ticket)),
+                    _ => None,
+                })
+                .collect();
+            assert!(!queries.is_empty());
+
+            queries
+                .into_iter()
+                .map(|(receiver, ticket)| {
+                    match peers.handle_referral(ticket, 640, [1_000, 1_100], receiver, 6) {
+                        Some(PeerAction::SendQuery { receiver, .. }) => Some(receiver),
+                        _ => None,
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let first = follow_ups();
+        assert!(first.iter().any(Option::is_some));
+        assert_eq!(first, follow_ups());
+    }
+
+    #[test]
+    fn test_unanswered_election_expires_after_election_timeout() {
+        use rand::SeedableRng;
+
+        // Collection window longer than the timeout: process_elections never evaluates it
+        let config = PeerManagerConfig {
+            min_collection_time: 50,
+            election_timeout: 30,
+            ..Default::default()
+        };
+        let rng = rand::rngs::StdRng::seed_from_u64(56);
+        let mut peers = EcPeers::with_config_and_rng(123, config, rng);
+        for id in [100, 200, 300] {
+            peers.update_peer(&id, 0);
+        }
+        let storage = EmptyTokenStorage;
+        peers.pause_elections_until(EcTime::MAX);
+
+        assert!(!peers.start_election(250, 5).is_empty());
+        peers.tick(&storage, 34);
+        assert_eq!(peers.num_active_elections(), 1);
+
+        peers.tick(&storage, 35);
+        assert_eq!(peers.num_active_elections(), 0);
+        let (_, _, timeouts, _) = peers.get_election_stats();
+        assert_eq!(timeouts, 1);
+    }
+
+    #[test]
+    fn test_keepalives_hold_quiet_link_past_connection_timeout() {
+        use rand::SeedableRng;
+
+        let run = |keepalives_per_tick: usize| {
+            let config = PeerManagerConfig {
+                keepalives_per_tick,
+                ..Default::default()
+            };
+            let mut a = EcPeers::with_config_and_rng(
+                100,
+                config.clone(),
+                rand::rngs::StdRng::seed_from_u64(57),
+            );
+            let mut b =
+                EcPeers::with_config_and_rng(200, config, rand::rngs::StdRng::seed_from_u64(58));
+            a.update_peer(&200, 0);
+            b.update_peer(&100, 0);
+            a.pause_elections_until(EcTime::MAX);
+            b.pause_elections_until(EcTime::MAX);
+
+            // No traffic but what the ticks produce
+            let storage = EmptyTokenStorage;
+            let timeout = PeerManagerConfig::default().connection_timeout;
+            for time in 1..=3 * timeout {
+                for action in a.tick(&storage, time) {
+                    if let PeerAction::SendKeepalive { receiver: 200 } = action {
+                        b.handle_keepalive(100, time);
+                    }
+                }
+                for action in b.tick(&storage, time) {
+                    if let PeerAction::SendKeepalive { receiver: 100 } = action {
+                        a.handle_keepalive(200, time);
+                    }
+                }
+            }
+            a.is_active(&200) && b.is_active(&100)
+        };
+
+        assert!(run(1));
+        assert!(!run(0));
+    }
+
+    #[test]
+    fn test_peers_in_state_lists_ids_per_state() {
+        let mut peers = EcPeers::new(1);
+        for id in [40, 10, 70] {
+            peers.add_seed_peer(id, None, 0);
+        }
+        for id in [20, 30, 50, 60] {
+            peers.add_identified_peer(id, 0);
+        }
+        assert!(peers.promote_to_pending(50, 99, 0));
+        assert!(peers.promote_to_pending(20, 99, 0));
+
+        let connected = peers.peers_in_state(PeerStateKind::Connected);
+        let pending = peers.peers_in_state(PeerStateKind::Pending);
+        let identified = peers.peers_in_state(PeerStateKind::Identified);
+        assert_eq!(connected, vec![10, 40, 70]);
+        assert_eq!(pending, vec![20, 50]);
+        assert_eq!(identified, vec![30, 60]);
+        assert_eq!(connected.len(), peers.num_connected());
+        assert_eq!(pending.len(), peers.num_pending());
+        assert_eq!(identified.len(), peers.num_identified());
+    }
+
+    #[test]
+    fn test_keepalive_transition_per_sender_state() {
+        let mut peers = EcPeers::new(1);
+        peers.update_peer(&10, 0);
+        peers.add_identified_peer(20, 0);
+        peers.add_identified_peer(30, 0);
+        assert!(peers.promote_to_pending(30, 99, 0));
+        peers.ban_peer(40, 0);
+
+        // Connected: keepalive refreshed, survives past its original deadline
+        let timeout = peers.config.connection_timeout;
+        peers.handle_keepalive(10, timeout - 1);
+        peers.detect_connection_timeouts(timeout);
+        assert!(peers.peers[&10].state.is_connected());
+
+        // Identified: unchanged
+        peers.handle_keepalive(20, 5);
+        assert!(peers.peers[&20].state.is_identified());
+
+        // Pending: promoted
+        peers.handle_keepalive(30, 5);
+        assert!(peers.peers[&30].state.is_connected());
+
+        // Unknown: identified; banned: ignored
+        peers.handle_keepalive(50, 5);
+        assert!(peers.peers[&50].state.is_identified());
+        peers.handle_keepalive(40, 5);
+        assert!(!peers.peers.contains_key(&40));
+    }
+
+    #[test]
+    fn test_timestamps_ahead_of_time_do_not_underflow() {
+        let mut peers = EcPeers::new(1);
+        peers.update_peer(&10, 1000);
+        peers.add_identified_peer(20, 1000);
+        assert!(peers.promote_to_pending(20, 99, 1000));
+
+        // Time behind the stored timestamps (clock skew): nothing is overdue
+        peers.tick(&EmptyTokenStorage, 500);
+        assert!(peers.peers[&10].state.is_connected());
+        assert!(peers.peers[&20].state.is_pending());
+
+        // Timeouts still count from the stored timestamps
+        let timeout = peers
+            .config
+            .connection_timeout
+            .max(peers.config.pending_timeout);
+        peers.tick(&EmptyTokenStorage, 1000 + timeout);
+        assert!(!peers.peers[&10].state.is_connected());
+        assert!(!peers.peers[&20].state.is_pending());
+    }
+
+    #[test]
+    fn test_unknown_keepalive_sender_respects_identified_capacity() {
+        let config = PeerManagerConfig {
+            identified_max_capacity: 1,
+            ..Default::default()
+        };
+        let mut peers = EcPeers::with_config(1, config);
+        peers.handle_keepalive(10, 0);
+        peers.handle_keepalive(20, 0);
+        assert_eq!(peers.num_identified(), 1);
+        assert!(!peers.peers.contains_key(&20));
+    }
+
+    #[test]
+    fn test_lifecycle_events_emitted() {
+        use rand::SeedableRng;
+
+        let rng = rand::rngs::StdRng::seed_from_u64(61);
+        let mut peers = EcPeers::with_config_and_rng(123, PeerManagerConfig::default(), rng);
+        let events = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        peers.set_event_sink(Box::new(CaptureSink(events.clone())));
+
+        peers.update_peer(&200, 0);
+        peers.update_peer(&300, 0);
+        peers.add_identified_peer(400, 0);
+        peers.promote_to_connected(400, 1);
+        peers.demote_from_connected(400, 2);
+
+        let token: TokenId = 7;
+        let actions = peers.start_election(token, 5);
+        let tickets: Vec<(PeerId, MessageTicket)> = actions
+            .iter()
+            .filter_map(|action| match action {
+                PeerAction::SendQuery {
+                    receiver, ticket, ..
+                } => Some((*receiver, *ticket)),
+                _ => None,
+            })
+            .collect();
+        assert!(tickets.len() >= 2);
+
+        // All responders agree on the same signature -> single winner
+        let answer = TokenMapping {
+            id: token,
+            block: 99,
+        };
+        let signature = synthetic_signature(answer.id, answer.block, 123, 1 << 10, 10 << 10);
+        for (receiver, ticket) in &tickets {
+            peers.handle_answer(
+                &answer,
+                &signature,
+                *ticket,
+                *receiver,
+                6,
+                &EmptyTokenStorage,
+                0,
+            );
+        }
+        peers.process_elections(&EmptyTokenStorage, 20);
+
+        let events = events.borrow();
+        assert!(matches!(events[0], Event::PeerConnected { peer_id: 200 }));
+        assert!(matches!(events[1], Event::PeerConnected { peer_id: 300 }));
+        assert!(matches!(events[2], Event::PeerConnected { peer_id: 400 }));
+        assert!(matches!(events[3], Event::PeerDemoted { peer_id: 400 }));
+        assert!(matches!(events[4], Event::ElectionStarted { token: 7 }));
+        assert!(events.iter().any(|event| matches!(
+            event,
+            Event::ElectionCompleted { token: 7, winner }
+                if tickets.iter().any(|(receiver, _)| receiver == winner)
+        )));
+    }
+
+    #[test]
+    fn test_split_brain_resolves_once_extra_channels_tip_the_balance() {
+        use crate::ec_proof_of_storage::WinnerResult;
+        use rand::SeedableRng;
+
+        let rng = rand::rngs::StdRng::seed_from_u64(62);
+        let mut peers = EcPeers::with_config_and_rng(123, PeerManagerConfig::default(), rng);
+        for peer_id in [100, 200, 300, 400, 500, 600, 700, 800] {
+            peers.update_peer(&peer_id, 0);
+        }
+        let token: TokenId = 450;
+        let queried = |actions: &[PeerAction]| -> Vec<(PeerId, MessageTicket)> {
+            actions
+                .iter()
+                .filter_map(|action| match action {
+                    PeerAction::SendQuery {
+                        receiver, ticket, ..
+                    } => Some((*receiver, *ticket)),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        // Two responders vouch for block 98, two for block 99
+        let sides = [98, 99].map(|block| {
+            let answer = TokenMapping { id: token, block };
+            let signature = synthetic_signature(token, block, 123, 1 << 10, 10 << 10);
+            (answer, signature)
+        });
+        let answer = |peers: &mut EcPeers, side: usize, (receiver, ticket), time| {
+            let (answer, signature) = &sides[side];
+            peers.handle_answer(
+                answer,
+                signature,
+                ticket,
+                receiver,
+                time,
+                &EmptyTokenStorage,
+                0,
+            );
+        };
+
+        let first = queried(&peers.request_election(token, 5));
+        assert_eq!(first.len(), 4);
+        for (i, query) in first.into_iter().enumerate() {
+            answer(&mut peers, i % 2, query, 6);
+        }
+        let election = |peers: &EcPeers| peers.active_elections[&token].election.check_for_winner();
+        assert!(matches!(election(&peers), WinnerResult::SplitBrain { .. }));
+
+        // Split brain: two new peers get queried and both side with block 99
+        let extra = queried(&peers.process_elections(&EmptyTokenStorage, 15));
+        assert_eq!(extra.len(), 2);
+        assert!(extra
+            .iter()
+            .all(|(receiver, _)| [200, 700].contains(receiver)));
+        for query in extra {
+            answer(&mut peers, 1, query, 16);
+        }
+        assert!(matches!(election(&peers), WinnerResult::Single { .. }));
+
+        peers.process_elections(&EmptyTokenStorage, 20);
+        assert_eq!(peers.num_active_elections(), 0);
+        assert_eq!(peers.get_election_stats(), (1, 1, 0, 0));
+        assert_eq!(peers.num_split_brains_resolved(), 1);
+    }
+
+    #[test]
+    fn test_banned_peer_not_readded_via_referral() {
+        use rand::SeedableRng;
+
+        let rng = rand::rngs::StdRng::seed_from_u64(67);
+        let mut peers = EcPeers::with_config_and_rng(123, PeerManagerConfig::default(), rng);
+        for peer_id in [200, 300, 400, 500] {
+            peers.update_peer(&peer_id, 0);
+        }
+
+        // Banning a Connected peer demotes and forgets it
+        assert!(peers.ban_peer(500, 1));
+        assert!(!peers.ban_peer(500, 1));
+        assert!(!peers.peers.contains_key(&500));
+        assert!(!peers.get_active_peers().contains(&500));
+        peers.update_peer(&500, 2);
+        assert!(!peers.add_identified_peer(500, 2));
+        assert!(!peers.peers.contains_key(&500));
+
+        let token: TokenId = 7;
+        let tickets: Vec<(PeerId, MessageTicket)> = peers
+            .start_election(token, 5)
+            .iter()
+            .filter_map(|action| match action {
+                PeerAction::SendQuery {
+                    receiver, ticket, ..
+                } => Some((*receiver, *ticket)),
+                _ => None,
+            })
+            .collect();
+        assert!(tickets.len() >= 2);
+        assert!(tickets.iter().all(|(receiver, _)| *receiver != 500));
+
+        // Referral suggesting the banned peer is ignored
+        let (sender, ticket) = tickets[0];
+        assert!(peers
+            .handle_referral(ticket, token, [500, 500], sender, 6)
+            .is_none());
+        assert!(!peers.peers.contains_key(&500));
+
+        // Unbanning restores eligibility
+        assert!(peers.unban_peer(500));
+        assert!(!peers.is_banned(&500));
+        let (sender, ticket) = tickets[1];
+        let action = peers.handle_referral(ticket, token, [500, 500], sender, 7);
+        assert!(matches!(
+            action,
+            Some(PeerAction::SendQuery { receiver: 500, .. })
+        ));
+        assert!(peers.peers.contains_key(&500));
+    }
+
+    #[test]
+    fn test_quality_score_tracks_election_wins() {
+        let mut peers = EcPeers::new(0);
+        let winner: PeerId = u64::MAX / 4;
+        let loser: PeerId = u64::MAX / 4 + 1;
+        peers.update_peer(&winner, 0);
+        peers.update_peer(&loser, 0);
+
+        let state_of = |peers: &EcPeers, peer_id: PeerId| match peers.peers[&peer_id].state {
+            PeerState::Connected {
+                election_wins,
+                election_attempts,
+                quality_score,
+                ..
+            } => (election_wins, election_attempts, quality_score),
+            _ => panic!("peer should stay Connected"),
+        };
+        let prune_prob = |peers: &EcPeers, peer_id: PeerId| {
+            let distance_fraction = peers.distance_fraction(peer_id);
+            EcPeers::connected_prune_probability(distance_fraction, state_of(peers, peer_id).2)
+        };
+
+        let initial_prob = prune_prob(&peers, winner);
+        assert_eq!(initial_prob, prune_prob(&peers, loser));
+
+        let mut last_quality = state_of(&peers, winner).2;
+        for round in 0..10 {
+            // Both answer, only `winner` is elected
+            peers.record_election_attempt(winner);
+            peers.record_election_attempt(loser);
+            peers.handle_election_success(&EmptyTokenStorage, round, winner, round);
+
+            let quality = state_of(&peers, winner).2;
+            assert!(quality > last_quality);
+            last_quality = quality;
+        }
+
+        assert_eq!(state_of(&peers, winner), (10, 10, last_quality));
+        assert_eq!(state_of(&peers, loser).0, 0);
+        assert_eq!(state_of(&peers, loser).1, 10);
+        assert!(state_of(&peers, loser).2 < INITIAL_QUALITY_SCORE);
+
+        assert!(prune_prob(&peers, winner) < initial_prob);
+        assert!(prune_prob(&peers, loser) > initial_prob);
+    }
+
+    #[test]
+    fn test_identically_seeded_managers_make_identical_decisions() {
+        use rand::{Rng, SeedableRng};
+
+        let config = PeerManagerConfig {
+            connected_max_capacity: 8,
+            peer_exchange_interval: 1,
+            ..PeerManagerConfig::default()
+        };
+        let peer_id: PeerId = 1 << 63;
+        let mut a = EcPeers::with_config_and_rng(
+            peer_id,
+            config.clone(),
+            rand::rngs::StdRng::seed_from_u64(46),
+        );
+        // Built with a random seed, then made reproducible
+        let mut b = EcPeers::with_config(peer_id, config);
+        b.reseed(rand::rngs::StdRng::seed_from_u64(46));
+
+        let mut seed_rng = rand::rngs::StdRng::seed_from_u64(460);
+        let known: Vec<PeerId> = (0..40).map(|_| seed_rng.gen()).collect();
+        for peers in [&mut a, &mut b] {
+            for (i, peer) in known.iter().enumerate() {
+                // Half connected, so pruning has something to choose from
+                if i % 2 == 0 {
+                    peers.update_peer(peer, 0);
+                } else {
+                    peers.add_identified_peer(*peer, 0);
+                }
+            }
+        }
+
+        for time in 1..=20 {
+            let actions_a = a.tick(&EmptyTokenStorage, time);
+            let actions_b = b.tick(&EmptyTokenStorage, time);
+            assert_eq!(format!("{:?}", actions_a), format!("{:?}", actions_b));
+            assert_eq!(
+                a.active, b.active,
+                "connected set diverged at tick {}",
+                time
+            );
+        }
+        assert!(a.elections_started_total > 0);
+        assert_eq!(a.elections_started_total, b.elections_started_total);
+    }
+
+    #[test]
+    fn test_peer_exchange_spreads_neighbors() {
+        use rand::SeedableRng;
+
+        let config = PeerManagerConfig {
+            peer_exchange_interval: 1,
+            ..PeerManagerConfig::default()
+        };
+        let (a_id, b_id): (PeerId, PeerId) = (1_000, 2_000);
+        let mut a = EcPeers::with_config_and_rng(
+            a_id,
+            config.clone(),
+            rand::rngs::StdRng::seed_from_u64(71),
+        );
+        let mut b =
+            EcPeers::with_config_and_rng(b_id, config, rand::rngs::StdRng::seed_from_u64(72));
+        for peer_id in [b_id, 1_001, 1_002] {
+            a.update_peer(&peer_id, 0);
+        }
+        for peer_id in [a_id, 2_001, 2_002] {
+            b.update_peer(&peer_id, 0);
+        }
+        assert!(!a.peers.contains_key(&2_001));
+        assert!(!b.peers.contains_key(&1_001));
+
+        let deliver = |actions: Vec<PeerAction>, from: PeerId, to: &mut EcPeers, time| {
+            for action in actions {
+                if let PeerAction::SendPeerList { receiver, peers } = action {
+                    if receiver == to.peer_id {
+                        to.handle_peer_list(&peers, from, time);
+                    }
+                }
+            }
+        };
+        for time in 1..=10 {
+            let actions = a.tick(&EmptyTokenStorage, time);
+            deliver(actions, a_id, &mut b, time);
+            let actions = b.tick(&EmptyTokenStorage, time);
+            deliver(actions, b_id, &mut a, time);
+        }
+
+        assert!(a.peers.contains_key(&2_001) && a.peers.contains_key(&2_002));
+        assert!(b.peers.contains_key(&1_001) && b.peers.contains_key(&1_002));
+
+        // Lists from unknown senders are ignored
+        assert_eq!(a.handle_peer_list(&[3_001], 3_000, 11), 0);
+        assert!(!a.peers.contains_key(&3_001));
+    }
+
     #[test]
     fn test_density_repair_invite_stops_when_answer_span_is_filled() {
         use rand::SeedableRng;
@@ -3665,6 +5503,30 @@ mod tests {
         assert!(peers.peers.contains_key(&500));
     }
 
+    #[test]
+    fn test_invitation_cooldown_gates_repeat_invitations() {
+        use rand::SeedableRng;
+
+        let config = PeerManagerConfig {
+            invitation_cooldown: 5,
+            ..Default::default()
+        };
+        let rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut peers = EcPeers::with_config_and_rng(123, config, rng);
+        peers.add_identified_peer(500, 0);
+
+        let answer = TokenMapping { id: 7, block: 99 };
+        let signature = synthetic_signature(answer.id, answer.block, 123, 1 << 10, 10 << 10);
+        let invite = |peers: &mut EcPeers, time| {
+            peers.handle_answer(&answer, &signature, 0, 500, time, &EmptyTokenStorage, 0);
+            peers.cancel_election(answer.id)
+        };
+
+        assert!(invite(&mut peers, 100));
+        assert!(!invite(&mut peers, 105), "within the cooldown");
+        assert!(invite(&mut peers, 106));
+    }
+
     #[test]
     fn test_density_repair_discovery_answer_stops_when_answer_span_is_filled() {
         use rand::SeedableRng;
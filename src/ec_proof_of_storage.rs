@@ -13,27 +13,142 @@ use crate::ec_interface::{
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-/// Number of signature chunks (10-bit each = 100 bits total)
-/// This must match TOKENS_SIGNATURE_SIZE from ec_interface
-pub const SIGNATURE_CHUNKS: usize = TOKENS_SIGNATURE_SIZE;
+/// Default number of signature chunks (10-bit each = 100 bits total)
+///
+/// Runtime deployments may choose a different count via `SignatureParams`,
+/// up to TOKENS_SIGNATURE_SIZE from ec_interface.
+pub const SIGNATURE_CHUNKS: usize = 10;
 
-/// Bits per signature chunk
-const CHUNK_BITS: usize = 10;
+/// Default bits per signature chunk
+pub const CHUNK_BITS: usize = 10;
 
 /// Mask for extracting last 10 bits (0x3FF = 1023)
 const CHUNK_MASK: u64 = 0x3FF;
 
+/// Largest supported chunk width (chunks are carried as u16)
+pub const MAX_CHUNK_BITS: usize = 16;
+
+/// Runtime shape of a proof-of-storage signature
+///
+/// A signature is `chunks` values of `bits` bits each, extracted from a
+/// 256-bit Blake3 hash. Every peer in a network must use the same params,
+/// since the verifier recomputes the chunks from its own configuration.
+///
+/// # Constraints
+/// - `1 <= chunks <= TOKENS_SIGNATURE_SIZE`
+/// - `1 <= bits <= MAX_CHUNK_BITS`
+/// - `chunks * bits <= 256`
+///
+/// # Example
+/// ```
+/// use ec_rust::ec_proof_of_storage::SignatureParams;
+///
+/// // Smaller private network: 6 chunks of 8 bits (48-bit signature)
+/// let params = SignatureParams { chunks: 6, bits: 8 };
+/// assert!(params.is_valid());
+/// assert_eq!(params.chunk_mask(), 0xFF);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignatureParams {
+    /// Number of signature chunks (tokens in a complete signature)
+    pub chunks: usize,
+
+    /// Bits per chunk (matched against the low bits of a TokenId)
+    pub bits: usize,
+}
+
+impl Default for SignatureParams {
+    fn default() -> Self {
+        Self {
+            chunks: SIGNATURE_CHUNKS,
+            bits: CHUNK_BITS,
+        }
+    }
+}
+
+impl SignatureParams {
+    /// Check the params fit the signature array and the 256-bit hash
+    pub fn is_valid(&self) -> bool {
+        self.chunks >= 1
+            && self.chunks <= TOKENS_SIGNATURE_SIZE
+            && self.bits >= 1
+            && self.bits <= MAX_CHUNK_BITS
+            && self.chunks * self.bits <= 256
+    }
+
+    /// Default search split: chunks matched above the lookup token
+    ///
+    /// The backward pass covers the remaining `chunks - forward_chunks()`.
+    pub fn forward_chunks(&self) -> usize {
+        self.chunks / 2
+    }
+
+    /// Mask for extracting the last `bits` bits of a token
+    #[inline]
+    pub fn chunk_mask(&self) -> u64 {
+        (1u64 << self.bits) - 1
+    }
+
+    /// Check if a token's last `bits` bits match a signature chunk
+    #[inline]
+    pub fn matches_chunk(&self, token: &TokenId, chunk_value: u16) -> bool {
+        (token & self.chunk_mask()) == chunk_value as u64
+    }
+
+    /// Extract `chunks` chunks of `bits` bits from a 256-bit hash
+    ///
+    /// Chunks are read little-endian from consecutive bit offsets, so the
+    /// default params produce the same output as
+    /// `extract_signature_chunks_from_256bit_hash`.
+    pub fn extract_chunks(&self, hash_bytes: &[u8; 32]) -> Vec<u16> {
+        assert!(self.is_valid(), "Invalid signature params: {:?}", self);
+
+        let mask = self.chunk_mask() as u32;
+        (0..self.chunks)
+            .map(|i| {
+                let bit_offset = i * self.bits;
+                let byte_offset = bit_offset / 8;
+                let bit_in_byte = bit_offset % 8;
+
+                // A chunk of up to 16 bits spans at most three bytes
+                let mut combined = 0u32;
+                for k in 0..3 {
+                    if let Some(&byte) = hash_bytes.get(byte_offset + k) {
+                        combined |= (byte as u32) << (8 * k);
+                    }
+                }
+                ((combined >> bit_in_byte) & mask) as u16
+            })
+            .collect()
+    }
+}
+
 /// Result of a signature-based token search
 #[derive(Debug, Clone)]
 pub struct SignatureSearchResult {
-    /// Tokens found matching the signature (up to 10)
+    /// Tokens found matching the signature (up to the active chunk count)
     pub tokens: Vec<TokenId>,
-    /// Number of search steps taken
+    /// Number of search steps taken (`forward_steps + backward_steps`)
     pub steps: usize,
+    /// Steps spent searching above the lookup token (including the ring wrap)
+    pub forward_steps: usize,
+    /// Steps spent searching below the lookup token (including the ring wrap)
+    pub backward_steps: usize,
+    /// Number of chunks matched, same as `tokens.len()`
+    pub chunks_matched: usize,
     /// Whether all signature chunks were matched
     pub complete: bool,
 }
 
+/// Possibly incomplete proof-of-storage signature (see `generate_partial_signature`)
+#[derive(Debug, Clone)]
+pub struct PartialSignature {
+    /// Signature with zero mappings in the positions no token was found for
+    pub signature: TokenSignature,
+    /// Number of signature chunks backed by a stored token
+    pub found: usize,
+}
+
 /// Backend abstraction for token storage operations
 ///
 /// This trait defines the minimal interface needed for proof-of-storage
@@ -54,11 +169,24 @@ pub trait TokenStorageBackend {
     /// that copying is negligible compared to storage access costs.
     fn lookup(&self, token: &TokenId) -> Option<BlockTime>;
 
+    /// Look up several tokens at once, results in input order
+    ///
+    /// The default loops `lookup`; backends with a batched read (e.g. RocksDB
+    /// `multi_get`) should override it.
+    fn get_many(&self, tokens: &[TokenId]) -> Vec<Option<BlockTime>> {
+        tokens.iter().map(|token| self.lookup(token)).collect()
+    }
+
     /// Set or update a token's block mapping
     ///
     /// For newly created tokens (genesis transactions), use GENESIS_BLOCK_ID as the parent.
     fn set(&mut self, token: &TokenId, block: &BlockId, parent: &BlockId, time: EcTime);
 
+    /// Remove a token's mapping, e.g. once it falls outside our responsibility range
+    ///
+    /// Returns the removed mapping, or None if the token was not stored.
+    fn remove(&mut self, token: &TokenId) -> Option<BlockTime>;
+
     /// Search for tokens matching signature chunks in ring topology
     ///
     /// This method encapsulates the entire signature search algorithm, allowing
@@ -66,26 +194,79 @@ pub trait TokenStorageBackend {
     /// backend (e.g., sorted Vec scan, BTreeMap iteration, RocksDB sequential read).
     ///
     /// # Algorithm
-    /// - Search above (forward from) lookup_token for first 5 signature chunks
-    /// - Search below (backward from) lookup_token for last 5 signature chunks
+    /// - Search above (forward from) lookup_token for the first `forward_chunks` chunks
+    /// - Search below (backward from) lookup_token for the remaining chunks
     /// - Wrap around the ring when reaching end/beginning of token space
-    /// - Match 10-bit suffix of TokenId against signature chunks
+    /// - Match the `params.bits` suffix of TokenId against signature chunks
+    /// - Stop early once `max_steps` tokens have been examined
     ///
     /// # Arguments
     /// - `lookup_token`: Starting point for bidirectional search
-    /// - `signature_chunks`: Array of 10 signature chunks (10-bit values) to match
+    /// - `signature_chunks`: Signature chunks to match (`params.chunks` values)
+    /// - `params`: Signature shape (chunk count and bits per chunk)
+    /// - `forward_chunks`: Chunks matched above lookup_token (`params.forward_chunks()`
+    ///   by default); values past `signature_chunks.len()` search forward only
+    /// - `max_steps`: Budget of tokens to examine (`usize::MAX` for unbounded)
     ///
     /// # Returns
     /// `SignatureSearchResult` containing:
-    /// - `complete`: true if all 10 tokens were found
-    /// - `tokens`: Vec of matching token IDs (up to 10)
-    /// - `steps`: Number of tokens examined during search
+    /// - `complete`: true if a token was found for every chunk
+    /// - `tokens`: Vec of matching token IDs (up to `signature_chunks.len()`)
+    /// - `steps`: Number of tokens examined during search (never above `max_steps`)
     fn search_signature(
         &self,
         lookup_token: &TokenId,
-        signature_chunks: &[u16; SIGNATURE_CHUNKS],
+        signature_chunks: &[u16],
+        params: &SignatureParams,
+        forward_chunks: usize,
+        max_steps: usize,
     ) -> SignatureSearchResult;
 
+    /// Iterate stored mappings strictly above `token` in ascending order (no wrap)
+    fn range_after(&self, token: &TokenId) -> Box<dyn Iterator<Item = (TokenId, BlockTime)> + '_>;
+
+    /// Count stored tokens with `start <= token < end`
+    ///
+    /// The segment follows the ring: when `start > end` it wraps past
+    /// `TokenId::MAX`, and `start == end` is empty. The default walks
+    /// `range_after`; sorted in-memory backends override it.
+    fn count_in_range(&self, start: &TokenId, end: &TokenId) -> usize {
+        // Tokens in [lo, hi), or [lo, MAX] when hi is None
+        let count_segment = |lo: TokenId, hi: Option<TokenId>| {
+            usize::from(self.lookup(&lo).is_some())
+                + self
+                    .range_after(&lo)
+                    .take_while(|(token, _)| hi.is_none_or(|hi| *token < hi))
+                    .count()
+        };
+
+        match start.cmp(end) {
+            std::cmp::Ordering::Equal => 0,
+            std::cmp::Ordering::Less => count_segment(*start, Some(*end)),
+            std::cmp::Ordering::Greater if *end == 0 => count_segment(*start, None),
+            std::cmp::Ordering::Greater => {
+                count_segment(*start, None) + count_segment(0, Some(*end))
+            }
+        }
+    }
+
+    /// Copy every mapping held by `other` into this backend
+    ///
+    /// Each mapping goes through `set`, so its rules for tokens already stored
+    /// here apply. The default walks `other.range_after`, plus token 0 which
+    /// that excludes.
+    fn import_from(&mut self, other: &dyn TokenStorageBackend) {
+        let first = other.lookup(&TokenId::MIN).map(|b| (TokenId::MIN, b));
+        for (token, block_time) in first.into_iter().chain(other.range_after(&TokenId::MIN)) {
+            self.set(
+                &token,
+                &block_time.block,
+                &block_time.parent,
+                block_time.time,
+            );
+        }
+    }
+
     /// Get total number of tokens stored
     fn len(&self) -> usize;
 
@@ -116,7 +297,11 @@ pub trait TokenStorageBackend {
 /// }
 /// ```
 pub struct ProofOfStorage {
-    // Zero-sized type - all methods take storage as parameter
+    // Storage is passed as a parameter to all methods
+    /// Signature shape used for generation and search
+    params: SignatureParams,
+    /// Maximum tokens a single signature search may examine
+    max_steps: usize,
 }
 
 /// Result of consensus cluster analysis
@@ -134,6 +319,38 @@ pub struct ConsensusCluster {
 // Peer Election System: Ring Distance & Ticket Generation
 // ============================================================================
 
+/// An identifier on the ring (token or peer id)
+///
+/// Ring arithmetic only needs wrapping subtraction plus ordering, so wider ids
+/// (e.g. a 256-bit newtype) can reuse `ring_distance_generic` by implementing this.
+pub trait RingId: Copy + Ord {
+    /// Subtract modulo the size of the id space
+    fn wrapping_sub(self, other: Self) -> Self;
+}
+
+impl RingId for u64 {
+    #[inline]
+    fn wrapping_sub(self, other: Self) -> Self {
+        u64::wrapping_sub(self, other)
+    }
+}
+
+/// Calculate ring distance between two IDs of any width
+///
+/// Distance is the minimum of the clockwise and counter-clockwise distances.
+///
+/// # Example
+/// ```
+/// use ec_rust::ec_proof_of_storage::ring_distance_generic;
+///
+/// assert_eq!(ring_distance_generic(10u64, u64::MAX - 5), 16);
+/// ```
+pub fn ring_distance_generic<T: RingId>(a: T, b: T) -> T {
+    let forward = b.wrapping_sub(a);
+    let backward = a.wrapping_sub(b);
+    forward.min(backward)
+}
+
 /// Calculate ring distance between two IDs in circular space
 ///
 /// In a ring topology, distance is the minimum of clockwise and counter-clockwise
@@ -147,8 +364,8 @@ pub struct ConsensusCluster {
 /// Minimum distance between the two IDs (wrapping around ring)
 ///
 /// # Note
-/// Currently works with u64 types. When migrating to 256-bit types, this function
-/// will need to be updated to handle U256 arithmetic with wrapping.
+/// Concrete u64 convenience for `ring_distance_generic`; other id widths implement
+/// `RingId` instead of changing this function.
 ///
 /// # Example
 /// ```
@@ -161,9 +378,7 @@ pub struct ConsensusCluster {
 /// assert_eq!(ring_distance(10, u64::MAX - 5), 16);
 /// ```
 pub fn ring_distance(a: u64, b: u64) -> u64 {
-    let forward = b.wrapping_sub(a);
-    let backward = a.wrapping_sub(b);
-    forward.min(backward)
+    ring_distance_generic(a, b)
 }
 
 /// Generate a secure ticket for an election channel
@@ -201,19 +416,21 @@ fn generate_ticket(
 
 /// Count common mappings between two signatures
 ///
-/// Compares the signature arrays (not the answer field) to find matching
-/// (token_id, block_id) pairs. Order doesn't matter - any mapping in sig1
-/// that appears anywhere in sig2 counts as a match.
+/// Compares the first `chunks` entries of the signature arrays (not the answer
+/// field) to find matching (token_id, block_id) pairs. Order doesn't matter -
+/// any mapping in sig1's active prefix that appears anywhere in sig2's active
+/// prefix counts as a match. Zero-filled slots past `chunks` are ignored.
 ///
 /// Used for consensus clustering in elections and signature comparison.
 ///
 /// # Performance
-/// O(TOKENS_SIGNATURE_SIZE²) = O(100) for 10-element arrays.
+/// O(chunks²) = O(100) for the default 10 chunks.
 /// For small signature arrays this is faster than building hash sets.
-pub fn count_common_mappings(sig1: &TokenSignature, sig2: &TokenSignature) -> usize {
+pub fn count_common_mappings(sig1: &TokenSignature, sig2: &TokenSignature, chunks: usize) -> usize {
+    let chunks = chunks.min(TOKENS_SIGNATURE_SIZE);
     let mut count = 0;
-    for mapping1 in &sig1.signature {
-        for mapping2 in &sig2.signature {
+    for mapping1 in &sig1.signature[..chunks] {
+        for mapping2 in &sig2.signature[..chunks] {
             if mapping1.id == mapping2.id && mapping1.block == mapping2.block {
                 count += 1;
                 break;
@@ -223,11 +440,39 @@ pub fn count_common_mappings(sig1: &TokenSignature, sig2: &TokenSignature) -> us
     count
 }
 
+/// Sum the weights of common mappings between two signatures
+///
+/// Like `count_common_mappings`, but each mapping in sig1's active prefix that
+/// also appears in sig2's active prefix contributes `weights[i]` (its position
+/// in sig1) instead of 1. With all weights equal to 1.0 this equals the count.
+pub fn weighted_common_mappings(
+    sig1: &TokenSignature,
+    sig2: &TokenSignature,
+    chunks: usize,
+    weights: &[f64; TOKENS_SIGNATURE_SIZE],
+) -> f64 {
+    let chunks = chunks.min(TOKENS_SIGNATURE_SIZE);
+    let mut total = 0.0;
+    for (i, mapping1) in sig1.signature[..chunks].iter().enumerate() {
+        if sig2.signature[..chunks]
+            .iter()
+            .any(|mapping2| mapping1.id == mapping2.id && mapping1.block == mapping2.block)
+        {
+            total += weights[i];
+        }
+    }
+    total
+}
+
 /// Find ALL valid consensus clusters from signatures
 ///
-/// Returns all maximal clusters where all pairs agree on at least `min_threshold` mappings
-/// and the cluster has at least `min_size` members. Filters out clusters that are subsets
-/// of larger clusters.
+/// Returns all maximal clusters where all pairs agree on at least `min_threshold` of the
+/// first `chunks` mappings and the cluster has at least `min_size` members. Clusters that
+/// are subsets of larger clusters are not returned.
+///
+/// Clusters are the maximal cliques of the agreement graph (edge when two signatures share
+/// at least `min_threshold` mappings), enumerated with Bron-Kerbosch and pivoting, so the
+/// cost scales with the number of cliques rather than with all 2^n subsets.
 ///
 /// This is used for split-brain detection - if multiple large clusters exist, it indicates
 /// competing views of network state.
@@ -237,10 +482,35 @@ pub fn count_common_mappings(sig1: &TokenSignature, sig2: &TokenSignature) -> us
 /// size and agreement quality).
 pub fn find_all_consensus_clusters(
     signatures: &[TokenSignature],
+    chunks: usize,
     min_threshold: usize,
     min_size: usize,
+) -> Vec<ConsensusCluster> {
+    find_all_consensus_clusters_weighted(signatures, chunks, None, min_threshold as f64, min_size)
+}
+
+/// Find ALL valid consensus clusters, scoring agreement with per-position weights
+///
+/// Same as `find_all_consensus_clusters`, but pairwise agreement is the sum of
+/// `weights` over common mappings (see `weighted_common_mappings`), so matches on
+/// more discriminating positions count more. Both the `min_threshold` check and
+/// `avg_agreement` use the weighted score; `min_agreement` stays the plain count of
+/// common mappings.
+///
+/// With `weights = None` every position weighs 1.0 and the result is identical to
+/// `find_all_consensus_clusters`.
+pub fn find_all_consensus_clusters_weighted(
+    signatures: &[TokenSignature],
+    chunks: usize,
+    weights: Option<&[f64; TOKENS_SIGNATURE_SIZE]>,
+    min_threshold: f64,
+    min_size: usize,
 ) -> Vec<ConsensusCluster> {
     let n = signatures.len();
+    let full_score = match weights {
+        Some(weights) => weights[..chunks.min(TOKENS_SIGNATURE_SIZE)].iter().sum(),
+        None => chunks as f64,
+    };
 
     if n == 0 {
         return vec![];
@@ -250,110 +520,187 @@ pub fn find_all_consensus_clusters(
         if min_size <= 1 {
             return vec![ConsensusCluster {
                 members: vec![0],
-                min_agreement: SIGNATURE_CHUNKS,
-                avg_agreement: SIGNATURE_CHUNKS as f64,
+                min_agreement: chunks,
+                avg_agreement: full_score,
             }];
         } else {
             return vec![];
         }
     }
 
-    // Build pairwise agreement matrix
-    let mut agreement = vec![vec![0usize; n]; n];
-    for i in 0..n {
-        agreement[i][i] = SIGNATURE_CHUNKS;
-        for j in (i + 1)..n {
-            let common = count_common_mappings(&signatures[i], &signatures[j]);
-            agreement[i][j] = common;
-            agreement[j][i] = common;
-        }
-    }
-
-    let mut all_clusters = Vec::new();
-
-    // Check all possible subsets (2^n combinations)
-    for mask in 1..(1 << n) {
-        let members: Vec<usize> = (0..n).filter(|&i| (mask & (1 << i)) != 0).collect();
-
-        if members.len() < min_size {
-            continue; // Skip clusters below minimum size
-        }
-
-        // Check if this is a valid cluster
-        let mut min_agreement = SIGNATURE_CHUNKS;
-        let mut total_agreement = 0usize;
-        let mut pair_count = 0;
-        let mut valid = true;
-
-        for i in 0..members.len() {
-            for j in (i + 1)..members.len() {
-                let agree = agreement[members[i]][members[j]];
-                if agree < min_threshold {
-                    valid = false;
-                    break;
-                }
-                min_agreement = min_agreement.min(agree);
-                total_agreement += agree;
-                pair_count += 1;
-            }
-            if !valid {
-                break;
-            }
-        }
-
-        if !valid {
-            continue;
-        }
-
-        let avg_agreement = if pair_count > 0 {
-            total_agreement as f64 / pair_count as f64
-        } else {
-            SIGNATURE_CHUNKS as f64
-        };
-
-        all_clusters.push(ConsensusCluster {
-            members,
-            min_agreement,
-            avg_agreement,
-        });
-    }
-
-    // Filter out clusters that are strict subsets of larger clusters
-    let mut maximal_clusters = remove_subset_clusters(all_clusters);
-
-    // Sort clusters by quality: larger size first, then higher avg_agreement
+    let (agreement, score) = agreement_matrices(signatures, chunks, weights, full_score);
+
+    // Adjacency: an edge exists when two signatures agree strongly enough
+    let adjacency: Vec<Vec<bool>> = (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| i != j && score[i][j] >= min_threshold)
+                .collect()
+        })
+        .collect();
+
+    // Every maximal clique is a maximal cluster; filter by minimum size
+    let mut cliques = Vec::new();
+    bron_kerbosch(
+        &adjacency,
+        &mut Vec::new(),
+        (0..n).collect(),
+        Vec::new(),
+        &mut cliques,
+    );
+
+    let mut maximal_clusters: Vec<ConsensusCluster> = cliques
+        .into_iter()
+        .filter(|members| members.len() >= min_size)
+        .map(|mut members| {
+            members.sort_unstable();
+            cluster_from_members(members, &agreement, &score, full_score)
+        })
+        .collect();
+
+    // Sort clusters by quality: larger size first, then higher avg_agreement.
+    // Remaining ties are ordered as if members were bits of an ascending subset mask.
     maximal_clusters.sort_by(|a, b| match b.members.len().cmp(&a.members.len()) {
         std::cmp::Ordering::Equal => b
             .avg_agreement
             .partial_cmp(&a.avg_agreement)
-            .unwrap_or(std::cmp::Ordering::Equal),
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.members.iter().rev().cmp(b.members.iter().rev())),
         other => other,
     });
 
     maximal_clusters
 }
 
-/// Remove clusters that are strict subsets of other clusters
+/// Build the pairwise agreement matrices (plain count and weighted score)
 ///
-/// Returns only maximal clusters - those that are not proper subsets of any other cluster.
-fn remove_subset_clusters(clusters: Vec<ConsensusCluster>) -> Vec<ConsensusCluster> {
-    let mut maximal_clusters = Vec::new();
-
-    for candidate in &clusters {
-        let is_subset = clusters.iter().any(|other| {
-            if candidate.members.len() >= other.members.len() {
-                return false;
-            }
-            // Check if candidate is strict subset of other
-            candidate.members.iter().all(|m| other.members.contains(m))
-        });
+/// Only the upper triangle is computed, one row per signature, and then
+/// mirrored. With the `rayon` feature the rows are filled in parallel; each
+/// entry depends only on its pair, so the result is identical either way.
+fn agreement_matrices(
+    signatures: &[TokenSignature],
+    chunks: usize,
+    weights: Option<&[f64; TOKENS_SIGNATURE_SIZE]>,
+    full_score: f64,
+) -> (Vec<Vec<usize>>, Vec<Vec<f64>>) {
+    let n = signatures.len();
+    let row = |i: usize| -> Vec<(usize, f64)> {
+        ((i + 1)..n)
+            .map(|j| {
+                let common = count_common_mappings(&signatures[i], &signatures[j], chunks);
+                let weighted = match weights {
+                    Some(weights) => {
+                        weighted_common_mappings(&signatures[i], &signatures[j], chunks, weights)
+                    }
+                    None => common as f64,
+                };
+                (common, weighted)
+            })
+            .collect()
+    };
+
+    #[cfg(feature = "rayon")]
+    let rows: Vec<Vec<(usize, f64)>> = {
+        use rayon::prelude::*;
+        (0..n).into_par_iter().map(row).collect()
+    };
+    #[cfg(not(feature = "rayon"))]
+    let rows: Vec<Vec<(usize, f64)>> = (0..n).map(row).collect();
 
-        if !is_subset {
-            maximal_clusters.push(candidate.clone());
+    let mut agreement = vec![vec![0usize; n]; n];
+    let mut score = vec![vec![0f64; n]; n];
+    for (i, row) in rows.into_iter().enumerate() {
+        agreement[i][i] = chunks;
+        score[i][i] = full_score;
+        for (offset, (common, weighted)) in row.into_iter().enumerate() {
+            let j = i + 1 + offset;
+            agreement[i][j] = common;
+            agreement[j][i] = common;
+            score[i][j] = weighted;
+            score[j][i] = weighted;
         }
     }
+    (agreement, score)
+}
 
-    maximal_clusters
+/// Enumerate all maximal cliques using Bron-Kerbosch with pivoting
+///
+/// `r` is the clique being grown, `p` the candidates that extend it and `x`
+/// the vertices already covered by an earlier branch. Each maximal clique is
+/// pushed to `out` exactly once.
+fn bron_kerbosch(
+    adjacency: &[Vec<bool>],
+    r: &mut Vec<usize>,
+    mut p: Vec<usize>,
+    mut x: Vec<usize>,
+    out: &mut Vec<Vec<usize>>,
+) {
+    if p.is_empty() {
+        if x.is_empty() {
+            out.push(r.clone());
+        }
+        return;
+    }
+
+    // Pivot on the vertex with most neighbours in P to prune branches
+    let pivot = p
+        .iter()
+        .chain(x.iter())
+        .copied()
+        .max_by_key(|&u| p.iter().filter(|&&v| adjacency[u][v]).count())
+        .expect("p is not empty");
+
+    let candidates: Vec<usize> = p
+        .iter()
+        .copied()
+        .filter(|&v| !adjacency[pivot][v])
+        .collect();
+
+    for v in candidates {
+        let next_p = p.iter().copied().filter(|&u| adjacency[v][u]).collect();
+        let next_x = x.iter().copied().filter(|&u| adjacency[v][u]).collect();
+
+        r.push(v);
+        bron_kerbosch(adjacency, r, next_p, next_x, out);
+        r.pop();
+
+        p.retain(|&u| u != v);
+        x.push(v);
+    }
+}
+
+/// Build a cluster from sorted member indices and the pairwise agreement matrices
+///
+/// `min_agreement` comes from the plain counts, `avg_agreement` from the scores.
+fn cluster_from_members(
+    members: Vec<usize>,
+    agreement: &[Vec<usize>],
+    score: &[Vec<f64>],
+    full_score: f64,
+) -> ConsensusCluster {
+    let mut min_agreement = agreement[members[0]][members[0]];
+    let mut total_score = 0.0;
+    let mut pair_count = 0;
+
+    for i in 0..members.len() {
+        for j in (i + 1)..members.len() {
+            min_agreement = min_agreement.min(agreement[members[i]][members[j]]);
+            total_score += score[members[i]][members[j]];
+            pair_count += 1;
+        }
+    }
+
+    let avg_agreement = if pair_count > 0 {
+        total_score / pair_count as f64
+    } else {
+        full_score
+    };
+
+    ConsensusCluster {
+        members,
+        min_agreement,
+        avg_agreement,
+    }
 }
 
 // ============================================================================
@@ -361,7 +708,7 @@ fn remove_subset_clusters(clusters: Vec<ConsensusCluster>) -> Vec<ConsensusClust
 // ============================================================================
 
 /// State of an election channel
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ChannelState {
     /// Waiting for response
     Pending,
@@ -371,10 +718,13 @@ pub enum ChannelState {
 
     /// Multiple responses received - channel blocked (RED FLAG)
     Blocked,
+
+    /// No response within the channel timeout - late responses are rejected
+    Expired,
 }
 
 /// Response received from a channel
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChannelResponse {
     /// Proof-of-storage signature from responder
     pub signature: TokenSignature,
@@ -391,7 +741,7 @@ pub struct ChannelResponse {
 /// Each channel represents an independent route through the network,
 /// starting from a specific first-hop peer. Channels can receive at most
 /// one response - duplicate responses trigger blocking as an anti-gaming mechanism.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ElectionChannel {
     /// Ticket uniquely identifying this channel
     pub ticket: MessageTicket,
@@ -442,6 +792,16 @@ pub struct ElectionConfig {
     /// Winning cluster must have this fraction of valid responses to be a clear winner
     /// If no cluster reaches this threshold and there are multiple clusters, it's split-brain
     pub majority_threshold: f64,
+
+    /// Signature shape used to verify answers (default: 10 chunks of 10 bits)
+    /// `consensus_threshold` counts agreement within the first `signature_params.chunks` mappings
+    #[serde(default)]
+    pub signature_params: SignatureParams,
+
+    /// Optional per-position weights for clustering agreement (default: None = count)
+    /// When set, `consensus_threshold` is compared against the weighted agreement score
+    #[serde(default)]
+    pub signature_weights: Option<[f64; TOKENS_SIGNATURE_SIZE]>,
 }
 
 impl Default for ElectionConfig {
@@ -451,16 +811,51 @@ impl Default for ElectionConfig {
             min_cluster_size: 2,
             max_channels: 10,
             majority_threshold: 0.6,
+            signature_params: SignatureParams::default(),
+            signature_weights: None,
         }
     }
 }
 
+/// Strategy for picking the winner among a consensus cluster's members
+///
+/// The member with the highest score wins; ties go to the member listed first
+/// in the cluster. Closures `Fn(TokenId, PeerId, &ConsensusCluster) -> u64`
+/// implement this trait directly.
+pub trait WinnerSelector: Send + Sync {
+    /// Score a cluster member as winner for `challenge_token` (higher is better)
+    fn score(&self, challenge_token: TokenId, peer: PeerId, cluster: &ConsensusCluster) -> u64;
+}
+
+impl<F> WinnerSelector for F
+where
+    F: Fn(TokenId, PeerId, &ConsensusCluster) -> u64 + Send + Sync,
+{
+    fn score(&self, challenge_token: TokenId, peer: PeerId, cluster: &ConsensusCluster) -> u64 {
+        self(challenge_token, peer, cluster)
+    }
+}
+
+/// Default winner selection: the peer closest to the challenge token on the ring
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RingDistanceSelector;
+
+impl WinnerSelector for RingDistanceSelector {
+    fn score(&self, challenge_token: TokenId, peer: PeerId, _cluster: &ConsensusCluster) -> u64 {
+        u64::MAX - ring_distance(peer, challenge_token)
+    }
+}
+
+fn default_winner_selector() -> Box<dyn WinnerSelector> {
+    Box::new(RingDistanceSelector)
+}
+
 /// Result of checking for a winner
 #[derive(Debug, Clone, PartialEq)]
 pub enum WinnerResult {
     /// Single clear winner found
     Single {
-        /// The elected winner (peer closest to challenge_token)
+        /// The elected winner (by default the peer closest to challenge_token)
         winner: PeerId,
         /// The consensus cluster
         cluster: ConsensusCluster,
@@ -484,8 +879,21 @@ pub enum WinnerResult {
         signatures2: Vec<(PeerId, TokenSignature)>,
     },
 
-    /// No consensus found yet (not enough responses or agreement)
-    NoConsensus,
+    /// Fewer distinct responders than `min_cluster_size`
+    InsufficientResponses {
+        /// Distinct responders with a valid (non-blocked) response
+        have: usize,
+        /// Responders needed (`min_cluster_size`)
+        need: usize,
+    },
+
+    /// Enough responses, but no cluster of `min_cluster_size` agrees
+    NoAgreement {
+        /// Distinct responders with a valid (non-blocked) response
+        responses: usize,
+        /// Size of the largest group agreeing above `consensus_threshold`
+        best_cluster_size: usize,
+    },
 }
 
 /// Errors that can occur during election
@@ -512,14 +920,24 @@ pub enum ElectionError {
     /// Channel is blocked (duplicate response detected)
     ChannelBlocked,
 
-    /// Signature verification failed
-    SignatureVerificationFailed,
+    /// A signature mapping's low bits don't match the expected chunk
+    SignatureChunkMismatch {
+        /// Position of the first mismatching mapping in the signature
+        index: usize,
+        /// Chunk value derived from Blake3(challenger || token || block)
+        expected: u16,
+        /// Chunk value carried by the mapping's token id
+        actual: u16,
+    },
 
     /// All suggested peers from referral are already participating
     NoViableSuggestions,
 
     /// Trying to setup a channel for self
     SelfReference,
+
+    /// Channel expired before a response arrived
+    ChannelExpired,
 }
 
 // ============================================================================
@@ -546,6 +964,13 @@ pub enum ElectionError {
 /// // When Answer received, verify and submit
 /// // election.handle_answer(ticket, answer, signature, responder_peer).unwrap();
 /// ```
+///
+/// The full election state (including the ticket secret, channels and stored
+/// responses) implements serde `Serialize`/`Deserialize`, so an in-flight election
+/// can be persisted across restarts and resumed with identical behavior. A custom
+/// `WinnerSelector` is not part of the snapshot and must be set again after restore.
+#[derive(Serialize, Deserialize)]
+#[serde(from = "PeerElectionSnapshot")]
 pub struct PeerElection {
     /// Token being challenged
     challenge_token: TokenId,
@@ -562,8 +987,68 @@ pub struct PeerElection {
     /// Track first-hop peers to prevent duplicate channels
     first_hop_peers: HashMap<PeerId, MessageTicket>,
 
+    /// Peers with a stored response, and the ticket of the channel holding it
+    ///
+    /// Derived from `channels`, so it is rebuilt on restore rather than stored.
+    #[serde(skip)]
+    responders: HashMap<PeerId, MessageTicket>,
+
     /// Configuration
     config: ElectionConfig,
+
+    /// Strategy used to pick each cluster's winner
+    #[serde(skip, default = "default_winner_selector")]
+    winner_selector: Box<dyn WinnerSelector>,
+
+    /// Bumped whenever the set of responses (or the selector) changes
+    #[serde(skip)]
+    responses_version: u64,
+
+    /// Last `check_for_winner` result and the `responses_version` it was computed at
+    #[serde(skip)]
+    winner_cache: std::sync::Mutex<Option<(u64, WinnerResult)>>,
+
+    /// How often the clustering behind `check_for_winner` actually ran
+    #[serde(skip)]
+    winner_computations: std::sync::atomic::AtomicUsize,
+}
+
+/// Serialized fields of a `PeerElection`
+///
+/// Restoring goes through this so bookkeeping derived from the channels is
+/// rebuilt, also for snapshots written before that bookkeeping existed.
+#[derive(Deserialize)]
+struct PeerElectionSnapshot {
+    challenge_token: TokenId,
+    my_peer_id: PeerId,
+    election_secret: [u8; 32],
+    channels: HashMap<MessageTicket, ElectionChannel>,
+    first_hop_peers: HashMap<PeerId, MessageTicket>,
+    config: ElectionConfig,
+}
+
+impl From<PeerElectionSnapshot> for PeerElection {
+    fn from(snapshot: PeerElectionSnapshot) -> Self {
+        let mut election = Self::with_secret(
+            snapshot.challenge_token,
+            snapshot.my_peer_id,
+            snapshot.config,
+            snapshot.election_secret,
+        );
+        election.responders = snapshot
+            .channels
+            .iter()
+            .filter_map(|(ticket, channel)| {
+                channel
+                    .response
+                    .as_ref()
+                    .map(|response| (response.responder, *ticket))
+            })
+            .collect();
+        election.channels = snapshot.channels;
+        election.first_hop_peers = snapshot.first_hop_peers;
+        election
+    }
 }
 
 impl PeerElection {
@@ -581,16 +1066,49 @@ impl PeerElection {
         use rand::RngCore;
         rand::thread_rng().fill_bytes(&mut election_secret);
 
+        Self::with_secret(challenge_token, my_peer_id, config, election_secret)
+    }
+
+    /// Create a new election with a caller-provided election secret
+    ///
+    /// Tickets are derived from the secret, so two elections built with the same
+    /// secret, challenge token and first-hop peers produce identical tickets. Use
+    /// this to derive the secret from a seeded RNG for reproducible simulations.
+    ///
+    /// # Arguments
+    /// * `challenge_token` - Token to challenge
+    /// * `my_peer_id` - This node's peer ID (the challenger)
+    /// * `config` - Election configuration
+    /// * `election_secret` - Election-specific secret for ticket generation
+    pub fn with_secret(
+        challenge_token: TokenId,
+        my_peer_id: PeerId,
+        config: ElectionConfig,
+        election_secret: [u8; 32],
+    ) -> Self {
         Self {
             challenge_token,
             my_peer_id,
             election_secret,
             channels: HashMap::new(),
             first_hop_peers: HashMap::new(),
+            responders: HashMap::new(),
             config,
+            winner_selector: default_winner_selector(),
+            responses_version: 0,
+            winner_cache: Default::default(),
+            winner_computations: Default::default(),
         }
     }
 
+    /// Replace the winner-selection strategy (default: `RingDistanceSelector`)
+    ///
+    /// Used by `check_for_winner` for both single and split-brain winners.
+    pub fn set_winner_selector(&mut self, selector: Box<dyn WinnerSelector>) {
+        self.winner_selector = selector;
+        self.responses_version += 1;
+    }
+
     /// Create a new election from an invitation (unsolicited Answer message)
     ///
     /// This spawns an election with an initial channel already in Responded state,
@@ -606,7 +1124,7 @@ impl PeerElection {
     ///
     /// # Returns
     /// * `Ok(PeerElection)` - Election successfully created with initial response
-    /// * `Err(SignatureVerificationFailed)` - Signature doesn't match expected values
+    /// * `Err(SignatureChunkMismatch)` - A signature mapping doesn't match its expected chunk
     ///
     /// # Example
     /// ```no_run
@@ -636,22 +1154,39 @@ impl PeerElection {
         my_peer_id: PeerId,
         config: ElectionConfig,
     ) -> Result<Self, ElectionError> {
-        let challenge_token = answer.id;
-
         // Generate secure random election-specific secret
         let mut election_secret = [0u8; 32];
         use rand::RngCore;
         rand::thread_rng().fill_bytes(&mut election_secret);
 
-        // Create the election structure
-        let mut election = Self {
-            challenge_token,
+        Self::from_invitation_with_secret(
+            answer,
+            signature_mappings,
+            responder_peer,
+            time,
             my_peer_id,
-            election_secret,
-            channels: HashMap::new(),
-            first_hop_peers: HashMap::new(),
             config,
-        };
+            election_secret,
+        )
+    }
+
+    /// Create a new election from an invitation with a caller-provided election secret
+    ///
+    /// Same as `from_invitation`, but the ticket secret is supplied by the caller
+    /// (see `with_secret`).
+    pub fn from_invitation_with_secret(
+        answer: &TokenMapping,
+        signature_mappings: &[TokenMapping; TOKENS_SIGNATURE_SIZE],
+        responder_peer: PeerId,
+        time: EcTime,
+        my_peer_id: PeerId,
+        config: ElectionConfig,
+        election_secret: [u8; 32],
+    ) -> Result<Self, ElectionError> {
+        let challenge_token = answer.id;
+
+        // Create the election structure
+        let mut election = Self::with_secret(challenge_token, my_peer_id, config, election_secret);
 
         // Verify the signature before creating the channel
         election.verify_signature(answer.block, signature_mappings)?;
@@ -680,6 +1215,7 @@ impl PeerElection {
         // Store the channel
         election.channels.insert(ticket, channel);
         election.first_hop_peers.insert(responder_peer, ticket);
+        election.responders.insert(responder_peer, ticket);
 
         Ok(election)
     }
@@ -721,12 +1257,8 @@ impl PeerElection {
 
         // Check if this peer has already responded via another channel
         // (peer could be responder on a different route)
-        for channel in self.channels.values() {
-            if let Some(response) = &channel.response {
-                if response.responder == first_hop {
-                    return Err(ElectionError::PeerAlreadyParticipating);
-                }
-            }
+        if self.responders.contains_key(&first_hop) {
+            return Err(ElectionError::PeerAlreadyParticipating);
         }
 
         let ticket = generate_ticket(self.challenge_token, first_hop, &self.election_secret);
@@ -754,8 +1286,10 @@ impl PeerElection {
     /// * `Err(WrongToken)` - Answer is for a different token
     /// * `Err(UnknownTicket)` - Ticket not found in this election
     /// * `Err(ChannelBlocked)` - Channel is blocked
+    /// * `Err(ChannelExpired)` - Channel expired before the answer arrived
     /// * `Err(DuplicateResponse)` - Channel already has response (now blocked)
-    /// * `Err(SignatureVerificationFailed)` - Signature doesn't match expected values
+    /// * `Err(PeerAlreadyParticipating)` - Responder already answered on another channel
+    /// * `Err(SignatureChunkMismatch)` - A signature mapping doesn't match its expected chunk
     pub fn handle_answer(
         &mut self,
         ticket: MessageTicket,
@@ -769,6 +1303,14 @@ impl PeerElection {
             return Err(ElectionError::WrongToken);
         }
 
+        // A responder only counts once, so skip verifying its answers on other
+        // channels. Same-channel duplicates fall through and block the channel.
+        if let Some(&answered) = self.responders.get(&responder_peer) {
+            if answered != ticket {
+                return Err(ElectionError::PeerAlreadyParticipating);
+            }
+        }
+
         // Verify the signature BEFORE getting mutable access to channel
         // (to avoid borrow checker issues)
         self.verify_signature(answer.block, signature_mappings)?;
@@ -779,14 +1321,18 @@ impl PeerElection {
             .get_mut(&ticket)
             .ok_or(ElectionError::UnknownTicket)?;
 
-        // Check if channel is already blocked
+        // Check if channel is already blocked or expired
         if channel.state == ChannelState::Blocked {
             return Err(ElectionError::ChannelBlocked);
         }
+        if channel.state == ChannelState::Expired {
+            return Err(ElectionError::ChannelExpired);
+        }
 
         // Detect duplicate (anti-gaming mechanism)
         if channel.response.is_some() {
             channel.state = ChannelState::Blocked;
+            self.responses_version += 1;
             return Err(ElectionError::DuplicateResponse);
         }
 
@@ -802,14 +1348,17 @@ impl PeerElection {
             received_at,
         });
         channel.state = ChannelState::Responded;
+        self.responses_version += 1;
+        self.responders.insert(responder_peer, ticket);
 
         Ok(())
     }
 
-    /// Verify a signature by checking the 10-bit chunks
+    /// Verify a signature by checking the signature chunks
     ///
     /// Calculates the expected signature using Blake3(my_peer_id, token_id, response_block_id)
-    /// and verifies that the signature_mappings match the expected chunks.
+    /// and verifies that the first `signature_params.chunks` signature_mappings match the
+    /// expected chunks. Slots past the active count are not checked.
     fn verify_signature(
         &self,
         response_block_id: BlockId,
@@ -822,16 +1371,20 @@ impl PeerElection {
         hasher.update(&response_block_id.to_le_bytes());
         let hash = hasher.finalize();
 
-        // Extract expected 10-bit chunks from the hash
-        let expected_chunks = extract_signature_chunks_from_256bit_hash(hash.as_bytes());
-
-        // Verify each signature mapping matches the expected chunk
-        for (i, mapping) in signature_mappings.iter().enumerate() {
-            let expected_chunk = expected_chunks[i];
-            let token_last_bits = (mapping.id & 0x3FF) as u16; // Last 10 bits
-
-            if token_last_bits != expected_chunk {
-                return Err(ElectionError::SignatureVerificationFailed);
+        // Extract expected chunks from the hash
+        let params = &self.config.signature_params;
+        let expected_chunks = params.extract_chunks(hash.as_bytes());
+
+        // Verify each active signature mapping matches the expected chunk
+        for (index, (mapping, &expected)) in
+            signature_mappings.iter().zip(&expected_chunks).enumerate()
+        {
+            if !params.matches_chunk(&mapping.id, expected) {
+                return Err(ElectionError::SignatureChunkMismatch {
+                    index,
+                    expected,
+                    actual: (mapping.id & params.chunk_mask()) as u16,
+                });
             }
         }
 
@@ -854,6 +1407,7 @@ impl PeerElection {
     /// * `Err(WrongToken)` - Referral is for a different token
     /// * `Err(UnknownTicket)` - Ticket not found
     /// * `Err(ChannelBlocked)` - Channel is blocked, ignoring referral
+    /// * `Err(ChannelExpired)` - Channel expired, ignoring referral
     /// * `Err(NoViableSuggestions)` - Both suggested peers are already participating
     pub fn handle_referral(
         &mut self,
@@ -873,22 +1427,36 @@ impl PeerElection {
             .get(&ticket)
             .ok_or(ElectionError::UnknownTicket)?;
 
-        // If channel is blocked, reject the referral
+        // If channel is blocked or expired, reject the referral
         if channel.state == ChannelState::Blocked {
             return Err(ElectionError::ChannelBlocked);
         }
+        if channel.state == ChannelState::Expired {
+            return Err(ElectionError::ChannelExpired);
+        }
 
         // Get all participating peers to filter suggestions
         let participating = self.get_participating_peers();
 
         // Destroy the channel (no other answer should come for it)
+        if let Some(response) = &channel.response {
+            self.responders.remove(&response.responder);
+        }
         self.first_hop_peers.remove(&channel.first_hop_peer);
         self.channels.remove(&ticket);
+        self.responses_version += 1;
 
-        // Shuffle suggested peers to avoid predictability
+        // Shuffle suggested peers to avoid predictability. The order is derived
+        // from the election secret and ticket, so it stays unpredictable to
+        // other peers but reproducible when the secret comes from a seeded RNG.
         use rand::seq::SliceRandom;
+        use rand::SeedableRng;
+        let mut shuffle_seed = self.election_secret;
+        for (byte, ticket_byte) in shuffle_seed.iter_mut().zip(ticket.to_le_bytes()) {
+            *byte ^= ticket_byte;
+        }
         let mut peers_shuffled = suggested_peers.to_vec();
-        peers_shuffled.shuffle(&mut rand::thread_rng());
+        peers_shuffled.shuffle(&mut rand::rngs::StdRng::from_seed(shuffle_seed));
 
         // Find first suggested peer not already participating
         for &peer in &peers_shuffled {
@@ -912,19 +1480,41 @@ impl PeerElection {
     /// peer only participates once in consensus.
     ///
     /// User controls when to call this - can be called any time to check status.
+    /// The result is cached until a response arrives, a channel is blocked or
+    /// removed, or the winner selector changes, so repeated calls are cheap.
     ///
     /// # Returns
     /// * `WinnerResult::Single` - Clear winner with consensus cluster
     /// * `WinnerResult::SplitBrain` - Two competing clusters found
-    /// * `WinnerResult::NoConsensus` - Not enough responses or no agreement
+    /// * `WinnerResult::InsufficientResponses` - Not enough responses yet
+    /// * `WinnerResult::NoAgreement` - Enough responses, but no cluster agrees
     pub fn check_for_winner(&self) -> WinnerResult {
-        // Get valid responses (non-blocked)
-        let all_responses: Vec<_> = self
+        let mut cache = self.winner_cache.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((version, result)) = cache.as_ref() {
+            if *version == self.responses_version {
+                return result.clone();
+            }
+        }
+
+        let result = self.compute_winner();
+        *cache = Some((self.responses_version, result.clone()));
+        result
+    }
+
+    /// Uncached `check_for_winner`: cluster the current responses
+    fn compute_winner(&self) -> WinnerResult {
+        self.winner_computations
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+
+        // Get valid responses (non-blocked), in ticket order so the result does not
+        // depend on HashMap iteration order (e.g. after restoring a snapshot)
+        let mut all_responses: Vec<_> = self
             .channels
             .values()
             .filter(|ch| ch.state == ChannelState::Responded)
             .filter_map(|ch| ch.response.as_ref().map(|r| (ch.ticket, r.clone())))
             .collect();
+        all_responses.sort_by_key(|(ticket, _)| *ticket);
 
         // Deduplicate by responder PeerId (keep first response from each unique peer)
         // This prevents the same peer from being counted multiple times if they
@@ -936,7 +1526,10 @@ impl PeerElection {
             .collect();
 
         if valid_responses.len() < self.config.min_cluster_size {
-            return WinnerResult::NoConsensus;
+            return WinnerResult::InsufficientResponses {
+                have: valid_responses.len(),
+                need: self.config.min_cluster_size,
+            };
         }
 
         // Extract signatures for clustering
@@ -945,18 +1538,50 @@ impl PeerElection {
             .map(|(_, resp)| resp.signature.clone())
             .collect();
 
-        // Find ALL consensus clusters (returned sorted by size, then avg_agreement)
-        let all_clusters = find_all_consensus_clusters(
+        // Find ALL consensus clusters (returned sorted by size, then avg_agreement).
+        // Undersized ones are dropped here rather than by min_size, so a failed
+        // election can still report how close it came.
+        let mut all_clusters = find_all_consensus_clusters_weighted(
             &signatures,
-            self.config.consensus_threshold,
-            self.config.min_cluster_size,
+            self.config.signature_params.chunks,
+            self.config.signature_weights.as_ref(),
+            self.config.consensus_threshold as f64,
+            1,
         );
+        let best_cluster_size = all_clusters.first().map_or(0, |c| c.members.len());
+        all_clusters.retain(|c| c.members.len() >= self.config.min_cluster_size);
+
+        // Members are indices in ticket order, and tickets vary with the election
+        // secret; break equal-strength ties on the members' peer ids instead
+        let member_peers = |cluster: &ConsensusCluster| {
+            let mut peers: Vec<PeerId> = cluster
+                .members
+                .iter()
+                .map(|&idx| valid_responses[idx].1.responder)
+                .collect();
+            peers.sort_unstable();
+            peers
+        };
+        all_clusters.sort_by(|a, b| {
+            b.members
+                .len()
+                .cmp(&a.members.len())
+                .then_with(|| {
+                    b.avg_agreement
+                        .partial_cmp(&a.avg_agreement)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+                .then_with(|| member_peers(a).cmp(&member_peers(b)))
+        });
 
         if all_clusters.is_empty() {
-            return WinnerResult::NoConsensus;
+            return WinnerResult::NoAgreement {
+                responses: valid_responses.len(),
+                best_cluster_size,
+            };
         }
 
-        // Strongest cluster is first (results are pre-sorted)
+        // Strongest cluster is first
         let strongest_cluster = &all_clusters[0];
         let total_valid = valid_responses.len();
 
@@ -970,10 +1595,8 @@ impl PeerElection {
 
             // If second cluster also meets min_cluster_size, we have split-brain
             if cluster2.members.len() >= self.config.min_cluster_size {
-                let (winner1, sigs1) =
-                    Self::select_winner(self.challenge_token, strongest_cluster, &valid_responses);
-                let (winner2, sigs2) =
-                    Self::select_winner(self.challenge_token, cluster2, &valid_responses);
+                let (winner1, sigs1) = self.select_winner(strongest_cluster, &valid_responses);
+                let (winner2, sigs2) = self.select_winner(cluster2, &valid_responses);
 
                 return WinnerResult::SplitBrain {
                     cluster1: strongest_cluster.clone(),
@@ -987,8 +1610,7 @@ impl PeerElection {
         }
 
         // Single winner (either has decisive majority, or only one cluster exists)
-        let (winner, cluster_sigs) =
-            Self::select_winner(self.challenge_token, strongest_cluster, &valid_responses);
+        let (winner, cluster_sigs) = self.select_winner(strongest_cluster, &valid_responses);
 
         WinnerResult::Single {
             winner,
@@ -997,9 +1619,9 @@ impl PeerElection {
         }
     }
 
-    /// Select winner from consensus cluster (peer closest to challenge_token)
+    /// Select winner from consensus cluster (highest `winner_selector` score)
     fn select_winner(
-        challenge_token: TokenId,
+        &self,
         cluster: &ConsensusCluster,
         responses: &[(MessageTicket, ChannelResponse)],
     ) -> (PeerId, Vec<(PeerId, TokenSignature)>) {
@@ -1013,17 +1635,42 @@ impl PeerElection {
             })
             .collect();
 
-        // Find peer with minimum ring distance to challenge_token
+        // Find peer with the highest score (first member wins ties)
         let winner = cluster_responses
             .iter()
+            .map(|(peer_id, _)| {
+                let score = self
+                    .winner_selector
+                    .score(self.challenge_token, *peer_id, cluster);
+                (*peer_id, score)
+            })
+            .reduce(|best, next| if next.1 > best.1 { next } else { best })
             .map(|(peer_id, _)| peer_id)
-            .min_by_key(|&&peer_id| ring_distance(peer_id, challenge_token))
-            .copied()
             .expect("Cluster has members");
 
         (winner, cluster_responses)
     }
 
+    /// Expire Pending channels that have waited at least `timeout` since `sent_at`
+    ///
+    /// Expired channels keep their first-hop reservation but no longer accept
+    /// answers or referrals, so an election can prune silent routes on its own.
+    ///
+    /// # Returns
+    /// Number of channels expired by this call
+    pub fn expire_stale_channels(&mut self, now: EcTime, timeout: EcTime) -> usize {
+        let mut expired = 0;
+        for channel in self.channels.values_mut() {
+            if channel.state == ChannelState::Pending
+                && now.saturating_sub(channel.sent_at) >= timeout
+            {
+                channel.state = ChannelState::Expired;
+                expired += 1;
+            }
+        }
+        expired
+    }
+
     /// Get number of valid (non-blocked) responses currently collected
     pub fn valid_response_count(&self) -> usize {
         self.channels
@@ -1074,9 +1721,40 @@ impl PeerElection {
 }
 
 impl ProofOfStorage {
-    /// Create a new proof-of-storage system (zero-sized type)
+    /// Create a new proof-of-storage system with default signature params
     pub fn new() -> Self {
-        Self {}
+        Self::with_params(SignatureParams::default())
+    }
+
+    /// Create a new proof-of-storage system with custom signature params
+    ///
+    /// # Panics
+    /// Panics if `params` is not valid (see `SignatureParams::is_valid`).
+    pub fn with_params(params: SignatureParams) -> Self {
+        Self::with_params_and_max_steps(params, usize::MAX)
+    }
+
+    /// Create a new proof-of-storage system with custom signature params and
+    /// a search step budget
+    ///
+    /// Searches that exhaust `max_steps` stop early and report `complete: false`,
+    /// so sparse stores cannot stall the caller with a full double ring scan.
+    ///
+    /// # Panics
+    /// Panics if `params` is not valid (see `SignatureParams::is_valid`).
+    pub fn with_params_and_max_steps(params: SignatureParams, max_steps: usize) -> Self {
+        assert!(params.is_valid(), "Invalid signature params: {:?}", params);
+        Self { params, max_steps }
+    }
+
+    /// Get the signature params used by this system
+    pub fn params(&self) -> &SignatureParams {
+        &self.params
+    }
+
+    /// Get the search step budget used by this system
+    pub fn max_steps(&self) -> usize {
+        self.max_steps
     }
 
     /// Extract the last N bits from a token for signature matching
@@ -1095,15 +1773,16 @@ impl ProofOfStorage {
         Self::token_last_bits(token, CHUNK_BITS) == chunk_value as u64
     }
 
-    /// Generate a 100-bit signature from token, block, and peer
+    /// Generate a signature from token, block, and peer
     ///
-    /// Returns 10 chunks of 10 bits each.
+    /// Returns `params.chunks` chunks of `params.bits` bits each
+    /// (10 chunks of 10 bits = 100 bits by default).
     ///
     /// Uses Blake3 for cryptographically secure hashing.
     /// Hash is computed as: Blake3(peer || token || block)
     ///
     /// This matches the verification logic used in `PeerElection::verify_signature`.
-    fn signature_for(token: &TokenId, block: &BlockId, peer: &PeerId) -> [u16; SIGNATURE_CHUNKS] {
+    fn signature_for(&self, token: &TokenId, block: &BlockId, peer: &PeerId) -> Vec<u16> {
         // Create Blake3 hash from the three inputs
         // Order: peer, token, block (same as verify_signature)
         let mut hasher = blake3::Hasher::new();
@@ -1112,23 +1791,35 @@ impl ProofOfStorage {
         hasher.update(&block.to_le_bytes());
         let hash = hasher.finalize();
 
-        // Extract 10-bit chunks from the 256-bit hash
-        extract_signature_chunks_from_256bit_hash(hash.as_bytes())
+        // Extract chunks from the 256-bit hash
+        self.params.extract_chunks(hash.as_bytes())
     }
 
     /// Perform signature-based token search
     ///
     /// This delegates to the backend's `search_signature` implementation,
-    /// allowing each backend to optimize the search strategy.
+    /// allowing each backend to optimize the search strategy. Chunks are
+    /// matched using this system's `params.bits`, and the search stops after
+    /// `max_steps` tokens have been examined. The first `forward_chunks` chunks
+    /// are matched above `lookup_token`, the rest below it; signature
+    /// generation uses `params.forward_chunks()` so every peer splits alike.
     ///
     /// Returns tokens matching the signature criteria along with search statistics.
+    /// A search that ran out of budget returns the partial result with `complete: false`.
     pub fn search_by_signature<B: TokenStorageBackend + ?Sized>(
         &self,
         backend: &B,
         lookup_token: &TokenId,
-        signature_chunks: &[u16; SIGNATURE_CHUNKS],
+        signature_chunks: &[u16],
+        forward_chunks: usize,
     ) -> SignatureSearchResult {
-        backend.search_signature(lookup_token, signature_chunks)
+        backend.search_signature(
+            lookup_token,
+            signature_chunks,
+            &self.params,
+            forward_chunks,
+            self.max_steps,
+        )
     }
 
     /// Generate a complete proof-of-storage signature for a token
@@ -1144,8 +1835,10 @@ impl ProofOfStorage {
     /// - `peer`: The peer requesting the signature (affects signature generation)
     ///
     /// # Returns
-    /// - `Some(TokenSignature)`: If the token exists and all 10 signature tokens were found
+    /// - `Some(TokenSignature)`: If the token exists and all `params.chunks` signature tokens
+    ///   were found. Slots past `params.chunks` are zero.
     /// - `None`: If the token doesn't exist or the signature search was incomplete
+    ///   (including a search that exhausted the `max_steps` budget)
     ///
     /// # Example
     /// ```rust
@@ -1177,18 +1870,26 @@ impl ProofOfStorage {
         let block_time = backend.lookup(token)?;
 
         // Generate signature from token, block, and peer
-        let signature_chunks = Self::signature_for(token, &block_time.block, peer);
+        let signature_chunks = self.signature_for(token, &block_time.block, peer);
 
         // Perform signature-based search
-        let search_result = self.search_by_signature(backend, token, &signature_chunks);
+        let search_result = self.search_by_signature(
+            backend,
+            token,
+            &signature_chunks,
+            self.params.forward_chunks(),
+        );
 
-        // Only return a signature if we found all 10 tokens
+        // Only return a signature if we found a token for every chunk
         if search_result.complete {
             // Build the signature array from found tokens
             let mut signature = [TokenMapping { id: 0, block: 0 }; TOKENS_SIGNATURE_SIZE];
 
-            for (i, &token_id) in search_result.tokens.iter().enumerate() {
-                if let Some(block_time) = backend.lookup(&token_id) {
+            let block_times = backend.get_many(&search_result.tokens);
+            for (i, (&token_id, block_time)) in
+                search_result.tokens.iter().zip(block_times).enumerate()
+            {
+                if let Some(block_time) = block_time {
                     signature[i] = TokenMapping {
                         id: token_id,
                         block: block_time.block,
@@ -1208,6 +1909,108 @@ impl ProofOfStorage {
             None
         }
     }
+
+    /// Generate a signature even when the search can't match every chunk
+    ///
+    /// Unlike `generate_signature`, which is all-or-nothing, this returns what
+    /// a pruned or bootstrapping node can prove. Position `i` holds a stored
+    /// token matching chunk `i`; positions without one are zero mappings, as
+    /// are positions past `params.chunks`. Returns None only if `token` itself
+    /// isn't stored.
+    pub fn generate_partial_signature<B: TokenStorageBackend + ?Sized>(
+        &self,
+        backend: &B,
+        token: &TokenId,
+        peer: &PeerId,
+    ) -> Option<PartialSignature> {
+        let block_time = backend.lookup(token)?;
+        let signature_chunks = self.signature_for(token, &block_time.block, peer);
+        let search_result = self.search_by_signature(
+            backend,
+            token,
+            &signature_chunks,
+            self.params.forward_chunks(),
+        );
+
+        let mut signature = [TokenMapping { id: 0, block: 0 }; TOKENS_SIGNATURE_SIZE];
+        let mut found = 0;
+        let block_times = backend.get_many(&search_result.tokens);
+        for (i, (&token_id, token_block)) in
+            search_result.tokens.iter().zip(block_times).enumerate()
+        {
+            // Backends fill chunks in order, but only keep tokens that verify in place
+            let Some(&chunk) = signature_chunks.get(i) else {
+                break;
+            };
+            if let Some(token_block) = token_block {
+                if self.params.matches_chunk(&token_id, chunk) {
+                    signature[i] = TokenMapping {
+                        id: token_id,
+                        block: token_block.block,
+                    };
+                    found += 1;
+                }
+            }
+        }
+
+        Some(PartialSignature {
+            signature: TokenSignature {
+                answer: TokenMapping {
+                    id: *token,
+                    block: block_time.block,
+                },
+                signature,
+            },
+            found,
+        })
+    }
+
+    /// Find all consensus clusters among collected signatures
+    ///
+    /// Thin wrapper around `find_all_consensus_clusters` using this system's
+    /// `params.chunks`, for analysing competing network views (split-brain)
+    /// without running a full `PeerElection`. Member indices refer to positions
+    /// in `signatures`.
+    ///
+    /// Only maximal clusters are returned: a cluster that is a subset of a larger
+    /// valid cluster is dropped. Clusters are ordered largest first, then by
+    /// higher `avg_agreement`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use ec_rust::ec_interface::{TokenMapping, TokenSignature, TOKENS_SIGNATURE_SIZE};
+    /// use ec_rust::ec_proof_of_storage::ProofOfStorage;
+    ///
+    /// // Peers in one view all report block `view` for the same ten tokens
+    /// let signature_in_view = |view: u64| {
+    ///     let mut signature = [TokenMapping { id: 0, block: 0 }; TOKENS_SIGNATURE_SIZE];
+    ///     for (i, mapping) in signature.iter_mut().take(10).enumerate() {
+    ///         *mapping = TokenMapping { id: i as u64 + 1, block: view };
+    ///     }
+    ///     TokenSignature { answer: TokenMapping { id: 100, block: view }, signature }
+    /// };
+    ///
+    /// let signatures = vec![
+    ///     signature_in_view(1),
+    ///     signature_in_view(2),
+    ///     signature_in_view(1),
+    ///     signature_in_view(2),
+    ///     signature_in_view(1),
+    /// ];
+    ///
+    /// let clusters = ProofOfStorage::new().all_clusters(&signatures, 8, 2);
+    /// assert_eq!(clusters.len(), 2);
+    /// assert_eq!(clusters[0].members, vec![0, 2, 4]);
+    /// assert_eq!(clusters[1].members, vec![1, 3]);
+    /// ```
+    pub fn all_clusters(
+        &self,
+        signatures: &[TokenSignature],
+        min_threshold: usize,
+        min_size: usize,
+    ) -> Vec<ConsensusCluster> {
+        find_all_consensus_clusters(signatures, self.params.chunks, min_threshold, min_size)
+    }
 }
 
 // ============================================================================
@@ -1251,6 +2054,13 @@ mod tests {
     use super::*;
     use std::collections::BTreeMap;
 
+    // Pad a default-size signature to the full wire array (zero slots)
+    fn padded(mappings: [TokenMapping; SIGNATURE_CHUNKS]) -> [TokenMapping; TOKENS_SIGNATURE_SIZE] {
+        let mut signature = [TokenMapping { id: 0, block: 0 }; TOKENS_SIGNATURE_SIZE];
+        signature[..SIGNATURE_CHUNKS].copy_from_slice(&mappings);
+        signature
+    }
+
     // Simple in-memory backend for testing
     struct TestBackend {
         tokens: BTreeMap<TokenId, BlockTime>,
@@ -1280,69 +2090,90 @@ mod tests {
             );
         }
 
+        fn remove(&mut self, token: &TokenId) -> Option<BlockTime> {
+            self.tokens.remove(token)
+        }
+
         fn search_signature(
             &self,
             lookup_token: &TokenId,
-            signature_chunks: &[u16; SIGNATURE_CHUNKS],
+            signature_chunks: &[u16],
+            params: &SignatureParams,
+            forward_chunks: usize,
+            max_steps: usize,
         ) -> SignatureSearchResult {
             use std::ops::Bound::{Excluded, Unbounded};
 
-            let mut found_tokens = Vec::with_capacity(SIGNATURE_CHUNKS);
+            let total = signature_chunks.len();
+            let forward = forward_chunks.min(total);
+            let mut found_tokens = Vec::with_capacity(total);
             let mut steps = 0;
             let mut chunk_idx = 0;
 
-            // Helper to match signature chunk
-            #[inline]
-            fn matches_chunk(token: &TokenId, chunk_value: u16) -> bool {
-                (token & 0x3FF) as u16 == chunk_value
-            }
-
-            // Search above (forward) for first 5 chunks
-            for (token, _) in self.tokens.range((Excluded(lookup_token), Unbounded)) {
-                steps += 1;
-                if matches_chunk(token, signature_chunks[chunk_idx]) {
-                    found_tokens.push(*token);
-                    chunk_idx += 1;
-                    if chunk_idx >= 5 {
+            // Search above (forward) for first half of the chunks
+            if chunk_idx < forward {
+                for (token, _) in self.tokens.range((Excluded(lookup_token), Unbounded)) {
+                    if steps >= max_steps {
                         break;
                     }
+                    steps += 1;
+                    if params.matches_chunk(token, signature_chunks[chunk_idx]) {
+                        found_tokens.push(*token);
+                        chunk_idx += 1;
+                        if chunk_idx >= forward {
+                            break;
+                        }
+                    }
                 }
             }
 
             // Ring wrap: from beginning to lookup_token
-            if chunk_idx < 5 {
+            if chunk_idx < forward {
                 for (token, _) in self.tokens.range((Unbounded, Excluded(lookup_token))) {
+                    if steps >= max_steps {
+                        break;
+                    }
                     steps += 1;
-                    if matches_chunk(token, signature_chunks[chunk_idx]) {
+                    if params.matches_chunk(token, signature_chunks[chunk_idx]) {
                         found_tokens.push(*token);
                         chunk_idx += 1;
-                        if chunk_idx >= 5 {
+                        if chunk_idx >= forward {
                             break;
                         }
                     }
                 }
             }
 
-            // Search below (backward) for last 5 chunks
-            for (token, _) in self.tokens.range((Unbounded, Excluded(lookup_token))).rev() {
-                steps += 1;
-                if matches_chunk(token, signature_chunks[chunk_idx]) {
-                    found_tokens.push(*token);
-                    chunk_idx += 1;
-                    if chunk_idx >= SIGNATURE_CHUNKS {
+            let forward_steps = steps;
+
+            // Search below (backward) for remaining chunks
+            if chunk_idx < total {
+                for (token, _) in self.tokens.range((Unbounded, Excluded(lookup_token))).rev() {
+                    if steps >= max_steps {
                         break;
                     }
+                    steps += 1;
+                    if params.matches_chunk(token, signature_chunks[chunk_idx]) {
+                        found_tokens.push(*token);
+                        chunk_idx += 1;
+                        if chunk_idx >= total {
+                            break;
+                        }
+                    }
                 }
             }
 
             // Ring wrap: from end backwards to lookup_token
-            if chunk_idx < SIGNATURE_CHUNKS {
+            if chunk_idx < total {
                 for (token, _) in self.tokens.range((Excluded(lookup_token), Unbounded)).rev() {
+                    if steps >= max_steps {
+                        break;
+                    }
                     steps += 1;
-                    if matches_chunk(token, signature_chunks[chunk_idx]) {
+                    if params.matches_chunk(token, signature_chunks[chunk_idx]) {
                         found_tokens.push(*token);
                         chunk_idx += 1;
-                        if chunk_idx >= SIGNATURE_CHUNKS {
+                        if chunk_idx >= total {
                             break;
                         }
                     }
@@ -1350,12 +2181,27 @@ mod tests {
             }
 
             SignatureSearchResult {
-                complete: chunk_idx == SIGNATURE_CHUNKS,
+                complete: chunk_idx == total,
+                chunks_matched: found_tokens.len(),
                 tokens: found_tokens,
                 steps,
+                forward_steps,
+                backward_steps: steps - forward_steps,
             }
         }
 
+        fn range_after(
+            &self,
+            token: &TokenId,
+        ) -> Box<dyn Iterator<Item = (TokenId, BlockTime)> + '_> {
+            use std::ops::Bound::{Excluded, Unbounded};
+            Box::new(
+                self.tokens
+                    .range((Excluded(*token), Unbounded))
+                    .map(|(token, block_time)| (*token, *block_time)),
+            )
+        }
+
         fn len(&self) -> usize {
             self.tokens.len()
         }
@@ -1376,6 +2222,135 @@ mod tests {
         let _ = proof.generate_signature(&backend, &100, &42);
     }
 
+    #[test]
+    fn test_get_many_preserves_input_order() {
+        use crate::ec_interface::GENESIS_BLOCK_ID;
+        let mut backend = TestBackend::new();
+        backend.set(&100, &1, &GENESIS_BLOCK_ID, 10);
+        backend.set(&300, &3, &GENESIS_BLOCK_ID, 30);
+
+        let blocks: Vec<_> = backend
+            .get_many(&[300, 200, 100, 300])
+            .into_iter()
+            .map(|block_time| block_time.map(|b| b.block))
+            .collect();
+        assert_eq!(blocks, vec![Some(3), None, Some(1), Some(3)]);
+        assert!(backend.get_many(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_import_copies_every_mapping_into_mem_tokens() {
+        use crate::ec_memory_backend::MemTokens;
+
+        let mut backend = TestBackend::new();
+        backend.set(&TokenId::MIN, &1, &0, 10);
+        backend.set(&100, &2, &1, 20);
+        backend.set(&TokenId::MAX, &3, &2, 30);
+
+        let loaded = MemTokens::from_backend(&backend);
+        let mut imported = MemTokens::new();
+        imported.import_from(&backend);
+
+        for mem in [&loaded, &imported] {
+            assert_eq!(TokenStorageBackend::len(mem), 3);
+            for (token, block_time) in &backend.tokens {
+                let copied = TokenStorageBackend::lookup(mem, token).unwrap();
+                assert_eq!(
+                    (copied.block, copied.parent, copied.time),
+                    (block_time.block, block_time.parent, block_time.time)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_generate_signature_matches_per_token_lookups() {
+        use crate::ec_interface::GENESIS_BLOCK_ID;
+        use rand::{Rng, SeedableRng};
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(48);
+        let mut backend = TestBackend::new();
+        for _ in 0..50_000 {
+            backend.set(&rng.gen(), &rng.gen(), &GENESIS_BLOCK_ID, 1);
+        }
+        let proof = ProofOfStorage::new();
+
+        let mut checked = 0;
+        for &token in backend.tokens.keys().step_by(5_000) {
+            let Some(signature) = proof.generate_signature(&backend, &token, &7) else {
+                continue;
+            };
+            // Reference assembly: one lookup per found token, as before get_many
+            let answer_block = backend.lookup(&token).unwrap().block;
+            let chunks = proof.signature_for(&token, &answer_block, &7);
+            let found = proof.search_by_signature(&backend, &token, &chunks, SIGNATURE_CHUNKS / 2);
+            for (i, token_id) in found.tokens.iter().enumerate() {
+                assert_eq!(signature.signature[i].id, *token_id);
+                assert_eq!(
+                    signature.signature[i].block,
+                    backend.lookup(token_id).unwrap().block
+                );
+            }
+            checked += 1;
+        }
+        assert!(checked > 0, "expected at least one complete signature");
+    }
+
+    #[test]
+    fn test_partial_signature_reports_found_chunks() {
+        use crate::ec_interface::GENESIS_BLOCK_ID;
+
+        let my_peer_id = 999u64;
+        let challenge_token = 100_000u64;
+        let response_block_id = 42u64;
+        let mut backend = TestBackend::new();
+        backend.set(&challenge_token, &response_block_id, &GENESIS_BLOCK_ID, 100);
+
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&my_peer_id.to_le_bytes());
+        hasher.update(&challenge_token.to_le_bytes());
+        hasher.update(&response_block_id.to_le_bytes());
+        let expected_chunks =
+            extract_signature_chunks_from_256bit_hash(hasher.finalize().as_bytes());
+
+        // Store tokens for chunks 0-6 only (0-4 above, 5-6 below the challenge)
+        let mut stored = Vec::new();
+        for (i, &chunk) in expected_chunks.iter().enumerate().take(7) {
+            let base_id = if i < 5 {
+                challenge_token + 2000 + (i as u64 * 2000)
+            } else {
+                challenge_token - 2000 - ((i - 5) as u64 * 2000)
+            };
+            let token = (base_id & !0x3FF) | chunk as u64;
+            backend.set(&token, &(200 + i as u64), &GENESIS_BLOCK_ID, 100);
+            stored.push(token);
+        }
+
+        let proof = ProofOfStorage::new();
+        assert!(proof
+            .generate_signature(&backend, &challenge_token, &my_peer_id)
+            .is_none());
+
+        let partial = proof
+            .generate_partial_signature(&backend, &challenge_token, &my_peer_id)
+            .unwrap();
+        assert_eq!(partial.found, 7);
+        assert_eq!(partial.signature.answer.id, challenge_token);
+        assert_eq!(partial.signature.answer.block, response_block_id);
+        for (i, &token) in stored.iter().enumerate() {
+            assert_eq!(partial.signature.signature[i].id, token);
+            assert_eq!(partial.signature.signature[i].block, 200 + i as u64);
+        }
+        assert!(partial.signature.signature[7..]
+            .iter()
+            .all(|m| m.id == 0 && m.block == 0));
+
+        // Missing challenge token: nothing to prove
+        assert!(proof
+            .generate_partial_signature(&backend, &1, &my_peer_id)
+            .is_none());
+    }
+
     #[test]
     fn test_signature_generation_nonexistent_token() {
         let backend = TestBackend::new();
@@ -1391,7 +2366,7 @@ mod tests {
         let proof = ProofOfStorage::new();
 
         let signature = [0u16; SIGNATURE_CHUNKS];
-        let result = proof.search_by_signature(&backend, &1000, &signature);
+        let result = proof.search_by_signature(&backend, &1000, &signature, SIGNATURE_CHUNKS / 2);
 
         assert_eq!(result.tokens.len(), 0);
         assert!(!result.complete);
@@ -1409,13 +2384,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_signature_for_peer_changes_all_chunks() {
+        // Inputs differing only in the peer id must give independent chunks
+        // (all ten chunks come from the full 256-bit Blake3 output)
+        let proof = ProofOfStorage::new();
+        let token = 123_456u64;
+        let block = 789u64;
+
+        for peer in 1..50u64 {
+            let sig_a = proof.signature_for(&token, &block, &peer);
+            let sig_b = proof.signature_for(&token, &block, &(peer + 1));
+
+            let overlap = sig_a
+                .iter()
+                .zip(sig_b.iter())
+                .filter(|(a, b)| a == b)
+                .count();
+            assert!(
+                overlap <= 2,
+                "Peers {} and {} share {} chunk positions",
+                peer,
+                peer + 1,
+                overlap
+            );
+        }
+    }
+
     #[test]
     fn test_count_common_mappings() {
         use crate::ec_interface::TokenMapping;
 
         let sig1 = TokenSignature {
             answer: TokenMapping { id: 1, block: 100 },
-            signature: [
+            signature: padded([
                 TokenMapping { id: 10, block: 1 },
                 TokenMapping { id: 20, block: 2 },
                 TokenMapping { id: 30, block: 3 },
@@ -1426,12 +2428,12 @@ mod tests {
                 TokenMapping { id: 80, block: 8 },
                 TokenMapping { id: 90, block: 9 },
                 TokenMapping { id: 100, block: 10 },
-            ],
+            ]),
         };
 
         let sig2 = TokenSignature {
             answer: TokenMapping { id: 1, block: 100 },
-            signature: [
+            signature: padded([
                 TokenMapping { id: 10, block: 1 }, // match
                 TokenMapping { id: 20, block: 2 }, // match
                 TokenMapping { id: 30, block: 3 }, // match
@@ -1463,10 +2465,10 @@ mod tests {
                     id: 999,
                     block: 999,
                 },
-            ],
+            ]),
         };
 
-        let count = count_common_mappings(&sig1, &sig2);
+        let count = count_common_mappings(&sig1, &sig2, SIGNATURE_CHUNKS);
         assert_eq!(count, 3, "Should find 3 common mappings");
     }
 
@@ -1494,25 +2496,25 @@ mod tests {
                     id: 999,
                     block: 999,
                 },
-                signature: perfect_mappings,
+                signature: padded(perfect_mappings),
             },
             TokenSignature {
                 answer: TokenMapping {
                     id: 999,
                     block: 999,
                 },
-                signature: perfect_mappings,
+                signature: padded(perfect_mappings),
             },
             TokenSignature {
                 answer: TokenMapping {
                     id: 999,
                     block: 999,
                 },
-                signature: perfect_mappings,
+                signature: padded(perfect_mappings),
             },
         ];
 
-        let clusters = find_all_consensus_clusters(&signatures, 10, 2);
+        let clusters = find_all_consensus_clusters(&signatures, SIGNATURE_CHUNKS, 10, 2);
 
         assert!(!clusters.is_empty());
         let cluster = &clusters[0];
@@ -1588,26 +2590,26 @@ mod tests {
                     id: 999,
                     block: 999,
                 },
-                signature: common,
+                signature: padded(common),
             },
             TokenSignature {
                 answer: TokenMapping {
                     id: 999,
                     block: 999,
                 },
-                signature: common,
+                signature: padded(common),
             },
             TokenSignature {
                 answer: TokenMapping {
                     id: 999,
                     block: 999,
                 },
-                signature: outlier,
+                signature: padded(outlier),
             },
         ];
 
         // With threshold of 8, only sig1 and sig2 should form a cluster
-        let clusters = find_all_consensus_clusters(&signatures, 8, 2);
+        let clusters = find_all_consensus_clusters(&signatures, SIGNATURE_CHUNKS, 8, 2);
 
         assert!(!clusters.is_empty());
         let cluster = &clusters[0];
@@ -1657,40 +2659,40 @@ mod tests {
                     id: 999,
                     block: 999,
                 },
-                signature: group_a_mappings,
+                signature: padded(group_a_mappings),
             },
             TokenSignature {
                 answer: TokenMapping {
                     id: 999,
                     block: 999,
                 },
-                signature: group_a_mappings,
+                signature: padded(group_a_mappings),
             },
             TokenSignature {
                 answer: TokenMapping {
                     id: 999,
                     block: 999,
                 },
-                signature: group_b_mappings,
+                signature: padded(group_b_mappings),
             },
             TokenSignature {
                 answer: TokenMapping {
                     id: 999,
                     block: 999,
                 },
-                signature: group_b_mappings,
+                signature: padded(group_b_mappings),
             },
             TokenSignature {
                 answer: TokenMapping {
                     id: 999,
                     block: 999,
                 },
-                signature: group_b_mappings,
+                signature: padded(group_b_mappings),
             },
         ];
 
         // With threshold of 8, should find the larger group (Group B with 3 members)
-        let clusters = find_all_consensus_clusters(&signatures, 8, 2);
+        let clusters = find_all_consensus_clusters(&signatures, SIGNATURE_CHUNKS, 8, 2);
 
         assert!(!clusters.is_empty());
         let cluster = &clusters[0];
@@ -1718,7 +2720,7 @@ mod tests {
                     id: 999,
                     block: 999,
                 },
-                signature: [
+                signature: padded([
                     TokenMapping { id: 1, block: 1 },
                     TokenMapping { id: 2, block: 2 },
                     TokenMapping { id: 10, block: 10 },
@@ -1729,14 +2731,14 @@ mod tests {
                     TokenMapping { id: 15, block: 15 },
                     TokenMapping { id: 16, block: 16 },
                     TokenMapping { id: 17, block: 17 },
-                ],
+                ]),
             },
             TokenSignature {
                 answer: TokenMapping {
                     id: 999,
                     block: 999,
                 },
-                signature: [
+                signature: padded([
                     TokenMapping { id: 1, block: 1 },
                     TokenMapping { id: 2, block: 2 },
                     TokenMapping { id: 20, block: 20 },
@@ -1747,12 +2749,12 @@ mod tests {
                     TokenMapping { id: 25, block: 25 },
                     TokenMapping { id: 26, block: 26 },
                     TokenMapping { id: 27, block: 27 },
-                ],
+                ]),
             },
         ];
 
         // With very high threshold, no cluster should be found
-        let clusters = find_all_consensus_clusters(&signatures, 9, 2);
+        let clusters = find_all_consensus_clusters(&signatures, SIGNATURE_CHUNKS, 9, 2);
 
         // Should find nothing above threshold 9 (they only agree on 2/10)
         // min_size=2 means single-signature "clusters" are filtered out
@@ -1762,7 +2764,7 @@ mod tests {
     #[test]
     fn test_consensus_cluster_empty_input() {
         let signatures: Vec<TokenSignature> = vec![];
-        let clusters = find_all_consensus_clusters(&signatures, 5, 2);
+        let clusters = find_all_consensus_clusters(&signatures, SIGNATURE_CHUNKS, 5, 2);
         assert!(clusters.is_empty());
     }
 
@@ -1775,17 +2777,273 @@ mod tests {
                 id: 999,
                 block: 999,
             },
-            signature: [TokenMapping { id: 1, block: 1 }; SIGNATURE_CHUNKS],
+            signature: padded([TokenMapping { id: 1, block: 1 }; SIGNATURE_CHUNKS]),
         }];
 
         // Use min_size=1 to allow single-signature clusters
-        let clusters = find_all_consensus_clusters(&signatures, 5, 1);
+        let clusters = find_all_consensus_clusters(&signatures, SIGNATURE_CHUNKS, 5, 1);
         assert!(!clusters.is_empty());
         let cluster = &clusters[0];
         assert_eq!(cluster.members.len(), 1);
         assert_eq!(cluster.members[0], 0);
     }
 
+    /// Reference clustering: scan all 2^n subsets, then drop strict subsets
+    fn brute_force_consensus_clusters(
+        signatures: &[TokenSignature],
+        chunks: usize,
+        min_threshold: usize,
+        min_size: usize,
+    ) -> Vec<ConsensusCluster> {
+        let n = signatures.len();
+        let mut all_clusters = Vec::new();
+
+        for mask in 1..(1u32 << n) {
+            let members: Vec<usize> = (0..n).filter(|&i| (mask & (1 << i)) != 0).collect();
+            if members.len() < min_size {
+                continue;
+            }
+
+            let mut min_agreement = chunks;
+            let mut total_agreement = 0usize;
+            let mut pair_count = 0;
+            let mut valid = true;
+            for i in 0..members.len() {
+                for j in (i + 1)..members.len() {
+                    let agree = count_common_mappings(
+                        &signatures[members[i]],
+                        &signatures[members[j]],
+                        chunks,
+                    );
+                    if agree < min_threshold {
+                        valid = false;
+                    }
+                    min_agreement = min_agreement.min(agree);
+                    total_agreement += agree;
+                    pair_count += 1;
+                }
+            }
+            if !valid {
+                continue;
+            }
+
+            let avg_agreement = if pair_count > 0 {
+                total_agreement as f64 / pair_count as f64
+            } else {
+                chunks as f64
+            };
+            all_clusters.push(ConsensusCluster {
+                members,
+                min_agreement,
+                avg_agreement,
+            });
+        }
+
+        let mut maximal: Vec<ConsensusCluster> = all_clusters
+            .iter()
+            .filter(|candidate| {
+                !all_clusters.iter().any(|other| {
+                    candidate.members.len() < other.members.len()
+                        && candidate.members.iter().all(|m| other.members.contains(m))
+                })
+            })
+            .cloned()
+            .collect();
+
+        // Stable sort keeps ascending mask order among ties
+        maximal.sort_by(|a, b| match b.members.len().cmp(&a.members.len()) {
+            std::cmp::Ordering::Equal => b
+                .avg_agreement
+                .partial_cmp(&a.avg_agreement)
+                .unwrap_or(std::cmp::Ordering::Equal),
+            other => other,
+        });
+        maximal
+    }
+
+    #[test]
+    fn test_consensus_clusters_match_brute_force() {
+        use rand::{Rng, SeedableRng};
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0xC1A5);
+
+        for n in 1..=12usize {
+            for _ in 0..20 {
+                // Each slot picks one of three candidate mappings, giving a
+                // wide spread of pairwise agreement
+                let signatures: Vec<TokenSignature> = (0..n)
+                    .map(|_| {
+                        let mut mappings = [(0, 0); SIGNATURE_CHUNKS];
+                        for (slot, mapping) in mappings.iter_mut().enumerate() {
+                            let choice = rng.gen_range(0..3u64);
+                            *mapping = (slot as u64 * 10 + choice, 1);
+                        }
+                        create_test_signature(mappings)
+                    })
+                    .collect();
+
+                let min_threshold = rng.gen_range(2..=8);
+                let min_size = rng.gen_range(1..=3);
+
+                let fast = find_all_consensus_clusters(
+                    &signatures,
+                    SIGNATURE_CHUNKS,
+                    min_threshold,
+                    min_size,
+                );
+                let reference = brute_force_consensus_clusters(
+                    &signatures,
+                    SIGNATURE_CHUNKS,
+                    min_threshold,
+                    min_size,
+                );
+                assert_eq!(
+                    fast, reference,
+                    "n={} threshold={} min_size={}",
+                    n, min_threshold, min_size
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_agreement_matrices_match_serial_fill() {
+        use rand::{Rng, SeedableRng};
+
+        // Runs against whichever fill is compiled in (serial or `rayon`)
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0xA6EE);
+        let mut weights = [1.0; TOKENS_SIGNATURE_SIZE];
+        for weight in weights.iter_mut() {
+            *weight = rng.gen_range(0.5..3.0);
+        }
+
+        for n in [2usize, 16, 24, 40] {
+            let signatures: Vec<TokenSignature> = (0..n)
+                .map(|_| {
+                    let mut mappings = [(0, 0); SIGNATURE_CHUNKS];
+                    for (slot, mapping) in mappings.iter_mut().enumerate() {
+                        *mapping = (slot as u64 * 10 + rng.gen_range(0..3u64), 1);
+                    }
+                    create_test_signature(mappings)
+                })
+                .collect();
+
+            for weights in [None, Some(&weights)] {
+                let full_score = match weights {
+                    Some(weights) => weights[..SIGNATURE_CHUNKS].iter().sum(),
+                    None => SIGNATURE_CHUNKS as f64,
+                };
+                let (agreement, score) =
+                    agreement_matrices(&signatures, SIGNATURE_CHUNKS, weights, full_score);
+
+                for i in 0..n {
+                    for j in 0..n {
+                        let (common, weighted) = if i == j {
+                            (SIGNATURE_CHUNKS, full_score)
+                        } else {
+                            let (a, b) = (&signatures[i], &signatures[j]);
+                            let common = count_common_mappings(a, b, SIGNATURE_CHUNKS);
+                            let weighted = weights.map_or(common as f64, |w| {
+                                weighted_common_mappings(a, b, SIGNATURE_CHUNKS, w)
+                            });
+                            (common, weighted)
+                        };
+                        assert_eq!(agreement[i][j], common, "n={} ({}, {})", n, i, j);
+                        assert_eq!(score[i][j], weighted, "n={} ({}, {})", n, i, j);
+                    }
+                }
+
+                // Same input, same clusters on every run
+                let clusters = find_all_consensus_clusters_weighted(
+                    &signatures,
+                    SIGNATURE_CHUNKS,
+                    weights,
+                    6.0,
+                    2,
+                );
+                let again = find_all_consensus_clusters_weighted(
+                    &signatures,
+                    SIGNATURE_CHUNKS,
+                    weights,
+                    6.0,
+                    2,
+                );
+                assert_eq!(clusters, again);
+            }
+        }
+    }
+
+    #[test]
+    fn test_weighted_clustering_flips_strongest_cluster() {
+        // Group A agrees on positions 0..8, group B on positions 1..10;
+        // the two groups share nothing
+        let mut a1 = [(0, 0); SIGNATURE_CHUNKS];
+        let mut a2 = [(0, 0); SIGNATURE_CHUNKS];
+        let mut b1 = [(0, 0); SIGNATURE_CHUNKS];
+        let mut b2 = [(0, 0); SIGNATURE_CHUNKS];
+        for i in 0..SIGNATURE_CHUNKS as u64 {
+            let slot = i as usize;
+            a1[slot] = (100 + i, 1);
+            a2[slot] = if i < 8 { (100 + i, 1) } else { (150 + i, 1) };
+            b1[slot] = (200 + i, 2);
+            b2[slot] = if i >= 1 { (200 + i, 2) } else { (250 + i, 2) };
+        }
+        let signatures = vec![
+            create_test_signature(a1),
+            create_test_signature(a2),
+            create_test_signature(b1),
+            create_test_signature(b2),
+        ];
+
+        // Unweighted: B (9 common) beats A (8 common)
+        let plain = find_all_consensus_clusters(&signatures, SIGNATURE_CHUNKS, 8, 2);
+        assert_eq!(plain[0].members, vec![2, 3]);
+        assert_eq!(plain[1].members, vec![0, 1]);
+
+        // None weights are identical to the unweighted search
+        let unweighted =
+            find_all_consensus_clusters_weighted(&signatures, SIGNATURE_CHUNKS, None, 8.0, 2);
+        assert_eq!(unweighted, plain);
+
+        // Position 0 is highly discriminating: A scores 5 + 7 = 12, B scores 9
+        let mut weights = [1.0; TOKENS_SIGNATURE_SIZE];
+        weights[0] = 5.0;
+        let weighted = find_all_consensus_clusters_weighted(
+            &signatures,
+            SIGNATURE_CHUNKS,
+            Some(&weights),
+            8.0,
+            2,
+        );
+        assert_eq!(weighted[0].members, vec![0, 1]);
+        assert_eq!(weighted[0].min_agreement, 8);
+        assert_eq!(weighted[0].avg_agreement, 12.0);
+        assert_eq!(weighted[1].members, vec![2, 3]);
+        assert_eq!(weighted[1].avg_agreement, 9.0);
+    }
+
+    #[test]
+    fn test_consensus_clusters_twenty_channels() {
+        // Two disjoint groups of ten identical signatures: the subset scan
+        // would visit 2^20 masks, the clique search only the two cliques
+        let group_a = create_test_signature([(1, 1); SIGNATURE_CHUNKS]);
+        let group_b = create_test_signature([(2, 2); SIGNATURE_CHUNKS]);
+        let signatures: Vec<TokenSignature> = (0..20)
+            .map(|i| {
+                if i % 2 == 0 {
+                    group_a.clone()
+                } else {
+                    group_b.clone()
+                }
+            })
+            .collect();
+
+        let clusters = find_all_consensus_clusters(&signatures, SIGNATURE_CHUNKS, 8, 2);
+        assert_eq!(clusters.len(), 2);
+        assert_eq!(clusters[0].members, (0..20).step_by(2).collect::<Vec<_>>());
+        assert_eq!(clusters[1].members, (1..20).step_by(2).collect::<Vec<_>>());
+    }
+
     // ========================================================================
     // Peer Election Tests
     // ========================================================================
@@ -1815,6 +3073,32 @@ mod tests {
         assert!(dist > mid - 100 && dist < mid + 100);
     }
 
+    #[test]
+    fn test_ring_distance_generic_custom_width() {
+        // A 16-bit ring id exercises the generic path with a different width
+        #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+        struct SmallId(u16);
+
+        impl RingId for SmallId {
+            fn wrapping_sub(self, other: Self) -> Self {
+                SmallId(self.0.wrapping_sub(other.0))
+            }
+        }
+
+        assert_eq!(
+            ring_distance_generic(SmallId(100), SmallId(150)),
+            SmallId(50)
+        );
+        assert_eq!(
+            ring_distance_generic(SmallId(10), SmallId(u16::MAX - 5)),
+            SmallId(16)
+        );
+        assert_eq!(
+            ring_distance_generic(10u64, u64::MAX - 5),
+            ring_distance(10, u64::MAX - 5)
+        );
+    }
+
     #[test]
     fn test_ring_distance_self() {
         assert_eq!(ring_distance(42, 42), 0);
@@ -1828,7 +3112,7 @@ mod tests {
     fn create_test_signature(mappings: [(TokenId, BlockId); SIGNATURE_CHUNKS]) -> TokenSignature {
         use crate::ec_interface::TokenMapping;
 
-        let mut signature = [TokenMapping { id: 0, block: 0 }; SIGNATURE_CHUNKS];
+        let mut signature = [TokenMapping { id: 0, block: 0 }; TOKENS_SIGNATURE_SIZE];
         for (i, (id, block)) in mappings.iter().enumerate() {
             signature[i] = TokenMapping {
                 id: *id,
@@ -1863,6 +3147,434 @@ mod tests {
         assert_eq!(election.valid_response_count(), 0);
     }
 
+    #[test]
+    fn test_election_with_secret_deterministic_tickets() {
+        let secret = [7u8; 32];
+        let mut election_a =
+            PeerElection::with_secret(1000, 999, ElectionConfig::default(), secret);
+        let mut election_b =
+            PeerElection::with_secret(1000, 999, ElectionConfig::default(), secret);
+
+        let ticket_a = election_a.create_channel(100, 100).unwrap();
+        let ticket_b = election_b.create_channel(100, 250).unwrap();
+        assert_eq!(ticket_a, ticket_b);
+
+        // A different secret gives a different ticket for the same first hop
+        let mut election_c =
+            PeerElection::with_secret(1000, 999, ElectionConfig::default(), [8u8; 32]);
+        assert_ne!(election_c.create_channel(100, 100).unwrap(), ticket_a);
+    }
+
+    #[test]
+    fn test_election_with_secret_deterministic_referral_order() {
+        // Same secret and ticket pick the same suggestion from a referral
+        let picks: Vec<PeerId> = (0..2)
+            .map(|_| {
+                let mut election =
+                    PeerElection::with_secret(1000, 999, ElectionConfig::default(), [7u8; 32]);
+                let ticket = election.create_channel(100, 100).unwrap();
+                election
+                    .handle_referral(ticket, 1000, [200, 300], 100)
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(picks[0], picks[1]);
+    }
+
+    #[test]
+    fn test_election_serde_roundtrip() {
+        let my_peer_id = 999u64;
+        let challenge_token = 10_000_000u64;
+        let params = SignatureParams::default();
+        let backend = backend_with_signature(&params, challenge_token, 42, my_peer_id);
+        let signature = ProofOfStorage::new()
+            .generate_signature(&backend, &challenge_token, &my_peer_id)
+            .unwrap();
+
+        let mut election = PeerElection::with_secret(
+            challenge_token,
+            my_peer_id,
+            ElectionConfig::default(),
+            [3u8; 32],
+        );
+        let ticket1 = election.create_channel(100, 100).unwrap();
+        let ticket2 = election.create_channel(200, 105).unwrap();
+        let ticket3 = election.create_channel(300, 110).unwrap();
+        election
+            .handle_answer(ticket1, &signature.answer, &signature.signature, 101, 200)
+            .unwrap();
+
+        let snapshot = serde_yaml::to_string(&election).unwrap();
+        let mut restored: PeerElection = serde_yaml::from_str(&snapshot).unwrap();
+
+        // Responders are rebuilt from the channels: 101 can't answer again
+        assert_eq!(
+            restored.handle_answer(ticket3, &signature.answer, &signature.signature, 101, 205),
+            Err(ElectionError::PeerAlreadyParticipating)
+        );
+
+        assert_eq!(restored.channel_count(), election.channel_count());
+        assert_eq!(
+            restored.valid_response_count(),
+            election.valid_response_count()
+        );
+        assert_eq!(
+            restored.get_participating_peers(),
+            election.get_participating_peers()
+        );
+        assert_eq!(restored.check_for_winner(), election.check_for_winner());
+
+        // Existing tickets and first-hop bookkeeping survive the restore
+        assert_eq!(
+            restored.create_channel(200, 120),
+            Err(ElectionError::ChannelAlreadyExists)
+        );
+        assert_eq!(
+            restored.handle_answer(ticket2, &signature.answer, &signature.signature, 201, 210),
+            election.handle_answer(ticket2, &signature.answer, &signature.signature, 201, 210)
+        );
+        assert_eq!(restored.check_for_winner(), election.check_for_winner());
+    }
+
+    #[test]
+    fn test_election_records_times_and_expires_channels() {
+        let my_peer_id = 999u64;
+        let challenge_token = 10_000_000u64;
+        let backend =
+            backend_with_signature(&SignatureParams::default(), challenge_token, 42, my_peer_id);
+        let signature = ProofOfStorage::new()
+            .generate_signature(&backend, &challenge_token, &my_peer_id)
+            .unwrap();
+
+        let mut election =
+            PeerElection::new(challenge_token, my_peer_id, ElectionConfig::default());
+        let answered = election.create_channel(100, 100).unwrap();
+        let late = election.create_channel(200, 110).unwrap();
+        let fresh = election.create_channel(300, 125).unwrap();
+
+        election
+            .handle_answer(answered, &signature.answer, &signature.signature, 101, 115)
+            .unwrap();
+        assert_eq!(election.channels[&late].sent_at, 110);
+        assert_eq!(
+            election.channels[&answered]
+                .response
+                .as_ref()
+                .unwrap()
+                .received_at,
+            115
+        );
+
+        // Only the pending channel older than the timeout expires
+        assert_eq!(election.expire_stale_channels(130, 20), 1);
+        assert_eq!(election.channels[&late].state, ChannelState::Expired);
+        assert_eq!(election.channels[&fresh].state, ChannelState::Pending);
+        assert_eq!(election.channels[&answered].state, ChannelState::Responded);
+
+        assert_eq!(
+            election.handle_answer(late, &signature.answer, &signature.signature, 201, 131),
+            Err(ElectionError::ChannelExpired)
+        );
+        assert_eq!(
+            election.handle_referral(late, challenge_token, [400, 500], 201),
+            Err(ElectionError::ChannelExpired)
+        );
+        assert_eq!(election.expire_stale_channels(130, 20), 0);
+    }
+
+    #[test]
+    fn test_election_custom_winner_selector() {
+        let my_peer_id = 999u64;
+        let challenge_token = 10_000_000u64;
+        let backend =
+            backend_with_signature(&SignatureParams::default(), challenge_token, 42, my_peer_id);
+        let signature = ProofOfStorage::new()
+            .generate_signature(&backend, &challenge_token, &my_peer_id)
+            .unwrap();
+
+        let build_election = || {
+            let mut election = PeerElection::with_secret(
+                challenge_token,
+                my_peer_id,
+                ElectionConfig::default(),
+                [1u8; 32],
+            );
+            for (first_hop, responder) in [(100, 10_000_001), (200, 10_000_500), (300, 10_000_900)]
+            {
+                let ticket = election.create_channel(first_hop, 100).unwrap();
+                election
+                    .handle_answer(
+                        ticket,
+                        &signature.answer,
+                        &signature.signature,
+                        responder,
+                        110,
+                    )
+                    .unwrap();
+            }
+            election
+        };
+
+        // Default: closest to the challenge token by ring distance
+        let election = build_election();
+        match election.check_for_winner() {
+            WinnerResult::Single { winner, .. } => assert_eq!(winner, 10_000_001),
+            other => panic!("Expected single winner, got {:?}", other),
+        }
+
+        // Custom: numerically largest peer id
+        let mut election = build_election();
+        election.set_winner_selector(Box::new(
+            |_token: TokenId, peer: PeerId, _cluster: &ConsensusCluster| peer,
+        ));
+        match election.check_for_winner() {
+            WinnerResult::Single { winner, .. } => assert_eq!(winner, 10_000_900),
+            other => panic!("Expected single winner, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_check_for_winner_reuses_result_until_responses_change() {
+        let my_peer_id = 999u64;
+        let challenge_token = 10_000_000u64;
+        let backend =
+            backend_with_signature(&SignatureParams::default(), challenge_token, 42, my_peer_id);
+        let signature = ProofOfStorage::new()
+            .generate_signature(&backend, &challenge_token, &my_peer_id)
+            .unwrap();
+        let computations = |election: &PeerElection| {
+            election
+                .winner_computations
+                .load(std::sync::atomic::Ordering::Relaxed)
+        };
+
+        let mut election = PeerElection::with_secret(
+            challenge_token,
+            my_peer_id,
+            ElectionConfig::default(),
+            [2; 32],
+        );
+        let answer = |election: &mut PeerElection, first_hop, responder| {
+            let ticket = election.create_channel(first_hop, 100).unwrap();
+            election
+                .handle_answer(
+                    ticket,
+                    &signature.answer,
+                    &signature.signature,
+                    responder,
+                    110,
+                )
+                .unwrap();
+        };
+        answer(&mut election, 100, 10_000_001);
+        answer(&mut election, 200, 10_000_500);
+        answer(&mut election, 300, 10_000_900);
+
+        // No new response in between: the clustering runs once
+        let first = election.check_for_winner();
+        assert!(matches!(first, WinnerResult::Single { .. }));
+        assert_eq!(election.check_for_winner(), first);
+        assert_eq!(computations(&election), 1);
+
+        // Channels opening or expiring don't change the responses
+        election.create_channel(400, 120).unwrap();
+        election.expire_stale_channels(200, 20);
+        assert_eq!(election.check_for_winner(), first);
+        assert_eq!(computations(&election), 1);
+
+        // A new response invalidates the cache
+        answer(&mut election, 500, 10_000_700);
+        election.check_for_winner();
+        election.check_for_winner();
+        assert_eq!(computations(&election), 2);
+    }
+
+    #[test]
+    fn test_check_for_winner_reports_insufficient_responses() {
+        let my_peer_id = 999u64;
+        let challenge_token = 10_000_000u64;
+        let backend =
+            backend_with_signature(&SignatureParams::default(), challenge_token, 42, my_peer_id);
+        let signature = ProofOfStorage::new()
+            .generate_signature(&backend, &challenge_token, &my_peer_id)
+            .unwrap();
+        let config = ElectionConfig {
+            min_cluster_size: 3,
+            ..Default::default()
+        };
+        let mut election = PeerElection::new(challenge_token, my_peer_id, config);
+
+        for (first_hop, responder) in [(100, 10_000_001), (200, 10_000_500)] {
+            let ticket = election.create_channel(first_hop, 100).unwrap();
+            election
+                .handle_answer(
+                    ticket,
+                    &signature.answer,
+                    &signature.signature,
+                    responder,
+                    110,
+                )
+                .unwrap();
+        }
+
+        assert_eq!(
+            election.check_for_winner(),
+            WinnerResult::InsufficientResponses { have: 2, need: 3 }
+        );
+    }
+
+    #[test]
+    fn test_check_for_winner_reports_no_agreement() {
+        let my_peer_id = 999u64;
+        let challenge_token = 10_000_000u64;
+        let mut election =
+            PeerElection::new(challenge_token, my_peer_id, ElectionConfig::default());
+
+        // Each responder vouches for a different block, so no two signatures agree
+        for (i, block) in [42u64, 43, 44].into_iter().enumerate() {
+            let backend = backend_with_signature(
+                &SignatureParams::default(),
+                challenge_token,
+                block,
+                my_peer_id,
+            );
+            let signature = ProofOfStorage::new()
+                .generate_signature(&backend, &challenge_token, &my_peer_id)
+                .unwrap();
+            let ticket = election.create_channel(100 * (i as u64 + 1), 100).unwrap();
+            election
+                .handle_answer(
+                    ticket,
+                    &signature.answer,
+                    &signature.signature,
+                    10_000_001 + i as u64,
+                    110,
+                )
+                .unwrap();
+        }
+
+        assert_eq!(
+            election.check_for_winner(),
+            WinnerResult::NoAgreement {
+                responses: 3,
+                best_cluster_size: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_equal_strength_clusters_ordered_by_member_peer_ids() {
+        let my_peer_id = 999u64;
+        let challenge_token = 10_000_000u64;
+        let signature_for = |block| {
+            let backend = backend_with_signature(
+                &SignatureParams::default(),
+                challenge_token,
+                block,
+                my_peer_id,
+            );
+            ProofOfStorage::new()
+                .generate_signature(&backend, &challenge_token, &my_peer_id)
+                .unwrap()
+        };
+        let (sig_a, sig_b) = (signature_for(42), signature_for(43));
+
+        // Two pairs with identical agreement; the secret only changes the tickets
+        for secret in 0..8u8 {
+            let mut election = PeerElection::with_secret(
+                challenge_token,
+                my_peer_id,
+                ElectionConfig::default(),
+                [secret; 32],
+            );
+            let answers = [
+                (10_000_003, &sig_a),
+                (10_000_001, &sig_b),
+                (10_000_004, &sig_a),
+                (10_000_002, &sig_b),
+            ];
+            for (i, (responder, signature)) in answers.into_iter().enumerate() {
+                let ticket = election.create_channel(100 * (i as u64 + 1), 100).unwrap();
+                election
+                    .handle_answer(
+                        ticket,
+                        &signature.answer,
+                        &signature.signature,
+                        responder,
+                        110,
+                    )
+                    .unwrap();
+            }
+
+            let WinnerResult::SplitBrain {
+                signatures1,
+                signatures2,
+                ..
+            } = election.check_for_winner()
+            else {
+                panic!("expected a split brain with secret {secret}");
+            };
+            let mut first: Vec<PeerId> = signatures1.iter().map(|(peer, _)| *peer).collect();
+            let mut second: Vec<PeerId> = signatures2.iter().map(|(peer, _)| *peer).collect();
+            first.sort_unstable();
+            second.sort_unstable();
+            assert_eq!(first, vec![10_000_001, 10_000_002], "secret {secret}");
+            assert_eq!(second, vec![10_000_003, 10_000_004], "secret {secret}");
+        }
+    }
+
+    #[test]
+    fn test_answer_from_already_answered_peer_rejected_before_verification() {
+        let my_peer_id = 999u64;
+        let challenge_token = 10_000_000u64;
+        let backend =
+            backend_with_signature(&SignatureParams::default(), challenge_token, 42, my_peer_id);
+        let signature = ProofOfStorage::new()
+            .generate_signature(&backend, &challenge_token, &my_peer_id)
+            .unwrap();
+        let mut election =
+            PeerElection::new(challenge_token, my_peer_id, ElectionConfig::default());
+
+        let first = election.create_channel(100, 100).unwrap();
+        let second = election.create_channel(200, 100).unwrap();
+        election
+            .handle_answer(
+                first,
+                &signature.answer,
+                &signature.signature,
+                10_000_001,
+                110,
+            )
+            .unwrap();
+
+        // A garbage signature would fail verification, so this error shows it never ran
+        let garbage = create_test_signature([(1, 10); SIGNATURE_CHUNKS]);
+        assert_eq!(
+            election.handle_answer(
+                second,
+                &signature.answer,
+                &garbage.signature,
+                10_000_001,
+                120
+            ),
+            Err(ElectionError::PeerAlreadyParticipating)
+        );
+        assert_eq!(election.channels[&second].state, ChannelState::Pending);
+
+        // A duplicate on the same channel still blocks it
+        assert_eq!(
+            election.handle_answer(
+                first,
+                &signature.answer,
+                &signature.signature,
+                10_000_001,
+                130
+            ),
+            Err(ElectionError::DuplicateResponse)
+        );
+        assert_eq!(election.channels[&first].state, ChannelState::Blocked);
+    }
+
     #[test]
     fn test_election_max_channels_limit() {
         let config = ElectionConfig {
@@ -1971,7 +3683,10 @@ mod tests {
         let election = PeerElection::new(1000, 999, ElectionConfig::default());
         let result = election.check_for_winner();
 
-        assert!(matches!(result, WinnerResult::NoConsensus));
+        assert_eq!(
+            result,
+            WinnerResult::InsufficientResponses { have: 0, need: 2 }
+        );
     }
 
     #[test]
@@ -2117,7 +3832,10 @@ mod tests {
         // Verify the signature structure
         assert_eq!(signature.answer.id, challenge_token);
         assert_eq!(signature.answer.block, response_block_id);
-        assert_eq!(signature.signature.len(), SIGNATURE_CHUNKS);
+        assert_eq!(signature.signature.len(), TOKENS_SIGNATURE_SIZE);
+        assert!(signature.signature[SIGNATURE_CHUNKS..]
+            .iter()
+            .all(|m| m.id == 0 && m.block == 0));
 
         // Now validate using PeerElection's verify_signature (via handle_answer)
         let mut election =
@@ -2150,11 +3868,189 @@ mod tests {
         let ticket2 = election.create_channel(200, 110).unwrap();
         let result = election.handle_answer(ticket2, &signature.answer, &bad_signature, 102, 210);
 
-        // Should fail - signature is invalid
+        // Should fail - signature is invalid, and the first mapping is reported
         assert_eq!(
             result,
-            Err(ElectionError::SignatureVerificationFailed),
+            Err(ElectionError::SignatureChunkMismatch {
+                index: 0,
+                expected: (signature.signature[0].id & 0x3FF) as u16,
+                actual: (bad_signature[0].id & 0x3FF) as u16,
+            }),
             "Corrupted signature should fail verification"
         );
     }
+
+    #[test]
+    fn test_verify_signature_reports_mismatch_index() {
+        let my_peer_id = 999u64;
+        let challenge_token = 10_000_000u64;
+        let backend =
+            backend_with_signature(&SignatureParams::default(), challenge_token, 42, my_peer_id);
+        let signature = ProofOfStorage::new()
+            .generate_signature(&backend, &challenge_token, &my_peer_id)
+            .unwrap();
+
+        let mut election =
+            PeerElection::new(challenge_token, my_peer_id, ElectionConfig::default());
+
+        // Tamper with a single mapping in the backward half
+        let mut bad_signature = signature.signature;
+        bad_signature[7].id ^= 0x155;
+
+        let ticket = election.create_channel(100, 100).unwrap();
+        let result = election.handle_answer(ticket, &signature.answer, &bad_signature, 101, 110);
+        match result {
+            Err(ElectionError::SignatureChunkMismatch {
+                index,
+                expected,
+                actual,
+            }) => {
+                assert_eq!(index, 7);
+                assert_eq!(expected, (signature.signature[7].id & 0x3FF) as u16);
+                assert_eq!(actual, expected ^ 0x155);
+            }
+            other => panic!("Expected chunk mismatch, got {:?}", other),
+        }
+        assert_eq!(election.valid_response_count(), 0);
+    }
+
+    // Build a backend holding a complete signature for (token, block, peer)
+    // under the given params: first half above the token, the rest below
+    fn backend_with_signature(
+        params: &SignatureParams,
+        challenge_token: TokenId,
+        block_id: BlockId,
+        peer: PeerId,
+    ) -> TestBackend {
+        use crate::ec_interface::GENESIS_BLOCK_ID;
+
+        let mut backend = TestBackend::new();
+        backend.set(&challenge_token, &block_id, &GENESIS_BLOCK_ID, 100);
+
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&peer.to_le_bytes());
+        hasher.update(&challenge_token.to_le_bytes());
+        hasher.update(&block_id.to_le_bytes());
+        let chunks = params.extract_chunks(hasher.finalize().as_bytes());
+
+        // Space tokens wider than the chunk mask so chunk bits don't affect ordering
+        let spacing = 1u64 << (params.bits + 1);
+        let forward = params.chunks / 2;
+        for (i, &chunk) in chunks.iter().enumerate() {
+            let base_id = if i < forward {
+                challenge_token + spacing * (i as u64 + 1)
+            } else {
+                challenge_token - spacing * ((i - forward) as u64 + 1)
+            };
+            let token = (base_id & !params.chunk_mask()) | chunk as u64;
+            backend.set(&token, &(200 + i as u64), &GENESIS_BLOCK_ID, 100);
+        }
+        backend
+    }
+
+    fn check_signature_roundtrip(params: SignatureParams) {
+        let my_peer_id = 999u64;
+        let challenge_token = 10_000_000u64;
+        let block_id = 42u64;
+
+        let backend = backend_with_signature(&params, challenge_token, block_id, my_peer_id);
+        let proof_system = ProofOfStorage::with_params(params);
+        let signature = proof_system
+            .generate_signature(&backend, &challenge_token, &my_peer_id)
+            .expect("Signature generation should succeed");
+
+        // Active prefix is filled, the rest of the array is zero
+        assert!(signature.signature[..params.chunks]
+            .iter()
+            .all(|m| m.id != 0));
+        assert!(signature.signature[params.chunks..]
+            .iter()
+            .all(|m| m.id == 0 && m.block == 0));
+
+        // A default-params search cannot reproduce the signature
+        let default_proof = ProofOfStorage::new();
+        let default_sig = default_proof.generate_signature(&backend, &challenge_token, &my_peer_id);
+        assert_ne!(default_sig, Some(signature.clone()));
+
+        // Verification honors the runtime length
+        let config = ElectionConfig {
+            signature_params: params,
+            ..Default::default()
+        };
+        let mut election = PeerElection::new(challenge_token, my_peer_id, config);
+        let ticket = election.create_channel(100, 100).unwrap();
+        assert_eq!(
+            election.handle_answer(ticket, &signature.answer, &signature.signature, 101, 200),
+            Ok(())
+        );
+
+        // Corrupting the last active chunk fails verification
+        let mut bad_signature = signature.signature;
+        bad_signature[params.chunks - 1].id ^= params.chunk_mask();
+        let ticket2 = election.create_channel(200, 110).unwrap();
+        assert_eq!(
+            election.handle_answer(ticket2, &signature.answer, &bad_signature, 102, 210),
+            Err(ElectionError::SignatureChunkMismatch {
+                index: params.chunks - 1,
+                expected: (signature.signature[params.chunks - 1].id & params.chunk_mask()) as u16,
+                actual: (bad_signature[params.chunks - 1].id & params.chunk_mask()) as u16,
+            })
+        );
+
+        // Agreement is counted over the active prefix only
+        assert_eq!(
+            count_common_mappings(&signature, &signature, params.chunks),
+            params.chunks
+        );
+    }
+
+    #[test]
+    fn test_signature_params_six_chunks() {
+        let params = SignatureParams { chunks: 6, bits: 8 };
+        assert!(params.is_valid());
+        check_signature_roundtrip(params);
+    }
+
+    #[test]
+    fn test_signature_params_sixteen_chunks() {
+        let params = SignatureParams {
+            chunks: 16,
+            bits: 16,
+        };
+        assert!(params.is_valid());
+        check_signature_roundtrip(params);
+    }
+
+    #[test]
+    fn test_signature_params_default_matches_legacy_extraction() {
+        let hash = blake3::hash(b"signature params");
+        let legacy = extract_signature_chunks_from_256bit_hash(hash.as_bytes());
+        let params = SignatureParams::default();
+        assert_eq!(params.extract_chunks(hash.as_bytes()), legacy.to_vec());
+    }
+
+    #[test]
+    fn test_signature_params_validation() {
+        assert!(SignatureParams::default().is_valid());
+        assert!(!SignatureParams {
+            chunks: 0,
+            bits: 10
+        }
+        .is_valid());
+        assert!(!SignatureParams {
+            chunks: 17,
+            bits: 10
+        }
+        .is_valid());
+        assert!(!SignatureParams {
+            chunks: 10,
+            bits: 17
+        }
+        .is_valid());
+        assert!(SignatureParams {
+            chunks: 16,
+            bits: 16
+        }
+        .is_valid());
+    }
 }
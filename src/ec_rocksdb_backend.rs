@@ -6,8 +6,10 @@
 //
 // Architecture:
 // - Single RocksDB instance with multiple Column Families
-// - "tokens" CF: TokenId -> BlockTime mappings
+// - "tokens" CF: TokenId -> current (trusted) mapping, the only one served
+// - "pending" CF: TokenId -> unconfirmed sync mapping awaiting a second peer
 // - "blocks" CF: BlockId -> Block data
+// - "commit_chain" CF: CommitBlockId -> CommitBlock data, plus the chain head
 // - Shared block cache and Write-Ahead Log
 // - Atomic commits across both collections via WriteBatch
 
@@ -15,14 +17,23 @@ use rocksdb::{ColumnFamilyDescriptor, Direction, IteratorMode, Options, WriteBat
 use std::path::Path;
 use std::sync::Arc;
 
+use std::collections::HashMap;
+
 use crate::ec_interface::{
-    BatchedBackend, Block, BlockId, BlockTime, EcTime, StorageBatch, TokenId, TOKENS_PER_BLOCK,
+    BatchedBackend, Block, BlockId, BlockTime, CommitBlock, CommitBlockId, ConflictResolution,
+    EcCommitChainBackend, EcTime, PeerId, PendingMapping, StorageBatch, TokenId, TokenState,
+    TrustSource, TrustedMapping, TOKENS_PER_BLOCK,
 };
-use crate::ec_proof_of_storage::TokenStorageBackend;
+use crate::ec_proof_of_storage::{SignatureParams, TokenStorageBackend};
 
 // Column family names
 const CF_TOKENS: &str = "tokens";
+const CF_PENDING: &str = "pending";
 const CF_BLOCKS: &str = "blocks";
+const CF_COMMIT_CHAIN: &str = "commit_chain";
+
+// Fixed key for the commit chain head (distinct from 8-byte block keys)
+const COMMIT_CHAIN_HEAD_KEY: &[u8] = b"head";
 
 /// Main RocksDB database with column families for tokens and blocks
 ///
@@ -30,13 +41,18 @@ const CF_BLOCKS: &str = "blocks";
 /// specialized backend structs for tokens and blocks.
 ///
 /// # Example
-/// ```rust
+/// ```no_run
+/// use ec_rust::ec_proof_of_storage::ProofOfStorage;
+/// use ec_rust::ec_rocksdb_backend::EcRocksDb;
+///
 /// let db = EcRocksDb::open("./data")?;
 /// let tokens = db.tokens_backend();
 /// let blocks = db.blocks_backend();
 ///
 /// // Use with ProofOfStorage
-/// let proof_system = ProofOfStorage::new(tokens);
+/// let proof_system = ProofOfStorage::new();
+/// let signature = proof_system.generate_signature(&tokens, &42, &7);
+/// # Ok::<(), rocksdb::Error>(())
 /// ```
 pub struct EcRocksDb {
     db: Arc<DB>,
@@ -50,9 +66,16 @@ impl EcRocksDb {
         db_opts.create_missing_column_families(true);
 
         let cf_tokens = ColumnFamilyDescriptor::new(CF_TOKENS, Self::tokens_cf_options());
+        let cf_pending = ColumnFamilyDescriptor::new(CF_PENDING, Self::pending_cf_options());
         let cf_blocks = ColumnFamilyDescriptor::new(CF_BLOCKS, Self::blocks_cf_options());
+        let cf_commit_chain =
+            ColumnFamilyDescriptor::new(CF_COMMIT_CHAIN, Self::commit_chain_cf_options());
 
-        let db = DB::open_cf_descriptors(&db_opts, path, vec![cf_tokens, cf_blocks])?;
+        let db = DB::open_cf_descriptors(
+            &db_opts,
+            path,
+            vec![cf_tokens, cf_pending, cf_blocks, cf_commit_chain],
+        )?;
 
         Ok(Self { db: Arc::new(db) })
     }
@@ -65,9 +88,11 @@ impl EcRocksDb {
     /// - `expected_tokens`: Expected number of tokens (helps size bloom filters)
     ///
     /// # Example
-    /// ```rust
+    /// ```no_run
+    /// # use ec_rust::ec_rocksdb_backend::EcRocksDb;
     /// // For 100M tokens with 8 GB shared cache
     /// let db = EcRocksDb::open_optimized("./data", 8, 100_000_000)?;
+    /// # Ok::<(), rocksdb::Error>(())
     /// ```
     pub fn open_optimized(
         path: impl AsRef<Path>,
@@ -86,10 +111,21 @@ impl EcRocksDb {
             CF_TOKENS,
             Self::tokens_cf_options_with_cache(cache.clone()),
         );
-        let cf_blocks =
-            ColumnFamilyDescriptor::new(CF_BLOCKS, Self::blocks_cf_options_with_cache(cache));
+        let cf_pending = ColumnFamilyDescriptor::new(CF_PENDING, Self::pending_cf_options());
+        let cf_blocks = ColumnFamilyDescriptor::new(
+            CF_BLOCKS,
+            Self::blocks_cf_options_with_cache(cache.clone()),
+        );
+        let cf_commit_chain = ColumnFamilyDescriptor::new(
+            CF_COMMIT_CHAIN,
+            Self::commit_chain_cf_options_with_cache(cache),
+        );
 
-        let db = DB::open_cf_descriptors(&db_opts, path, vec![cf_tokens, cf_blocks])?;
+        let db = DB::open_cf_descriptors(
+            &db_opts,
+            path,
+            vec![cf_tokens, cf_pending, cf_blocks, cf_commit_chain],
+        )?;
 
         Ok(Self { db: Arc::new(db) })
     }
@@ -128,6 +164,17 @@ impl EcRocksDb {
         opts
     }
 
+    /// Column family options for pending sync mappings (small, short-lived)
+    fn pending_cf_options() -> Options {
+        let mut opts = Options::default();
+
+        // Entries live until a second peer confirms them or they age out
+        opts.set_write_buffer_size(32 * 1024 * 1024); // 32 MB
+        opts.set_max_write_buffer_number(2);
+
+        opts
+    }
+
     /// Column family options for blocks (optimized for point lookups)
     fn blocks_cf_options() -> Options {
         let mut opts = Options::default();
@@ -159,6 +206,30 @@ impl EcRocksDb {
         opts
     }
 
+    /// Column family options for commit chain (small, append-only point lookups)
+    fn commit_chain_cf_options() -> Options {
+        let mut opts = Options::default();
+
+        // Commit blocks are small and written once per commit
+        opts.set_write_buffer_size(32 * 1024 * 1024); // 32 MB
+        opts.set_max_write_buffer_number(2);
+        opts.set_compression_type(rocksdb::DBCompressionType::Lz4);
+
+        opts
+    }
+
+    /// Column family options for commit chain with shared cache
+    fn commit_chain_cf_options_with_cache(cache: rocksdb::Cache) -> Options {
+        let mut opts = Self::commit_chain_cf_options();
+
+        let mut block_opts = rocksdb::BlockBasedOptions::default();
+        block_opts.set_block_cache(&cache);
+        block_opts.set_bloom_filter(10.0, false);
+        opts.set_block_based_table_factory(&block_opts);
+
+        opts
+    }
+
     /// Get tokens storage backend
     pub fn tokens_backend(&self) -> RocksDbTokens {
         RocksDbTokens {
@@ -173,6 +244,13 @@ impl EcRocksDb {
         }
     }
 
+    /// Get commit chain storage backend
+    pub fn commit_chain_backend(&self) -> RocksDbCommitChain {
+        RocksDbCommitChain {
+            db: Arc::clone(&self.db),
+        }
+    }
+
     /// Get database statistics for monitoring
     pub fn stats(&self) -> Option<String> {
         self.db.property_value("rocksdb.stats").ok().flatten()
@@ -183,9 +261,15 @@ impl EcRocksDb {
         if let Some(cf) = self.db.cf_handle(CF_TOKENS) {
             self.db.compact_range_cf(cf, None::<&[u8]>, None::<&[u8]>);
         }
+        if let Some(cf) = self.db.cf_handle(CF_PENDING) {
+            self.db.compact_range_cf(cf, None::<&[u8]>, None::<&[u8]>);
+        }
         if let Some(cf) = self.db.cf_handle(CF_BLOCKS) {
             self.db.compact_range_cf(cf, None::<&[u8]>, None::<&[u8]>);
         }
+        if let Some(cf) = self.db.cf_handle(CF_COMMIT_CHAIN) {
+            self.db.compact_range_cf(cf, None::<&[u8]>, None::<&[u8]>);
+        }
     }
 }
 
@@ -207,26 +291,109 @@ impl RocksDbTokens {
         token.to_be_bytes()
     }
 
-    /// Encode BlockTime to bytes for storage
+    /// Encode a current (trusted) mapping to bytes for storage
+    ///
+    /// Format:
+    /// - 8 bytes: block (u64)
+    /// - 8 bytes: parent (u64)
+    /// - 8 bytes: time (u64)
+    /// - 1 byte: source (0 = Confirmed, 1 = Local)
     #[inline]
-    fn encode_value(block_time: &BlockTime) -> Vec<u8> {
-        let mut buf = Vec::with_capacity(16);
-        buf.extend_from_slice(&block_time.block.to_be_bytes());
-        buf.extend_from_slice(&block_time.time.to_be_bytes());
+    fn encode_value(current: &TrustedMapping) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(25);
+        buf.extend_from_slice(&current.block.to_be_bytes());
+        buf.extend_from_slice(&current.parent.to_be_bytes());
+        buf.extend_from_slice(&current.time.to_be_bytes());
+        buf.push(match current.source {
+            TrustSource::Confirmed => 0,
+            TrustSource::Local => 1,
+        });
         buf
     }
 
-    /// Decode bytes to BlockTime
+    /// Decode bytes to a current (trusted) mapping
     #[inline]
-    fn decode_value(bytes: &[u8]) -> Option<BlockTime> {
-        if bytes.len() < 16 {
+    fn decode_current(bytes: &[u8]) -> Option<TrustedMapping> {
+        if bytes.len() < 25 {
             return None;
         }
 
         let block = u64::from_be_bytes(bytes[0..8].try_into().ok()?);
-        let time = u64::from_be_bytes(bytes[8..16].try_into().ok()?);
+        let parent = u64::from_be_bytes(bytes[8..16].try_into().ok()?);
+        let time = u64::from_be_bytes(bytes[16..24].try_into().ok()?);
+        let source = match bytes[24] {
+            1 => TrustSource::Local,
+            _ => TrustSource::Confirmed,
+        };
 
-        Some(BlockTime { block, time })
+        Some(TrustedMapping {
+            block,
+            parent,
+            time,
+            source,
+        })
+    }
+
+    /// Decode bytes to the BlockTime served to queries
+    #[inline]
+    fn decode_value(bytes: &[u8]) -> Option<BlockTime> {
+        Self::decode_current(bytes).map(|c| BlockTime::new(c.block, c.parent, c.time))
+    }
+
+    /// Encode a pending mapping to bytes for storage
+    ///
+    /// Format: block, parent, time and source peer, 8 bytes each (u64)
+    #[inline]
+    fn encode_pending(pending: &PendingMapping) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(32);
+        buf.extend_from_slice(&pending.block.to_be_bytes());
+        buf.extend_from_slice(&pending.parent.to_be_bytes());
+        buf.extend_from_slice(&pending.time.to_be_bytes());
+        buf.extend_from_slice(&pending.source_peer.to_be_bytes());
+        buf
+    }
+
+    /// Decode bytes to a pending mapping
+    #[inline]
+    fn decode_pending(bytes: &[u8]) -> Option<PendingMapping> {
+        if bytes.len() < 32 {
+            return None;
+        }
+
+        Some(PendingMapping {
+            block: u64::from_be_bytes(bytes[0..8].try_into().ok()?),
+            parent: u64::from_be_bytes(bytes[8..16].try_into().ok()?),
+            time: u64::from_be_bytes(bytes[16..24].try_into().ok()?),
+            source_peer: u64::from_be_bytes(bytes[24..32].try_into().ok()?),
+        })
+    }
+
+    /// Read both slots of a token
+    fn read_state(db: &DB, token: &TokenId) -> TokenState {
+        let key = Self::encode_key(token);
+        let read = |cf_name: &str| {
+            let cf = db.cf_handle(cf_name).expect("token CFs should exist");
+            db.get_cf(cf, key).ok().flatten()
+        };
+        TokenState {
+            current: read(CF_TOKENS).and_then(|value| Self::decode_current(&value)),
+            pending: read(CF_PENDING).and_then(|value| Self::decode_pending(&value)),
+        }
+    }
+
+    /// Stage both slots of a token into `batch`, deleting empty slots
+    fn write_state(db: &DB, batch: &mut WriteBatch, token: &TokenId, state: &TokenState) {
+        let key = Self::encode_key(token);
+        let tokens_cf = db.cf_handle(CF_TOKENS).expect("tokens CF should exist");
+        let pending_cf = db.cf_handle(CF_PENDING).expect("pending CF should exist");
+        match &state.current {
+            Some(current) => batch.put_cf(tokens_cf, key, Self::encode_value(current)),
+            None => batch.delete_cf(tokens_cf, key),
+        }
+        match &state.pending {
+            Some(pending) => batch.put_cf(pending_cf, key, Self::encode_pending(pending)),
+            None => batch.delete_cf(pending_cf, key),
+        }
     }
 
     /// Get column family handle for tokens
@@ -249,65 +416,84 @@ impl TokenStorageBackend for RocksDbTokens {
             .and_then(|value| Self::decode_value(&value))
     }
 
-    fn set(&mut self, token: &TokenId, block: &BlockId, time: EcTime) {
-        let cf = self.cf_handle();
-        let key = Self::encode_key(token);
-
-        // Check if we should update (only if newer)
-        let should_update = if let Ok(Some(existing)) = self.db.get_cf(cf, &key) {
-            if let Some(existing_bt) = Self::decode_value(&existing) {
-                time > existing_bt.time
-            } else {
-                true
-            }
-        } else {
-            true
-        };
+    fn set(&mut self, token: &TokenId, block: &BlockId, parent: &BlockId, time: EcTime) {
+        // set() is called by mempool - always becomes Local, clears pending
+        let state = Self::read_state(&self.db, token);
+        if state.current.is_some_and(|current| current.time >= time) {
+            return;
+        }
 
-        if should_update {
-            let value = Self::encode_value(&BlockTime {
+        let new_state = TokenState {
+            current: Some(TrustedMapping {
                 block: *block,
+                parent: *parent,
                 time,
-            });
-            let _ = self.db.put_cf(cf, &key, &value);
-        }
+                source: TrustSource::Local,
+            }),
+            pending: None,
+        };
+        let mut batch = WriteBatch::default();
+        Self::write_state(&self.db, &mut batch, token, &new_state);
+        let _ = self.db.write(batch);
+    }
+
+    fn remove(&mut self, token: &TokenId) -> Option<BlockTime> {
+        // Drops both slots; a pending-only token returns its pending mapping
+        let state = Self::read_state(&self.db, token);
+        let removed = state
+            .current
+            .map(|c| BlockTime::new(c.block, c.parent, c.time))
+            .or_else(|| {
+                state
+                    .pending
+                    .map(|p| BlockTime::new(p.block, p.parent, p.time))
+            })?;
+        let mut batch = WriteBatch::default();
+        Self::write_state(&self.db, &mut batch, token, &TokenState::default());
+        let _ = self.db.write(batch);
+        Some(removed)
     }
 
     fn search_signature(
         &self,
         lookup_token: &TokenId,
-        signature_chunks: &[u16; crate::ec_proof_of_storage::SIGNATURE_CHUNKS],
+        signature_chunks: &[u16],
+        params: &SignatureParams,
+        forward_chunks: usize,
+        max_steps: usize,
     ) -> crate::ec_proof_of_storage::SignatureSearchResult {
-        use crate::ec_proof_of_storage::{SignatureSearchResult, SIGNATURE_CHUNKS};
+        use crate::ec_proof_of_storage::SignatureSearchResult;
 
-        let mut found_tokens = Vec::with_capacity(SIGNATURE_CHUNKS);
+        let total = signature_chunks.len();
+        let forward = forward_chunks.min(total);
+        let mut found_tokens = Vec::with_capacity(total);
         let mut steps = 0;
         let mut chunk_idx = 0;
 
-        // Helper to match signature chunk
-        #[inline]
-        fn matches_chunk(token: &TokenId, chunk_value: u16) -> bool {
-            (token & 0x3FF) as u16 == chunk_value
-        }
-
         let cf = self.cf_handle();
         let lookup_key = Self::encode_key(lookup_token);
 
-        // Search above (forward) for first 5 chunks
+        // Search above (forward) for first half of the chunks
         let iter = self
             .db
             .iterator_cf(cf, IteratorMode::From(&lookup_key, Direction::Forward))
             .skip(1); // Skip lookup_token itself
 
         for result in iter {
+            if chunk_idx >= forward {
+                break;
+            }
             if let Ok((key, _value)) = result {
+                if steps >= max_steps {
+                    break;
+                }
                 steps += 1;
                 if let Ok(token_bytes) = TryInto::<[u8; 8]>::try_into(key.as_ref()) {
                     let token = u64::from_be_bytes(token_bytes);
-                    if matches_chunk(&token, signature_chunks[chunk_idx]) {
+                    if params.matches_chunk(&token, signature_chunks[chunk_idx]) {
                         found_tokens.push(token);
                         chunk_idx += 1;
-                        if chunk_idx >= 5 {
+                        if chunk_idx >= forward {
                             break;
                         }
                     }
@@ -316,7 +502,7 @@ impl TokenStorageBackend for RocksDbTokens {
         }
 
         // Ring wrap forward: from beginning to lookup_token
-        if chunk_idx < 5 {
+        if chunk_idx < forward {
             let iter = self.db.iterator_cf(cf, IteratorMode::Start);
             for result in iter {
                 if let Ok((key, _value)) = result {
@@ -325,11 +511,14 @@ impl TokenStorageBackend for RocksDbTokens {
                         if token >= *lookup_token {
                             break; // Reached lookup_token
                         }
+                        if steps >= max_steps {
+                            break;
+                        }
                         steps += 1;
-                        if matches_chunk(&token, signature_chunks[chunk_idx]) {
+                        if params.matches_chunk(&token, signature_chunks[chunk_idx]) {
                             found_tokens.push(token);
                             chunk_idx += 1;
-                            if chunk_idx >= 5 {
+                            if chunk_idx >= forward {
                                 break;
                             }
                         }
@@ -338,21 +527,29 @@ impl TokenStorageBackend for RocksDbTokens {
             }
         }
 
-        // Search below (backward) for last 5 chunks
+        let forward_steps = steps;
+
+        // Search below (backward) for remaining chunks
         let iter = self
             .db
             .iterator_cf(cf, IteratorMode::From(&lookup_key, Direction::Reverse))
             .skip(1); // Skip lookup_token itself
 
         for result in iter {
+            if chunk_idx >= total {
+                break;
+            }
             if let Ok((key, _value)) = result {
+                if steps >= max_steps {
+                    break;
+                }
                 steps += 1;
                 if let Ok(token_bytes) = TryInto::<[u8; 8]>::try_into(key.as_ref()) {
                     let token = u64::from_be_bytes(token_bytes);
-                    if matches_chunk(&token, signature_chunks[chunk_idx]) {
+                    if params.matches_chunk(&token, signature_chunks[chunk_idx]) {
                         found_tokens.push(token);
                         chunk_idx += 1;
-                        if chunk_idx >= SIGNATURE_CHUNKS {
+                        if chunk_idx >= total {
                             break;
                         }
                     }
@@ -361,7 +558,7 @@ impl TokenStorageBackend for RocksDbTokens {
         }
 
         // Ring wrap backward: from end backwards to lookup_token
-        if chunk_idx < SIGNATURE_CHUNKS {
+        if chunk_idx < total {
             let iter = self.db.iterator_cf(cf, IteratorMode::End);
             for result in iter {
                 if let Ok((key, _value)) = result {
@@ -370,11 +567,14 @@ impl TokenStorageBackend for RocksDbTokens {
                         if token <= *lookup_token {
                             break; // Reached lookup_token
                         }
+                        if steps >= max_steps {
+                            break;
+                        }
                         steps += 1;
-                        if matches_chunk(&token, signature_chunks[chunk_idx]) {
+                        if params.matches_chunk(&token, signature_chunks[chunk_idx]) {
                             found_tokens.push(token);
                             chunk_idx += 1;
-                            if chunk_idx >= SIGNATURE_CHUNKS {
+                            if chunk_idx >= total {
                                 break;
                             }
                         }
@@ -384,12 +584,43 @@ impl TokenStorageBackend for RocksDbTokens {
         }
 
         SignatureSearchResult {
-            complete: chunk_idx == SIGNATURE_CHUNKS,
+            complete: chunk_idx == total,
+            chunks_matched: found_tokens.len(),
             tokens: found_tokens,
             steps,
+            forward_steps,
+            backward_steps: steps - forward_steps,
         }
     }
 
+    fn get_many(&self, tokens: &[TokenId]) -> Vec<Option<BlockTime>> {
+        let cf = self.cf_handle();
+        let keys = tokens.iter().map(|token| (cf, Self::encode_key(token)));
+        self.db
+            .multi_get_cf(keys)
+            .into_iter()
+            .map(|result| {
+                result
+                    .ok()
+                    .flatten()
+                    .and_then(|value| Self::decode_value(&value))
+            })
+            .collect()
+    }
+
+    fn range_after(&self, token: &TokenId) -> Box<dyn Iterator<Item = (TokenId, BlockTime)> + '_> {
+        // Keys are big-endian, so seeking to token + 1 starts strictly above it
+        let Some(first) = token.checked_add(1) else {
+            return Box::new(std::iter::empty());
+        };
+        let key = Self::encode_key(&first);
+        let iter = self.db.iterator_cf(
+            self.cf_handle(),
+            IteratorMode::From(&key, Direction::Forward),
+        );
+        Box::new(RocksDbTokenIterator::new(iter))
+    }
+
     fn len(&self) -> usize {
         let cf = self.cf_handle();
         // RocksDB doesn't have fast len() - use approximate count
@@ -402,11 +633,11 @@ impl TokenStorageBackend for RocksDbTokens {
 
 /// Iterator for token range scans
 struct RocksDbTokenIterator<'a> {
-    inner: std::iter::Skip<rocksdb::DBIterator<'a>>,
+    inner: rocksdb::DBIterator<'a>,
 }
 
 impl<'a> RocksDbTokenIterator<'a> {
-    fn new(inner: std::iter::Skip<rocksdb::DBIterator<'a>>) -> Self {
+    fn new(inner: rocksdb::DBIterator<'a>) -> Self {
         Self { inner }
     }
 }
@@ -584,6 +815,132 @@ impl crate::ec_interface::EcBlocks for RocksDbBlocks {
     }
 }
 
+// ============================================================================
+// Commit Chain Storage Backend (Column Family: "commit_chain")
+// ============================================================================
+
+/// RocksDB-backed commit chain storage
+///
+/// Persistent counterpart of `MemCommitChain`: commit blocks are keyed by
+/// their ID and the chain head is stored under a fixed key in the same
+/// column family, so both survive restarts.
+pub struct RocksDbCommitChain {
+    db: Arc<DB>,
+}
+
+impl RocksDbCommitChain {
+    /// Encode a CommitBlockId to bytes for storage (big-endian)
+    #[inline]
+    fn encode_key(id: &CommitBlockId) -> [u8; 8] {
+        id.to_be_bytes()
+    }
+
+    /// Encode CommitBlock to bytes for storage
+    ///
+    /// Format:
+    /// - 8 bytes: block.id (u64)
+    /// - 8 bytes: block.previous (u64)
+    /// - 8 bytes: block.time (u64)
+    /// - 4 bytes: number of committed blocks (u32)
+    /// - For each committed block:
+    ///   - 8 bytes: block id (u64)
+    fn encode_value(block: &CommitBlock) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(28 + block.committed_blocks.len() * 8);
+
+        buf.extend_from_slice(&block.id.to_be_bytes());
+        buf.extend_from_slice(&block.previous.to_be_bytes());
+        buf.extend_from_slice(&block.time.to_be_bytes());
+        buf.extend_from_slice(&(block.committed_blocks.len() as u32).to_be_bytes());
+        for block_id in &block.committed_blocks {
+            buf.extend_from_slice(&block_id.to_be_bytes());
+        }
+
+        buf
+    }
+
+    /// Decode bytes to CommitBlock
+    fn decode_value(bytes: &[u8]) -> Option<CommitBlock> {
+        if bytes.len() < 28 {
+            return None;
+        }
+
+        let id = u64::from_be_bytes(bytes[0..8].try_into().ok()?);
+        let previous = u64::from_be_bytes(bytes[8..16].try_into().ok()?);
+        let time = u64::from_be_bytes(bytes[16..24].try_into().ok()?);
+        let count = u32::from_be_bytes(bytes[24..28].try_into().ok()?) as usize;
+
+        if bytes.len() != 28 + count * 8 {
+            return None;
+        }
+
+        let committed_blocks = bytes[28..]
+            .chunks_exact(8)
+            .map(|chunk| u64::from_be_bytes(chunk.try_into().unwrap()))
+            .collect();
+
+        Some(CommitBlock {
+            id,
+            previous,
+            time,
+            committed_blocks,
+        })
+    }
+
+    /// Get column family handle for commit chain
+    #[inline]
+    fn cf_handle(&self) -> &rocksdb::ColumnFamily {
+        self.db
+            .cf_handle(CF_COMMIT_CHAIN)
+            .expect("commit_chain CF should exist")
+    }
+
+    /// Save a commit block without moving the head
+    pub fn save(&mut self, block: &CommitBlock) {
+        let cf = self.cf_handle();
+        let key = Self::encode_key(&block.id);
+        let value = Self::encode_value(block);
+        let _ = self.db.put_cf(cf, &key, &value);
+    }
+
+    /// Set the head of the commit chain
+    pub fn set_head(&mut self, id: &CommitBlockId) {
+        let cf = self.cf_handle();
+        let _ = self
+            .db
+            .put_cf(cf, COMMIT_CHAIN_HEAD_KEY, &Self::encode_key(id));
+    }
+
+    /// Save a commit block and make it the new head in one atomic write
+    pub fn save_as_head(&mut self, block: &CommitBlock) -> Result<(), rocksdb::Error> {
+        let cf = self.cf_handle();
+        let mut batch = WriteBatch::default();
+        batch.put_cf(cf, &Self::encode_key(&block.id), &Self::encode_value(block));
+        batch.put_cf(cf, COMMIT_CHAIN_HEAD_KEY, &Self::encode_key(&block.id));
+        self.db.write(batch)
+    }
+}
+
+impl EcCommitChainBackend for RocksDbCommitChain {
+    fn lookup(&self, id: &CommitBlockId) -> Option<CommitBlock> {
+        let cf = self.cf_handle();
+        let key = Self::encode_key(id);
+        self.db
+            .get_cf(cf, &key)
+            .ok()
+            .flatten()
+            .and_then(|value| Self::decode_value(&value))
+    }
+
+    fn get_head(&self) -> Option<CommitBlockId> {
+        let cf = self.cf_handle();
+        self.db
+            .get_cf(cf, COMMIT_CHAIN_HEAD_KEY)
+            .ok()
+            .flatten()
+            .and_then(|value| value.as_slice().try_into().ok().map(u64::from_be_bytes))
+    }
+}
+
 // ============================================================================
 // Batched Commit Support
 // ============================================================================
@@ -593,10 +950,36 @@ impl crate::ec_interface::EcBlocks for RocksDbBlocks {
 /// Uses RocksDB's WriteBatch for true atomic multi-operation commits.
 /// All operations are accumulated in the batch and committed atomically
 /// with a single WAL sync.
+///
+/// Sync updates need the stored two-slot state, so they are staged and
+/// resolved at commit time in the same order as `MemoryBatch`: local
+/// updates, then sync updates, then expired pending mappings.
 pub struct RocksDbBatch {
     db: Arc<DB>,
     batch: WriteBatch,
     block_count: usize,
+    /// Token states written by this batch, seen by later staged operations
+    staged: HashMap<TokenId, TokenState>,
+    /// Sync token updates (from commit chain) - use two-slot state machine
+    sync_tokens: Vec<(
+        TokenId,
+        BlockId,
+        BlockId,
+        EcTime,
+        PeerId,
+        ConflictResolution,
+    )>,
+    /// Aged-out pending mappings to drop (from commit chain)
+    expired_pending: Vec<(TokenId, BlockId)>,
+}
+
+impl RocksDbBatch {
+    /// Token state as of the operations staged so far
+    fn state(&mut self, token: &TokenId) -> &mut TokenState {
+        self.staged
+            .entry(*token)
+            .or_insert_with(|| RocksDbTokens::read_state(&self.db, token))
+    }
 }
 
 impl StorageBatch for RocksDbBatch {
@@ -608,22 +991,70 @@ impl StorageBatch for RocksDbBatch {
         self.block_count += 1;
     }
 
-    fn update_token(&mut self, token: &TokenId, block: &BlockId, time: EcTime) {
-        let tokens_cf = self.db.cf_handle(CF_TOKENS).expect("tokens CF exists");
-        let token_key = RocksDbTokens::encode_key(token);
-        let token_value = RocksDbTokens::encode_value(&BlockTime {
-            block: *block,
-            time,
-        });
-        self.batch.put_cf(tokens_cf, &token_key, &token_value);
+    fn update_token(&mut self, token: &TokenId, block: &BlockId, parent: &BlockId, time: EcTime) {
+        let state = TokenState {
+            current: Some(TrustedMapping {
+                block: *block,
+                parent: *parent,
+                time,
+                source: TrustSource::Local,
+            }),
+            pending: None,
+        };
+        RocksDbTokens::write_state(&self.db, &mut self.batch, token, &state);
+        self.staged.insert(*token, state);
+    }
+
+    fn update_token_sync(
+        &mut self,
+        token: &TokenId,
+        block: &BlockId,
+        parent: &BlockId,
+        time: EcTime,
+        source_peer: PeerId,
+        resolution: ConflictResolution,
+    ) {
+        self.sync_tokens
+            .push((*token, *block, *parent, time, source_peer, resolution));
+    }
+
+    fn expire_pending(&mut self, token: &TokenId, block: &BlockId) {
+        self.expired_pending.push((*token, *block));
     }
 
-    fn commit(self: Box<Self>) -> Result<(), Box<dyn std::error::Error>> {
+    fn commit(mut self: Box<Self>) -> Result<(), Box<dyn std::error::Error>> {
+        // Resolve sync updates against stored state (after local updates)
+        let mut touched = Vec::new();
+        for (token, block, parent, time, source_peer, resolution) in
+            std::mem::take(&mut self.sync_tokens)
+        {
+            let state = self.state(&token);
+            state.apply_sync_update(block, parent, time, source_peer, resolution);
+            touched.push(token);
+        }
+
+        // Drop aged-out pending mappings (after sync, so fresh updates win)
+        for (token, block) in std::mem::take(&mut self.expired_pending) {
+            let state = self.state(&token);
+            if state.pending.map(|p| p.block) == Some(block) {
+                state.pending = None;
+                touched.push(token);
+            }
+        }
+
+        for token in touched {
+            RocksDbTokens::write_state(&self.db, &mut self.batch, &token, &self.staged[&token]);
+        }
+
         // Single atomic write - all or nothing
         self.db.write(self.batch)?;
         Ok(())
     }
 
+    fn rollback(self: Box<Self>) {
+        // Nothing reaches the DB before `write`; dropping the WriteBatch discards it
+    }
+
     fn block_count(&self) -> usize {
         self.block_count
     }
@@ -635,6 +1066,9 @@ impl BatchedBackend for EcRocksDb {
             db: Arc::clone(&self.db),
             batch: WriteBatch::default(),
             block_count: 0,
+            staged: HashMap::new(),
+            sync_tokens: Vec::new(),
+            expired_pending: Vec::new(),
         })
     }
 }
@@ -642,7 +1076,7 @@ impl BatchedBackend for EcRocksDb {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ec_interface::TokenBlock;
+    use crate::ec_interface::{EcBlocks, TokenBlock, GENESIS_BLOCK_ID};
     use tempfile::TempDir;
 
     #[test]
@@ -660,13 +1094,14 @@ mod tests {
         let db = EcRocksDb::open(dir.path()).unwrap();
         let mut tokens = db.tokens_backend();
 
-        tokens.set(&100, &1, 42);
+        tokens.set(&100, &1, &GENESIS_BLOCK_ID, 42);
         assert!(tokens.len() > 0);
 
         let result = tokens.lookup(&100);
         assert!(result.is_some());
         let block_time = result.unwrap();
         assert_eq!(block_time.block, 1);
+        assert_eq!(block_time.parent, GENESIS_BLOCK_ID);
         assert_eq!(block_time.time, 42);
     }
 
@@ -678,16 +1113,16 @@ mod tests {
 
         // Insert test data
         for i in 0..10 {
-            tokens.set(&(i * 100), &i, i);
+            tokens.set(&(i * 100), &i, &GENESIS_BLOCK_ID, i);
         }
 
         // Test range_after
         let result: Vec<_> = tokens.range_after(&250).take(3).map(|(t, _)| t).collect();
         assert_eq!(result, vec![300, 400, 500]);
 
-        // Test range_before
-        let result: Vec<_> = tokens.range_before(&250).take(3).map(|(t, _)| t).collect();
-        assert_eq!(result, vec![200, 100, 0]);
+        // Starts strictly above an existing token
+        let result: Vec<_> = tokens.range_after(&800).map(|(t, _)| t).collect();
+        assert_eq!(result, vec![900]);
     }
 
     #[test]
@@ -779,7 +1214,12 @@ mod tests {
             batch.save_block(&block);
             // Add token updates
             for i in 0..block.used as usize {
-                batch.update_token(&block.parts[i].token, &block.id, block.time);
+                batch.update_token(
+                    &block.parts[i].token,
+                    &block.id,
+                    &GENESIS_BLOCK_ID,
+                    block.time,
+                );
             }
             assert_eq!(batch.block_count(), 1);
             batch.commit().unwrap();
@@ -868,7 +1308,12 @@ mod tests {
                 batch.save_block(block);
                 // Add token updates for this block
                 for i in 0..block.used as usize {
-                    batch.update_token(&block.parts[i].token, &block.id, block.time);
+                    batch.update_token(
+                        &block.parts[i].token,
+                        &block.id,
+                        &GENESIS_BLOCK_ID,
+                        block.time,
+                    );
                 }
             }
             assert_eq!(batch.block_count(), 3);
@@ -902,4 +1347,87 @@ mod tests {
 
         // Should succeed with no changes
     }
+
+    #[test]
+    fn test_rocksdb_batch_sync_needs_second_peer() {
+        let dir = TempDir::new().unwrap();
+        let mut db = EcRocksDb::open(dir.path()).unwrap();
+        let resolution = ConflictResolution::default();
+
+        // One peer only creates a pending mapping, which is never served
+        let mut batch = db.begin_batch();
+        batch.update_token_sync(&10, &5, &GENESIS_BLOCK_ID, 100, 1, resolution);
+        batch.update_token_sync(&20, &6, &GENESIS_BLOCK_ID, 100, 1, resolution);
+        batch.commit().unwrap();
+        assert!(db.tokens_backend().lookup(&10).is_none());
+
+        // A second peer confirms token 10; token 20's pending ages out
+        let mut batch = db.begin_batch();
+        batch.update_token_sync(&10, &5, &GENESIS_BLOCK_ID, 100, 2, resolution);
+        batch.expire_pending(&20, &6);
+        batch.commit().unwrap();
+
+        let mut tokens = db.tokens_backend();
+        assert_eq!(tokens.lookup(&10).unwrap().block, 5);
+        assert!(tokens.lookup(&20).is_none());
+        assert!(tokens.remove(&20).is_none());
+
+        // A local update clears a newer pending mapping
+        let mut batch = db.begin_batch();
+        batch.update_token_sync(&10, &9, &5, 200, 1, resolution);
+        batch.commit().unwrap();
+        tokens.set(&10, &7, &5, 150);
+        let mut batch = db.begin_batch();
+        batch.update_token_sync(&10, &9, &5, 200, 2, resolution);
+        batch.commit().unwrap();
+        assert_eq!(tokens.lookup(&10).unwrap().block, 7);
+    }
+
+    #[test]
+    fn test_commit_chain_encoding_roundtrip() {
+        let block = CommitBlock::new(7, 6, 1234, vec![11, 22, 33]);
+        let decoded =
+            RocksDbCommitChain::decode_value(&RocksDbCommitChain::encode_value(&block)).unwrap();
+
+        assert_eq!(decoded.id, 7);
+        assert_eq!(decoded.previous, 6);
+        assert_eq!(decoded.time, 1234);
+        assert_eq!(decoded.committed_blocks, vec![11, 22, 33]);
+    }
+
+    #[test]
+    fn test_commit_chain_survives_reopen() {
+        let dir = TempDir::new().unwrap();
+
+        {
+            let db = EcRocksDb::open(dir.path()).unwrap();
+            let mut chain = db.commit_chain_backend();
+            assert_eq!(chain.get_head(), None);
+
+            chain
+                .save_as_head(&CommitBlock::new(1, 0, 100, vec![10]))
+                .unwrap();
+            chain
+                .save_as_head(&CommitBlock::new(2, 1, 200, vec![20, 21]))
+                .unwrap();
+            chain
+                .save_as_head(&CommitBlock::new(3, 2, 300, vec![]))
+                .unwrap();
+            assert_eq!(chain.get_head(), Some(3));
+        }
+
+        let db = EcRocksDb::open(dir.path()).unwrap();
+        let chain = db.commit_chain_backend();
+        assert_eq!(chain.get_head(), Some(3));
+
+        // Walk back from the head following `previous`
+        let mut walked = Vec::new();
+        let mut current = chain.get_head();
+        while let Some(id) = current.filter(|id| *id != 0) {
+            let block = chain.lookup(&id).unwrap();
+            walked.push((block.id, block.time, block.committed_blocks.len()));
+            current = Some(block.previous);
+        }
+        assert_eq!(walked, vec![(3, 300, 0), (2, 200, 2), (1, 100, 1)]);
+    }
 }
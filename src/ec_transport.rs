@@ -0,0 +1,326 @@
+//! Transport abstraction and async node driver
+//!
+//! `Transport` is what an integrator implements to move `MessageEnvelope`s
+//! between nodes. With the `tokio-transport` feature the crate also provides:
+//! - `EcNode::run`: ticks on a timer and feeds received envelopes to the node
+//! - `TokioTcpTransport`: a reference TCP transport using the framing in `ec_wire`
+//!
+//! `EcNode` holds its backend in `Rc<RefCell<..>>`, so drivers run on one thread
+//! (e.g. a current-thread runtime or a `LocalSet`) and futures need not be `Send`.
+
+use crate::ec_interface::{MessageEnvelope, PeerId};
+use std::io;
+use std::net::SocketAddr;
+
+/// Moves envelopes between nodes
+// Futures are deliberately not `Send`: EcNode is single-threaded (see module docs)
+#[allow(async_fn_in_trait)]
+pub trait Transport {
+    /// Deliver an envelope to `env.receiver`
+    ///
+    /// Delivery is best effort, like the simulators' lossy network: callers
+    /// drop the envelope on error.
+    async fn send(&self, env: MessageEnvelope) -> io::Result<()>;
+
+    /// Next envelope addressed to this node, or None once the transport is closed
+    ///
+    /// Must be cancel-safe: the driver polls it alongside its tick timer.
+    async fn recv(&mut self) -> Option<MessageEnvelope>;
+
+    /// Learn a peer's network address (from `EcPeers`)
+    ///
+    /// Transports that don't route by address can ignore this.
+    fn set_peer_addr(&mut self, _peer: PeerId, _addr: SocketAddr) {}
+}
+
+#[cfg(feature = "tokio-transport")]
+pub use self::tokio_impl::TokioTcpTransport;
+
+#[cfg(feature = "tokio-transport")]
+mod tokio_impl {
+    use super::Transport;
+    use crate::ec_interface::{
+        BatchedBackend, EcBlocks, EcCommitChainAccess, EcTokensV2, MessageEnvelope, PeerId,
+    };
+    use crate::ec_node::EcNode;
+    use crate::ec_proof_of_storage::TokenStorageBackend;
+    use crate::ec_wire::MAX_FRAME_LEN;
+    use std::collections::hash_map::Entry;
+    use std::collections::HashMap;
+    use std::io;
+    use std::net::SocketAddr;
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio::sync::{mpsc, Mutex};
+    use tokio::task::JoinHandle;
+
+    impl<
+            B: BatchedBackend + EcTokensV2 + EcBlocks + EcCommitChainAccess + 'static,
+            T: TokenStorageBackend,
+        > EcNode<B, T>
+    {
+        /// Drive the node over `transport` until it closes
+        ///
+        /// Calls `tick` every `tick_interval` and `handle_message` for each
+        /// received envelope, sending all outbound envelopes through the
+        /// transport. Receiver addresses known to `EcPeers` are passed to the
+        /// transport before sending.
+        pub async fn run<X: Transport>(&mut self, transport: &mut X, tick_interval: Duration) {
+            let mut interval = tokio::time::interval(tick_interval);
+            let mut outbound = Vec::new();
+
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => self.tick(&mut outbound),
+                    received = transport.recv() => match received {
+                        Some(env) => self.handle_message(&env, &mut outbound),
+                        None => return,
+                    },
+                }
+
+                for env in outbound.drain(..) {
+                    if let Some(addr) = self.peer_addr(&env.receiver) {
+                        transport.set_peer_addr(env.receiver, addr);
+                    }
+                    let receiver = env.receiver;
+                    if let Err(e) = transport.send(env).await {
+                        log::debug!("Dropping envelope to peer {}: {}", receiver, e);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Reference TCP transport: one outbound connection per peer, framed envelopes
+    ///
+    /// Incoming connections are accepted in the background and every frame
+    /// read from them is queued for `recv`. Replies go out over our own
+    /// connection to the sender's address, so both sides must know each
+    /// other's listening address.
+    pub struct TokioTcpTransport {
+        local_addr: SocketAddr,
+        addrs: HashMap<PeerId, SocketAddr>,
+        connections: Mutex<HashMap<PeerId, TcpStream>>,
+        incoming: mpsc::UnboundedReceiver<MessageEnvelope>,
+        accept_task: JoinHandle<()>,
+    }
+
+    impl TokioTcpTransport {
+        /// Listen on `addr` (use port 0 for an ephemeral port)
+        ///
+        /// Must be called inside a Tokio runtime.
+        pub async fn bind(addr: SocketAddr) -> io::Result<Self> {
+            let listener = TcpListener::bind(addr).await?;
+            let local_addr = listener.local_addr()?;
+            let (tx, incoming) = mpsc::unbounded_channel();
+
+            let accept_task = tokio::spawn(async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    tokio::spawn(read_frames(stream, tx.clone()));
+                }
+            });
+
+            Ok(Self {
+                local_addr,
+                addrs: HashMap::new(),
+                connections: Mutex::new(HashMap::new()),
+                incoming,
+                accept_task,
+            })
+        }
+
+        /// Address we are listening on
+        pub fn local_addr(&self) -> SocketAddr {
+            self.local_addr
+        }
+    }
+
+    impl Drop for TokioTcpTransport {
+        fn drop(&mut self) {
+            self.accept_task.abort();
+        }
+    }
+
+    impl Transport for TokioTcpTransport {
+        async fn send(&self, env: MessageEnvelope) -> io::Result<()> {
+            let receiver = env.receiver;
+            let mut frame = Vec::new();
+            env.write_framed(&mut frame)?;
+
+            let mut connections = self.connections.lock().await;
+            let stream = match connections.entry(receiver) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let addr = self.addrs.get(&receiver).ok_or_else(|| {
+                        io::Error::new(io::ErrorKind::NotFound, "no address for peer")
+                    })?;
+                    let stream = TcpStream::connect(addr).await?;
+                    stream.set_nodelay(true)?;
+                    entry.insert(stream)
+                }
+            };
+
+            let result = stream.write_all(&frame).await;
+            if result.is_err() {
+                // Reconnect on the next send
+                connections.remove(&receiver);
+            }
+            result
+        }
+
+        async fn recv(&mut self) -> Option<MessageEnvelope> {
+            self.incoming.recv().await
+        }
+
+        fn set_peer_addr(&mut self, peer: PeerId, addr: SocketAddr) {
+            self.addrs.insert(peer, addr);
+        }
+    }
+
+    /// Read frames from one connection until it closes or sends garbage
+    async fn read_frames(mut stream: TcpStream, tx: mpsc::UnboundedSender<MessageEnvelope>) {
+        loop {
+            let mut len = [0u8; 4];
+            if stream.read_exact(&mut len).await.is_err() {
+                return;
+            }
+            let body_len = u32::from_be_bytes(len) as usize;
+            if body_len > MAX_FRAME_LEN {
+                return;
+            }
+
+            // Reassemble the whole frame so read_framed checks length and CRC
+            let mut frame = vec![0u8; 4 + body_len + 4];
+            frame[..4].copy_from_slice(&len);
+            if stream.read_exact(&mut frame[4..]).await.is_err() {
+                return;
+            }
+
+            match MessageEnvelope::read_framed(&mut frame.as_slice()) {
+                Ok(env) => {
+                    if tx.send(env).is_err() {
+                        return;
+                    }
+                }
+                Err(e) => {
+                    log::debug!("Closing connection after bad frame: {}", e);
+                    return;
+                }
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::ec_interface::Message;
+        use crate::ec_memory_backend::{MemTokens, MemoryBackend};
+        use rand::{Rng, SeedableRng};
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        type Node = EcNode<MemoryBackend, MemoryBackend>;
+
+        const NODE_A: PeerId = 1 << 62;
+        const NODE_B: PeerId = 3 << 62;
+
+        fn node(peer_id: PeerId, token_storage: MemoryBackend) -> Node {
+            let backend = Rc::new(RefCell::new(MemoryBackend::new_with_peer_id(peer_id)));
+            let rng = rand::rngs::StdRng::seed_from_u64(peer_id);
+            EcNode::new(backend, peer_id, 0, token_storage, rng)
+        }
+
+        /// Records everything the wrapped transport receives
+        struct Recording<X> {
+            inner: X,
+            received: Rc<RefCell<Vec<MessageEnvelope>>>,
+        }
+
+        impl<X: Transport> Transport for Recording<X> {
+            async fn send(&self, env: MessageEnvelope) -> io::Result<()> {
+                self.inner.send(env).await
+            }
+
+            async fn recv(&mut self) -> Option<MessageEnvelope> {
+                let env = self.inner.recv().await?;
+                self.received.borrow_mut().push(env.clone());
+                Some(env)
+            }
+
+            fn set_peer_addr(&mut self, peer: PeerId, addr: SocketAddr) {
+                self.inner.set_peer_addr(peer, addr);
+            }
+        }
+
+        #[tokio::test]
+        async fn test_two_nodes_exchange_query_and_answer_over_loopback() {
+            // Node B stores enough tokens to produce a complete signature
+            let mut rng = rand::rngs::StdRng::seed_from_u64(39);
+            let mappings: Vec<_> = (0..50_000)
+                .map(|_| (rng.gen::<u64>(), rng.gen::<u64>(), 0, 1))
+                .collect();
+            let token = mappings[0].0;
+            let mut storage_b = MemoryBackend::new();
+            *storage_b.tokens_mut() = MemTokens::from_mappings(mappings);
+
+            let mut node_a = node(NODE_A, MemoryBackend::new());
+            let mut node_b = node(NODE_B, storage_b);
+
+            let loopback: SocketAddr = "127.0.0.1:0".parse().unwrap();
+            let transport_a = TokioTcpTransport::bind(loopback).await.unwrap();
+            let mut transport_b = TokioTcpTransport::bind(loopback).await.unwrap();
+
+            // Each node learns the other's address through EcPeers
+            node_a.seed_peer(&NODE_B);
+            node_b.seed_peer(&NODE_A);
+            assert!(node_a.set_peer_addr(&NODE_B, transport_b.local_addr()));
+            assert!(node_b.set_peer_addr(&NODE_A, transport_a.local_addr()));
+
+            let mut transport_a = Recording {
+                inner: transport_a,
+                received: Rc::new(RefCell::new(Vec::new())),
+            };
+            let received = transport_a.received.clone();
+
+            // A queries B for the token
+            transport_a.set_peer_addr(NODE_B, node_a.peer_addr(&NODE_B).unwrap());
+            transport_a
+                .send(MessageEnvelope {
+                    sender: NODE_A,
+                    receiver: NODE_B,
+                    ticket: 77,
+                    time: 0,
+                    message: Message::QueryToken {
+                        token_id: token,
+                        target: 0,
+                        ticket: 77,
+                    },
+                })
+                .await
+                .unwrap();
+
+            let tick = Duration::from_millis(5);
+            let answered = async {
+                loop {
+                    let got_answer = received.borrow().iter().any(|env| {
+                        env.sender == NODE_B
+                            && matches!(env.message, Message::Answer { answer, .. } if answer.id == token)
+                    });
+                    if got_answer {
+                        return;
+                    }
+                    tokio::time::sleep(tick).await;
+                }
+            };
+
+            tokio::select! {
+                _ = node_a.run(&mut transport_a, tick) => panic!("transport A closed"),
+                _ = node_b.run(&mut transport_b, tick) => panic!("transport B closed"),
+                result = tokio::time::timeout(Duration::from_secs(10), answered) => {
+                    result.expect("node A never received B's answer");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,889 @@
+//! Canonical binary wire codec for `Message`
+//!
+//! A compact, versioned format that does not depend on serde's data model, so
+//! independently built nodes agree on the bytes.
+//!
+//! Layout:
+//! - `[version: u8][discriminant: u8][payload]`
+//! - Integers are little-endian (same byte order as ticket/hash inputs)
+//! - Fixed arrays carry a `u8` length prefix that must match the constant
+//! - Variable lists (`RequestBatch`, `PeerList`, `QueryBlocks`, `Blocks`, committed blocks)
+//!   carry a `u32` count
+//! - Bools are one byte, `0` or `1`; `Option<Signature>` is a `0`/`1` flag plus the value
+//!
+//! Signature size:
+//! - `Answer` always carries `TOKENS_SIGNATURE_SIZE` (16) mappings; only the first
+//!   `SignatureParams::chunks` (default 10) are filled and the rest are zero
+//! - Builds from before the chunk count became configurable used 10 mappings. That is a
+//!   format break: their `Answer` fails to decode with `CodecError::ArrayLength`, and the
+//!   serde encoding of `Message::Answer` changed the same way
+//!
+//! Stream framing (`MessageEnvelope::write_framed` / `read_framed`):
+//! - `[len: u32 BE][sender u64][receiver u64][ticket u64][time u64][message][crc32: u32 BE]`
+//! - `len` counts the envelope bytes between the length and the checksum
+//! - The checksum is CRC-32 (IEEE) over those envelope bytes
+
+use crate::ec_interface::{
+    BatchRequestItem, Block, CommitBlock, Message, MessageEnvelope, TokenBlock, TokenMapping,
+    TOKENS_PER_BLOCK, TOKENS_SIGNATURE_SIZE,
+};
+use std::io::{self, Read, Write};
+
+/// Current wire format version (first byte of every encoded message)
+pub const WIRE_VERSION: u8 = 1;
+
+/// Largest envelope accepted by `read_framed` (guards the length prefix)
+pub const MAX_FRAME_LEN: usize = 1 << 20;
+
+// Message discriminants (never reuse a retired value)
+const MSG_INITIAL_VOTE: u8 = 0;
+const MSG_VOTE: u8 = 1;
+const MSG_QUERY_BLOCK: u8 = 2;
+const MSG_QUERY_TOKEN: u8 = 3;
+const MSG_REQUEST_BATCH: u8 = 4;
+const MSG_ANSWER: u8 = 5;
+const MSG_BLOCK: u8 = 6;
+const MSG_REFERRAL: u8 = 7;
+const MSG_PEER_LIST: u8 = 8;
+const MSG_QUERY_COMMIT_BLOCK: u8 = 9;
+const MSG_COMMIT_BLOCK: u8 = 10;
+const MSG_QUERY_BLOCKS: u8 = 11;
+const MSG_BLOCKS: u8 = 12;
+// 13: payload-less keepalive, retired when keepalives started carrying the chain head
+const MSG_KEEPALIVE: u8 = 14;
+const MSG_COMMIT_HEAD: u8 = 15;
+
+// Batch item discriminants
+const ITEM_VOTE: u8 = 0;
+const ITEM_QUERY_BLOCK: u8 = 1;
+const ITEM_QUERY_TOKEN: u8 = 2;
+
+/// Errors from decoding a wire message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CodecError {
+    /// Input ended before the message was complete
+    UnexpectedEnd,
+
+    /// Version byte is not one this build understands
+    UnsupportedVersion(u8),
+
+    /// Unknown message discriminant
+    UnknownMessage(u8),
+
+    /// Unknown `RequestBatch` item discriminant
+    UnknownBatchItem(u8),
+
+    /// Bool or option flag byte other than 0/1
+    InvalidFlag(u8),
+
+    /// Fixed array length prefix doesn't match the protocol constant
+    ArrayLength { expected: usize, found: usize },
+}
+
+impl Message {
+    /// Append the canonical wire encoding of this message to `out`
+    pub fn encode(&self, out: &mut Vec<u8>) {
+        out.push(WIRE_VERSION);
+        match self {
+            Message::InitialVote { block, vote } => {
+                out.push(MSG_INITIAL_VOTE);
+                put_block(out, block);
+                out.push(*vote);
+            }
+            Message::Vote {
+                block_id,
+                vote,
+                reply,
+            } => {
+                out.push(MSG_VOTE);
+                put_u64(out, *block_id);
+                out.push(*vote);
+                out.push(*reply as u8);
+            }
+            Message::QueryBlock {
+                block_id,
+                target,
+                ticket,
+            } => {
+                out.push(MSG_QUERY_BLOCK);
+                put_u64(out, *block_id);
+                put_u64(out, *target);
+                put_u64(out, *ticket);
+            }
+            Message::QueryToken {
+                token_id,
+                target,
+                ticket,
+            } => {
+                out.push(MSG_QUERY_TOKEN);
+                put_u64(out, *token_id);
+                put_u64(out, *target);
+                put_u64(out, *ticket);
+            }
+            Message::RequestBatch { items } => {
+                out.push(MSG_REQUEST_BATCH);
+                put_u32(out, items.len() as u32);
+                for item in items {
+                    put_batch_item(out, item);
+                }
+            }
+            Message::Answer {
+                answer,
+                signature,
+                head_of_chain,
+            } => {
+                out.push(MSG_ANSWER);
+                put_mapping(out, answer);
+                out.push(TOKENS_SIGNATURE_SIZE as u8);
+                for mapping in signature {
+                    put_mapping(out, mapping);
+                }
+                put_u64(out, *head_of_chain);
+            }
+            Message::Block { block } => {
+                out.push(MSG_BLOCK);
+                put_block(out, block);
+            }
+            Message::Referral { token, high, low } => {
+                out.push(MSG_REFERRAL);
+                put_u64(out, *token);
+                put_u64(out, *high);
+                put_u64(out, *low);
+            }
+            Message::PeerList { peers } => {
+                out.push(MSG_PEER_LIST);
+                put_u32(out, peers.len() as u32);
+                for peer in peers {
+                    put_u64(out, *peer);
+                }
+            }
+            Message::QueryCommitBlock { block_id, ticket } => {
+                out.push(MSG_QUERY_COMMIT_BLOCK);
+                put_u64(out, *block_id);
+                put_u64(out, *ticket);
+            }
+            Message::CommitBlock { block } => {
+                out.push(MSG_COMMIT_BLOCK);
+                put_commit_block(out, block);
+            }
+            Message::QueryBlocks { block_ids, ticket } => {
+                out.push(MSG_QUERY_BLOCKS);
+                put_u32(out, block_ids.len() as u32);
+                for block_id in block_ids {
+                    put_u64(out, *block_id);
+                }
+                put_u64(out, *ticket);
+            }
+            Message::Blocks { blocks } => {
+                out.push(MSG_BLOCKS);
+                put_u32(out, blocks.len() as u32);
+                for block in blocks {
+                    put_block(out, block);
+                }
+            }
+            Message::Keepalive { head_of_chain } => {
+                out.push(MSG_KEEPALIVE);
+                put_u64(out, *head_of_chain);
+            }
+            Message::CommitHead { head } => {
+                out.push(MSG_COMMIT_HEAD);
+                put_u64(out, *head);
+            }
+        }
+    }
+
+    /// Decode one message from the front of `bytes`
+    ///
+    /// Returns the message and the number of bytes consumed, so several
+    /// messages can be read back-to-back from one buffer.
+    pub fn decode(bytes: &[u8]) -> Result<(Message, usize), CodecError> {
+        let mut r = Reader { bytes, pos: 0 };
+
+        let version = r.u8()?;
+        if version != WIRE_VERSION {
+            return Err(CodecError::UnsupportedVersion(version));
+        }
+
+        let message = match r.u8()? {
+            MSG_INITIAL_VOTE => Message::InitialVote {
+                block: r.block()?,
+                vote: r.u8()?,
+            },
+            MSG_VOTE => Message::Vote {
+                block_id: r.u64()?,
+                vote: r.u8()?,
+                reply: r.bool()?,
+            },
+            MSG_QUERY_BLOCK => Message::QueryBlock {
+                block_id: r.u64()?,
+                target: r.u64()?,
+                ticket: r.u64()?,
+            },
+            MSG_QUERY_TOKEN => Message::QueryToken {
+                token_id: r.u64()?,
+                target: r.u64()?,
+                ticket: r.u64()?,
+            },
+            MSG_REQUEST_BATCH => {
+                let count = r.u32()? as usize;
+                // Don't trust the count for preallocation; each item is at least 2 bytes
+                let mut items = Vec::with_capacity(count.min(r.remaining() / 2));
+                for _ in 0..count {
+                    items.push(r.batch_item()?);
+                }
+                Message::RequestBatch { items }
+            }
+            MSG_ANSWER => {
+                let answer = r.mapping()?;
+                r.array_len(TOKENS_SIGNATURE_SIZE)?;
+                let mut signature = [TokenMapping { id: 0, block: 0 }; TOKENS_SIGNATURE_SIZE];
+                for mapping in signature.iter_mut() {
+                    *mapping = r.mapping()?;
+                }
+                Message::Answer {
+                    answer,
+                    signature,
+                    head_of_chain: r.u64()?,
+                }
+            }
+            MSG_BLOCK => Message::Block { block: r.block()? },
+            MSG_REFERRAL => Message::Referral {
+                token: r.u64()?,
+                high: r.u64()?,
+                low: r.u64()?,
+            },
+            MSG_PEER_LIST => Message::PeerList {
+                peers: r.u64_list()?,
+            },
+            MSG_QUERY_COMMIT_BLOCK => Message::QueryCommitBlock {
+                block_id: r.u64()?,
+                ticket: r.u64()?,
+            },
+            MSG_COMMIT_BLOCK => Message::CommitBlock {
+                block: r.commit_block()?,
+            },
+            MSG_QUERY_BLOCKS => Message::QueryBlocks {
+                block_ids: r.u64_list()?,
+                ticket: r.u64()?,
+            },
+            MSG_BLOCKS => {
+                let count = r.u32()? as usize;
+                // Don't trust the count either; a block has at least 19 fixed bytes
+                let mut blocks = Vec::with_capacity(count.min(r.remaining() / 19));
+                for _ in 0..count {
+                    blocks.push(r.block()?);
+                }
+                Message::Blocks { blocks }
+            }
+            MSG_KEEPALIVE => Message::Keepalive {
+                head_of_chain: r.u64()?,
+            },
+            MSG_COMMIT_HEAD => Message::CommitHead { head: r.u64()? },
+            other => return Err(CodecError::UnknownMessage(other)),
+        };
+
+        Ok((message, r.pos))
+    }
+}
+
+impl MessageEnvelope {
+    /// Write this envelope as one length-prefixed, checksummed frame
+    pub fn write_framed<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        let mut body = Vec::new();
+        put_u64(&mut body, self.sender);
+        put_u64(&mut body, self.receiver);
+        put_u64(&mut body, self.ticket);
+        put_u64(&mut body, self.time);
+        self.message.encode(&mut body);
+
+        if body.len() > MAX_FRAME_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "envelope exceeds MAX_FRAME_LEN",
+            ));
+        }
+
+        w.write_all(&(body.len() as u32).to_be_bytes())?;
+        w.write_all(&body)?;
+        w.write_all(&crc32(&body).to_be_bytes())
+    }
+
+    /// Read one frame written by `write_framed`
+    ///
+    /// Checksum mismatches, oversized frames and undecodable envelopes are
+    /// reported as `InvalidData`.
+    pub fn read_framed<R: Read>(r: &mut R) -> io::Result<MessageEnvelope> {
+        let mut len = [0u8; 4];
+        r.read_exact(&mut len)?;
+        let len = u32::from_be_bytes(len) as usize;
+        if len > MAX_FRAME_LEN {
+            return Err(invalid_data("frame length exceeds MAX_FRAME_LEN"));
+        }
+
+        let mut body = vec![0u8; len];
+        r.read_exact(&mut body)?;
+        let mut checksum = [0u8; 4];
+        r.read_exact(&mut checksum)?;
+        if u32::from_be_bytes(checksum) != crc32(&body) {
+            return Err(invalid_data("frame checksum mismatch"));
+        }
+
+        let mut reader = Reader {
+            bytes: &body,
+            pos: 0,
+        };
+        let header =
+            (|| Ok::<_, CodecError>((reader.u64()?, reader.u64()?, reader.u64()?, reader.u64()?)))(
+            );
+        let (sender, receiver, ticket, time) =
+            header.map_err(|e| invalid_data(&format!("bad envelope header: {:?}", e)))?;
+
+        let (message, used) = Message::decode(&body[reader.pos..])
+            .map_err(|e| invalid_data(&format!("bad message: {:?}", e)))?;
+        if reader.pos + used != body.len() {
+            return Err(invalid_data("trailing bytes in frame"));
+        }
+
+        Ok(MessageEnvelope {
+            sender,
+            receiver,
+            ticket,
+            time,
+            message,
+        })
+    }
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+/// CRC-32 (IEEE 802.3, reflected, as used by zlib/Ethernet)
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+// ============================================================================
+// Encoding helpers
+// ============================================================================
+
+fn put_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u64(out: &mut Vec<u8>, value: u64) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_mapping(out: &mut Vec<u8>, mapping: &TokenMapping) {
+    put_u64(out, mapping.id);
+    put_u64(out, mapping.block);
+}
+
+fn put_block(out: &mut Vec<u8>, block: &Block) {
+    put_u64(out, block.id);
+    put_u64(out, block.time);
+    out.push(block.used);
+    out.push(TOKENS_PER_BLOCK as u8);
+    for part in &block.parts {
+        put_u64(out, part.token);
+        put_u64(out, part.last);
+        put_u64(out, part.key);
+    }
+    out.push(TOKENS_PER_BLOCK as u8);
+    for signature in &block.signatures {
+        match signature {
+            Some(value) => {
+                out.push(1);
+                put_u64(out, *value);
+            }
+            None => out.push(0),
+        }
+    }
+}
+
+fn put_commit_block(out: &mut Vec<u8>, block: &CommitBlock) {
+    put_u64(out, block.id);
+    put_u64(out, block.previous);
+    put_u64(out, block.time);
+    put_u32(out, block.committed_blocks.len() as u32);
+    for block_id in &block.committed_blocks {
+        put_u64(out, *block_id);
+    }
+}
+
+fn put_batch_item(out: &mut Vec<u8>, item: &BatchRequestItem) {
+    match item {
+        BatchRequestItem::Vote {
+            block_id,
+            vote,
+            reply,
+        } => {
+            out.push(ITEM_VOTE);
+            put_u64(out, *block_id);
+            out.push(*vote);
+            out.push(*reply as u8);
+        }
+        BatchRequestItem::QueryBlock {
+            block_id,
+            target,
+            ticket,
+        } => {
+            out.push(ITEM_QUERY_BLOCK);
+            put_u64(out, *block_id);
+            put_u64(out, *target);
+            put_u64(out, *ticket);
+        }
+        BatchRequestItem::QueryToken {
+            token_id,
+            target,
+            ticket,
+        } => {
+            out.push(ITEM_QUERY_TOKEN);
+            put_u64(out, *token_id);
+            put_u64(out, *target);
+            put_u64(out, *ticket);
+        }
+    }
+}
+
+// ============================================================================
+// Decoding
+// ============================================================================
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.pos
+    }
+
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], CodecError> {
+        let end = self.pos + N;
+        let slice = self
+            .bytes
+            .get(self.pos..end)
+            .ok_or(CodecError::UnexpectedEnd)?;
+        self.pos = end;
+        Ok(slice.try_into().unwrap())
+    }
+
+    fn u8(&mut self) -> Result<u8, CodecError> {
+        Ok(self.take::<1>()?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, CodecError> {
+        Ok(u32::from_le_bytes(self.take()?))
+    }
+
+    fn u64(&mut self) -> Result<u64, CodecError> {
+        Ok(u64::from_le_bytes(self.take()?))
+    }
+
+    fn bool(&mut self) -> Result<bool, CodecError> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            other => Err(CodecError::InvalidFlag(other)),
+        }
+    }
+
+    fn array_len(&mut self, expected: usize) -> Result<(), CodecError> {
+        let found = self.u8()? as usize;
+        if found != expected {
+            return Err(CodecError::ArrayLength { expected, found });
+        }
+        Ok(())
+    }
+
+    fn u64_list(&mut self) -> Result<Vec<u64>, CodecError> {
+        let count = self.u32()? as usize;
+        if count > self.remaining() / 8 {
+            return Err(CodecError::UnexpectedEnd);
+        }
+        (0..count).map(|_| self.u64()).collect()
+    }
+
+    fn mapping(&mut self) -> Result<TokenMapping, CodecError> {
+        Ok(TokenMapping {
+            id: self.u64()?,
+            block: self.u64()?,
+        })
+    }
+
+    fn block(&mut self) -> Result<Block, CodecError> {
+        let id = self.u64()?;
+        let time = self.u64()?;
+        let used = self.u8()?;
+
+        self.array_len(TOKENS_PER_BLOCK)?;
+        let mut parts = [TokenBlock::default(); TOKENS_PER_BLOCK];
+        for part in parts.iter_mut() {
+            *part = TokenBlock {
+                token: self.u64()?,
+                last: self.u64()?,
+                key: self.u64()?,
+            };
+        }
+
+        self.array_len(TOKENS_PER_BLOCK)?;
+        let mut signatures = [None; TOKENS_PER_BLOCK];
+        for signature in signatures.iter_mut() {
+            if self.bool()? {
+                *signature = Some(self.u64()?);
+            }
+        }
+
+        Ok(Block {
+            id,
+            time,
+            used,
+            parts,
+            signatures,
+        })
+    }
+
+    fn commit_block(&mut self) -> Result<CommitBlock, CodecError> {
+        let id = self.u64()?;
+        let previous = self.u64()?;
+        let time = self.u64()?;
+        let committed_blocks = self.u64_list()?;
+        Ok(CommitBlock::new(id, previous, time, committed_blocks))
+    }
+
+    fn batch_item(&mut self) -> Result<BatchRequestItem, CodecError> {
+        match self.u8()? {
+            ITEM_VOTE => Ok(BatchRequestItem::Vote {
+                block_id: self.u64()?,
+                vote: self.u8()?,
+                reply: self.bool()?,
+            }),
+            ITEM_QUERY_BLOCK => Ok(BatchRequestItem::QueryBlock {
+                block_id: self.u64()?,
+                target: self.u64()?,
+                ticket: self.u64()?,
+            }),
+            ITEM_QUERY_TOKEN => Ok(BatchRequestItem::QueryToken {
+                token_id: self.u64()?,
+                target: self.u64()?,
+                ticket: self.u64()?,
+            }),
+            other => Err(CodecError::UnknownBatchItem(other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ec_interface::GENESIS_BLOCK_ID;
+
+    fn sample_block(id: u64) -> Block {
+        let mut block = Block {
+            id,
+            time: u64::MAX,
+            used: 2,
+            parts: [TokenBlock::default(); TOKENS_PER_BLOCK],
+            signatures: [None; TOKENS_PER_BLOCK],
+        };
+        block.parts[0] = TokenBlock {
+            token: 1,
+            last: GENESIS_BLOCK_ID,
+            key: u64::MAX,
+        };
+        block.parts[1] = TokenBlock {
+            token: 2,
+            last: 3,
+            key: 4,
+        };
+        block.signatures[0] = Some(0);
+        block.signatures[1] = Some(u64::MAX);
+        block
+    }
+
+    fn all_variants() -> Vec<Message> {
+        let mut signature = [TokenMapping { id: 0, block: 0 }; TOKENS_SIGNATURE_SIZE];
+        signature[TOKENS_SIGNATURE_SIZE - 1] = TokenMapping {
+            id: u64::MAX,
+            block: 1,
+        };
+        vec![
+            Message::InitialVote {
+                block: sample_block(7),
+                vote: 0b11_1111,
+            },
+            Message::Vote {
+                block_id: 0,
+                vote: 0,
+                reply: false,
+            },
+            Message::Vote {
+                block_id: u64::MAX,
+                vote: u8::MAX,
+                reply: true,
+            },
+            Message::QueryBlock {
+                block_id: 9,
+                target: 0,
+                ticket: 0,
+            },
+            Message::QueryToken {
+                token_id: u64::MAX,
+                target: 5,
+                ticket: u64::MAX,
+            },
+            Message::RequestBatch { items: vec![] },
+            Message::RequestBatch {
+                items: vec![
+                    BatchRequestItem::Vote {
+                        block_id: 1,
+                        vote: 2,
+                        reply: true,
+                    },
+                    BatchRequestItem::QueryBlock {
+                        block_id: 3,
+                        target: 4,
+                        ticket: 0,
+                    },
+                    BatchRequestItem::QueryToken {
+                        token_id: 6,
+                        target: 0,
+                        ticket: u64::MAX,
+                    },
+                ],
+            },
+            Message::Answer {
+                answer: TokenMapping { id: 5, block: 6 },
+                signature,
+                head_of_chain: 0,
+            },
+            Message::Block {
+                block: sample_block(u64::MAX),
+            },
+            Message::Referral {
+                token: 1,
+                high: u64::MAX,
+                low: 0,
+            },
+            Message::PeerList { peers: vec![] },
+            Message::PeerList {
+                peers: vec![0, 1, u64::MAX],
+            },
+            Message::QueryCommitBlock {
+                block_id: GENESIS_BLOCK_ID,
+                ticket: 0,
+            },
+            Message::CommitBlock {
+                block: CommitBlock::new(1, GENESIS_BLOCK_ID, 2, vec![]),
+            },
+            Message::CommitBlock {
+                block: CommitBlock::new(u64::MAX, 3, u64::MAX, vec![4, 5, 6]),
+            },
+            Message::QueryBlocks {
+                block_ids: vec![],
+                ticket: 0,
+            },
+            Message::QueryBlocks {
+                block_ids: vec![1, 2, u64::MAX],
+                ticket: u64::MAX,
+            },
+            Message::Blocks { blocks: vec![] },
+            Message::Blocks {
+                blocks: vec![sample_block(7), sample_block(u64::MAX)],
+            },
+            Message::Keepalive { head_of_chain: 0 },
+            Message::Keepalive {
+                head_of_chain: u64::MAX,
+            },
+            Message::CommitHead { head: 0 },
+            Message::CommitHead { head: u64::MAX },
+        ]
+    }
+
+    #[test]
+    fn test_every_variant_roundtrips() {
+        for message in all_variants() {
+            let mut bytes = Vec::new();
+            message.encode(&mut bytes);
+            assert_eq!(bytes[0], WIRE_VERSION);
+
+            let (decoded, used) = Message::decode(&bytes).unwrap();
+            assert_eq!(decoded, message);
+            assert_eq!(used, bytes.len());
+        }
+    }
+
+    #[test]
+    fn test_back_to_back_messages_decode_in_sequence() {
+        let messages = all_variants();
+        let mut bytes = Vec::new();
+        for message in &messages {
+            message.encode(&mut bytes);
+        }
+
+        let mut offset = 0;
+        for expected in &messages {
+            let (decoded, used) = Message::decode(&bytes[offset..]).unwrap();
+            assert_eq!(&decoded, expected);
+            offset += used;
+        }
+        assert_eq!(offset, bytes.len());
+    }
+
+    #[test]
+    fn test_integers_are_little_endian() {
+        let mut bytes = Vec::new();
+        Message::QueryCommitBlock {
+            block_id: 0x0102_0304_0506_0708,
+            ticket: 0,
+        }
+        .encode(&mut bytes);
+        assert_eq!(&bytes[..2], &[WIRE_VERSION, MSG_QUERY_COMMIT_BLOCK]);
+        assert_eq!(&bytes[2..10], &0x0102_0304_0506_0708u64.to_le_bytes());
+    }
+
+    #[test]
+    fn test_malformed_input_is_rejected() {
+        let mut bytes = Vec::new();
+        all_variants()[0].encode(&mut bytes);
+
+        assert_eq!(Message::decode(&[]), Err(CodecError::UnexpectedEnd));
+        for len in 1..bytes.len() {
+            assert_eq!(
+                Message::decode(&bytes[..len]),
+                Err(CodecError::UnexpectedEnd)
+            );
+        }
+
+        let mut wrong_version = bytes.clone();
+        wrong_version[0] = WIRE_VERSION + 1;
+        assert_eq!(
+            Message::decode(&wrong_version),
+            Err(CodecError::UnsupportedVersion(WIRE_VERSION + 1))
+        );
+
+        assert_eq!(
+            Message::decode(&[WIRE_VERSION, 200]),
+            Err(CodecError::UnknownMessage(200))
+        );
+
+        // InitialVote: version, tag, id, time, used, then the parts length prefix
+        let mut wrong_len = bytes.clone();
+        wrong_len[2 + 8 + 8 + 1] = 5;
+        assert_eq!(
+            Message::decode(&wrong_len),
+            Err(CodecError::ArrayLength {
+                expected: TOKENS_PER_BLOCK,
+                found: 5
+            })
+        );
+
+        let mut vote = Vec::new();
+        Message::Vote {
+            block_id: 1,
+            vote: 1,
+            reply: true,
+        }
+        .encode(&mut vote);
+        *vote.last_mut().unwrap() = 2;
+        assert_eq!(Message::decode(&vote), Err(CodecError::InvalidFlag(2)));
+
+        // Answer from a build with 10-mapping signatures: version, tag, answer, then the prefix
+        let mut answer = Vec::new();
+        Message::Answer {
+            answer: TokenMapping { id: 1, block: 2 },
+            signature: [TokenMapping { id: 0, block: 0 }; TOKENS_SIGNATURE_SIZE],
+            head_of_chain: 0,
+        }
+        .encode(&mut answer);
+        answer[2 + 16] = 10;
+        assert_eq!(
+            Message::decode(&answer),
+            Err(CodecError::ArrayLength {
+                expected: TOKENS_SIGNATURE_SIZE,
+                found: 10
+            })
+        );
+    }
+
+    fn envelope(sender: u64, message: Message) -> MessageEnvelope {
+        MessageEnvelope {
+            sender,
+            receiver: sender + 1,
+            ticket: 0xfeed,
+            time: 42,
+            message,
+        }
+    }
+
+    #[test]
+    fn test_crc32_known_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn test_framed_envelopes_read_back_in_order() {
+        let first = envelope(
+            1,
+            Message::QueryToken {
+                token_id: 7,
+                target: 0,
+                ticket: 9,
+            },
+        );
+        let second = envelope(
+            2,
+            Message::Block {
+                block: sample_block(3),
+            },
+        );
+
+        let mut stream = Vec::new();
+        first.write_framed(&mut stream).unwrap();
+        second.write_framed(&mut stream).unwrap();
+
+        let mut cursor = std::io::Cursor::new(stream);
+        assert_eq!(MessageEnvelope::read_framed(&mut cursor).unwrap(), first);
+        assert_eq!(MessageEnvelope::read_framed(&mut cursor).unwrap(), second);
+        let end = MessageEnvelope::read_framed(&mut cursor).unwrap_err();
+        assert_eq!(end.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_corrupted_frame_is_rejected() {
+        let mut stream = Vec::new();
+        envelope(5, Message::PeerList { peers: vec![1, 2] })
+            .write_framed(&mut stream)
+            .unwrap();
+
+        // Flip a bit in the body (after the 4-byte length)
+        let mut corrupted = stream.clone();
+        corrupted[4 + 8] ^= 0x01;
+        let err = MessageEnvelope::read_framed(&mut corrupted.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        // Or in the checksum itself
+        let mut corrupted = stream.clone();
+        *corrupted.last_mut().unwrap() ^= 0x80;
+        let err = MessageEnvelope::read_framed(&mut corrupted.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        // Oversized length prefix is refused before allocating
+        let mut oversized = stream;
+        oversized[..4].copy_from_slice(&u32::MAX.to_be_bytes());
+        let err = MessageEnvelope::read_framed(&mut oversized.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -19,6 +19,10 @@
 //! 3. Route MessageEnvelope between nodes via your network
 //! 4. Call `node.tick()` and `node.handle_message()` as messages arrive
 //!
+//! `ec_transport::Transport` is the trait to implement for step 1. With the
+//! `tokio-transport` feature, `EcNode::run` drives steps 3-4 over any transport
+//! and `ec_transport::TokioTcpTransport` is a reference TCP implementation.
+//!
 //! ```no_run
 //! use ec_rust::{EcNode, ec_memory_backend::MemoryBackend};
 //! use std::rc::Rc;
@@ -37,7 +41,8 @@
 //! let mut node = EcNode::new(backend, peer_id, 0, token_storage, rng);
 //!
 //! // In your network event loop:
-//! // - Call node.tick(&mut outgoing_messages) periodically
+//! // - Call node.tick(&mut outgoing_messages) periodically (each call advances
+//! //   time by one, unless a clock is set with node.set_time_source)
 //! // - Call node.handle_message(&incoming_msg, &mut outgoing_messages) for each message
 //! // - Send outgoing_messages via your network layer
 //! ```
@@ -48,9 +53,6 @@
 //! `simulator` crate in `src/simulator/`. It provides a configurable simulation
 //! framework for protocol validation and performance analysis.
 
-// Silence warning for rocksdb-backend feature not yet configured in Cargo.toml
-#![allow(unexpected_cfgs)]
-
 // Core consensus modules
 pub mod ec_commit_chain;
 pub mod ec_genesis;
@@ -62,6 +64,8 @@ pub mod ec_peer_lifecycle_v2;
 pub mod ec_peers;
 pub mod ec_proof_of_storage;
 pub mod ec_ticket_manager;
+pub mod ec_transport;
+pub mod ec_wire;
 
 // Storage backends
 pub mod ec_memory_backend;
@@ -71,13 +75,14 @@ pub mod ec_rocksdb_backend;
 
 // Re-export commonly used types
 pub use ec_interface::{
-    Block, BlockId, EcBlocks, EcTime, EcTokens, Event, EventSink, Message, MessageEnvelope,
-    NoOpSink, PeerId, TokenId,
+    Block, BlockId, EcBlocks, EcTime, EcTokens, Event, EventSink, ManualTimeSource, Message,
+    MessageEnvelope, MonotonicTimeSource, NoOpSink, PeerId, TimeSource, TokenId,
 };
 pub use ec_node::EcNode;
 // Public API for peer elections (used by clients to evaluate and discover peers)
 pub use ec_proof_of_storage::{
-    ring_distance, ConsensusCluster, ElectionConfig, ElectionError, PeerElection, WinnerResult,
+    ring_distance, ring_distance_generic, ConsensusCluster, ElectionConfig, ElectionError,
+    PeerElection, RingDistanceSelector, RingId, WinnerResult, WinnerSelector,
 };
 // Public API for peer identity generation and validation
 pub use ec_identity::{AddressConfig, PeerIdentity, Salt, SharedSecret};